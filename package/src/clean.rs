@@ -0,0 +1,80 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use termcolor::Color;
+use walkdir::WalkDir;
+
+use crate::utils::fs::{canonicalize, dev_cache_dir, remove_dir};
+use crate::{build_step, log};
+
+// N.B.: These are the dev cache subtrees the build and the integration tests populate.
+const CACHE_SUBTREES: [&str; 3] = ["downloads", "clones", "venvs"];
+
+pub(crate) fn clean(dry_run: bool) -> Result<()> {
+    let cache_root = canonicalize(&dev_cache_dir()?)?;
+    build_step!(
+        "{action} the dev cache at {cache_root}",
+        action = if dry_run { "Sizing" } else { "Cleaning" },
+        cache_root = cache_root.display()
+    );
+
+    let mut total_size = 0;
+    for subtree in CACHE_SUBTREES {
+        let path = cache_root.join(subtree);
+        if path.symlink_metadata().is_err() {
+            continue;
+        }
+        // Guard against deleting anything outside the cache, say via a symlinked subtree.
+        let resolved_path = canonicalize(&path)?;
+        if !resolved_path.starts_with(&cache_root) || resolved_path == cache_root {
+            bail!(
+                "Refusing to remove {path} since it resolves to {resolved_path} which is not \
+                inside the dev cache at {cache_root}.",
+                path = path.display(),
+                resolved_path = resolved_path.display(),
+                cache_root = cache_root.display()
+            );
+        }
+
+        let size = disk_usage(&resolved_path)?;
+        total_size += size;
+        if dry_run {
+            log!(
+                Color::Yellow,
+                "Would remove {path}: {size} bytes",
+                path = path.display()
+            );
+        } else {
+            remove_dir(&resolved_path)?;
+            log!(
+                Color::Yellow,
+                "Removed {path}: {size} bytes",
+                path = path.display()
+            );
+        }
+    }
+    log!(
+        Color::Yellow,
+        "{action} {total_size} bytes in total.",
+        action = if dry_run { "Would free" } else { "Freed" }
+    );
+    Ok(())
+}
+
+fn disk_usage(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in WalkDir::new(path) {
+        let entry =
+            entry.with_context(|| format!("Failed to walk {path}", path = path.display()))?;
+        if entry.file_type().is_file() {
+            size += entry
+                .metadata()
+                .with_context(|| format!("Failed to stat {path}", path = entry.path().display()))?
+                .len();
+        }
+    }
+    Ok(size)
+}
@@ -1,7 +1,9 @@
 // Copyright 2022 Pants project contributors.
 // Licensed under the Apache License, Version 2.0 (see LICENSE).
 
+mod clean;
 mod scie_pants;
+mod signing;
 
 #[macro_use]
 mod test;
@@ -12,20 +14,25 @@ mod tools_pex;
 mod utils;
 
 use std::fmt::{Display, Formatter};
-use std::io::Write;
+use std::num::NonZeroUsize;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Result};
-use clap::{arg, command, Parser, Subcommand};
-use termcolor::{Color, WriteColor};
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{arg, command, Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use termcolor::Color;
 use utils::fs;
 
-use crate::scie_pants::{build_scie_pants_scie, SciePantsBuild};
-use crate::test::run_integration_tests;
-use crate::tools_pex::build_tools_pex;
-use crate::utils::build::{check_sha256, fetch_science, BuildContext};
-use crate::utils::fs::{base_name, canonicalize, copy, ensure_directory};
+use crate::clean::clean;
+use crate::scie_pants::{build_scie_pants_scie, Packaging, SciePantsBuild};
+use crate::signing::{UpdatePublicKey, UpdateSigner};
+use crate::test::{keep_tempdirs_on_failure, run_integration_tests, verify_scie};
+use crate::tools_pex::{build_tools_pex, ToolsPexOptions, DEFAULT_TOOLS_PYTHON_CONSTRAINT};
+use crate::utils::build::{check_sha256, fetch_science, fingerprint, BuildContext};
+use crate::utils::exe::{set_print_command, set_verbose, CURRENT_PLATFORM};
+use crate::utils::fs::{base_name, canonicalize, copy, ensure_writable_directory};
+use crate::utils::logging::{clear_build_task, set_build_task, suppress_logging};
 
 const BINARY: &str = "scie-pants";
 
@@ -67,12 +74,82 @@ impl Display for SpecifiedPath {
     }
 }
 
+fn parse_jobs(jobs: &str) -> Result<NonZeroUsize, String> {
+    jobs.parse::<NonZeroUsize>()
+        .map_err(|_| format!("Expected a positive number of jobs, got: {jobs}"))
+}
+
+fn parse_tools_python_constraint(constraint: &str) -> Result<String, String> {
+    if constraint.trim().is_empty() {
+        Err("Expected a non-empty interpreter constraint, e.g.: CPython>=3.8,<3.13".to_string())
+    } else {
+        Ok(constraint.to_string())
+    }
+}
+
+fn is_release_tag(tag: &str) -> bool {
+    tag.strip_prefix('v').is_some_and(|version| {
+        let components = version.split('.').collect::<Vec<_>>();
+        components.len() == 3
+            && components.iter().all(|component| {
+                !component.is_empty() && component.chars().all(|char| char.is_ascii_digit())
+            })
+    })
+}
+
+fn parse_science_tag(tag: &str) -> Result<String, String> {
+    if is_release_tag(tag) {
+        Ok(tag.to_string())
+    } else {
+        Err(format!(
+            "Expected a science release tag of the form vX.Y.Z, e.g.: {SCIENCE_TAG}, got: {tag}"
+        ))
+    }
+}
+
+fn parse_ptex_tag(tag: &str) -> Result<String, String> {
+    if is_release_tag(tag) {
+        Ok(tag.to_string())
+    } else {
+        Err(format!(
+            "Expected a ptex release tag of the form vX.Y.Z, e.g.: v0.7.0, got: {tag}"
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, ValueEnum)]
+enum OutputFormat {
+    /// Log the build steps and the scie written in color to STDERR.
+    #[default]
+    Text,
+    /// Print a JSON report of the scie written to STDOUT and log nothing else.
+    Json,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Builds the `tools.pex` used by the scie-pants scie to perform Pants installs.
     Tools,
+    /// Removes the downloads, clones and venvs cached across builds and integration test runs.
+    Clean {
+        #[arg(
+            long,
+            help = "List the cached directories that would be removed and their sizes instead of \
+            removing them.",
+            default_value_t = false
+        )]
+        dry_run: bool,
+    },
     /// Builds the `scie-pants` Rust binary.
-    SciePants,
+    SciePants {
+        #[arg(
+            long,
+            help = "Build for release: the `scie-pants` binary must embed the update public key \
+            from SCIE_PANTS_UPDATE_PUBLIC_KEY.",
+            default_value_t = false
+        )]
+        release: bool,
+    },
     /// Builds the `scie-pants` scie.
     Scie {
         #[arg(
@@ -87,6 +164,24 @@ enum Commands {
             built fresh."
         )]
         tools_pex: Option<PathBuf>,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = Packaging::Lazy,
+            help = "How to package the CPython interpreters in the scie. A lazy scie is small but \
+            fetches an interpreter the first time it installs a given Pants version. An eager scie \
+            embeds all the interpreters up front; so it is much larger but can install Pants \
+            without network access to the interpreter download URLs."
+        )]
+        packaging: Packaging,
+        #[arg(
+            long,
+            help = "Build for release: the `scie-pants` binary must embed the update public key \
+            from SCIE_PANTS_UPDATE_PUBLIC_KEY and the scie is signed with the matching private \
+            key from SCIE_PANTS_UPDATE_SIGNING_KEY into a `.sig` file beside it.",
+            default_value_t = false
+        )]
+        release: bool,
     },
     /// Builds the `scie-pants` scie and runs it through a series of integration tests.
     Test {
@@ -116,6 +211,32 @@ enum Commands {
             default_value_t = false
         )]
         tools_pex_mismatch_warn: bool,
+        #[arg(
+            long,
+            help = "A directory holding a pre-populated Pants clone and venv for the Pants from \
+            sources tests to use instead of cloning Pants into the dev cache. See CONTRIBUTING.md \
+            for its layout."
+        )]
+        pants_clone_cache: Option<PathBuf>,
+        #[arg(
+            long,
+            help = "Only run the named integration test; e.g.: `--only test_pants_bootstrap_urls`. \
+            Can be repeated to run several tests."
+        )]
+        only: Vec<String>,
+        #[arg(
+            long,
+            help = "Keep the temporary directories of a failing integration test and print their \
+            paths instead of deleting them. This is for debugging only: the directories are never \
+            cleaned up and can hold Pants clones and venvs; so they can use a lot of disk.",
+            default_value_t = false
+        )]
+        keep_temp_on_failure: bool,
+    },
+    /// Re-checks the sha256 fingerprint of a built `scie-pants` scie and smoke tests it.
+    Verify {
+        #[arg(help = "The scie-pants scie to verify; its `.sha256` file must sit beside it.")]
+        scie: PathBuf,
     },
 }
 
@@ -132,93 +253,281 @@ struct Args {
         )
     )]
     science: Option<PathBuf>,
+    #[arg(
+        long,
+        conflicts_with = "science",
+        value_parser = parse_science_tag,
+        help = format!(
+            "Use this released science tag, e.g.: v0.3.2, instead of the default {SCIENCE_TAG}."
+        )
+    )]
+    science_tag: Option<String>,
+    #[arg(
+        long,
+        value_parser = parse_ptex_tag,
+        help = "Use this released ptex tag, e.g.: v0.7.1, instead of the ptex version pinned in the \
+        lift manifests of the scie-pants scie and the pbt helper binary."
+    )]
+    ptex_tag: Option<String>,
+    #[arg(
+        long,
+        help = "Build the `scie-pants` scie from this lift manifest instead of \
+        package/scie-pants.toml; e.g.: to try out manifest changes side-by-side."
+    )]
+    scie_manifest: Option<PathBuf>,
     #[arg(
         long,
         help = "Refresh the tools lock before building the tools.pex",
         default_value_t = false
     )]
     update_lock: bool,
+    #[arg(
+        long,
+        conflicts_with_all = ["update_lock", "tools_lock"],
+        help = "Only update this project in the tools lock, leaving the other pins alone where \
+        possible, before building the tools.pex; e.g.: `--update-lock-only cowsay`. Can be \
+        repeated to update several projects."
+    )]
+    update_lock_only: Vec<String>,
+    #[arg(
+        long,
+        conflicts_with = "update_lock",
+        help = "Build the tools.pex against this lock file instead of tools/lock.json; e.g.: to \
+        reproduce a tools.pex built from an older lock."
+    )]
+    tools_lock: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "Record the fingerprint of the tools.pex built as the expected fingerprint in \
+        tools/tools.pex.sha256 instead of verifying the tools.pex against it.",
+        default_value_t = false
+    )]
+    update_tools_pex_fingerprint: bool,
+    #[arg(
+        long,
+        value_parser = parse_jobs,
+        help = "The number of parallel jobs Pex uses to resolve the tools lock and build the \
+        tools.pex. Defaults to Pex's own default."
+    )]
+    jobs: Option<NonZeroUsize>,
+    #[arg(
+        long,
+        value_parser = parse_tools_python_constraint,
+        help = "The Python interpreter constraint to resolve the tools lock and build the \
+        tools.pex for.",
+        default_value = DEFAULT_TOOLS_PYTHON_CONSTRAINT
+    )]
+    tools_python_constraint: String,
+    #[arg(
+        long,
+        help = "Build the `scie-pants` Rust binary even if one was previously built from the same \
+        sources.",
+        default_value_t = false
+    )]
+    force_scie_pants: bool,
     #[arg(
         long,
         help = "The destination directory for the chosen binary and its checksum file.",
         default_value_t = SpecifiedPath::new("dist")
     )]
     dest_dir: SpecifiedPath,
+    #[arg(
+        long,
+        help = "Report the full command line and output of failed build commands instead of just \
+        the tail of their STDERR. Also enabled by RUST_LOG=debug.",
+        default_value_t = false
+    )]
+    verbose: bool,
+    #[arg(
+        long,
+        help = "Print the science and Pex commands the build runs, with the directory they run in, \
+        before running them; so they can be copy-pasted to reproduce a failure by hand.",
+        default_value_t = false
+    )]
+    print_command: bool,
+    #[arg(
+        long,
+        help = "Fail instead of downloading build tools, like science, that aren't already in the \
+        dev cache.",
+        default_value_t = false
+    )]
+    offline: bool,
+    #[arg(
+        long,
+        help = "Build the `scie-pants` Rust binary such that clean builds of the same sources on \
+        different machines produce identical binaries.",
+        default_value_t = false
+    )]
+    reproducible: bool,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        help = "How to report the scie-pants scie built."
+    )]
+    output: OutputFormat,
     #[command(subcommand)]
     command: Commands,
 }
 
+impl Args {
+    fn tools_pex_options(&self) -> ToolsPexOptions {
+        ToolsPexOptions {
+            update_lock: self.update_lock,
+            update_lock_only: self.update_lock_only.clone(),
+            tools_lock: self.tools_lock.clone(),
+            update_fingerprint: self.update_tools_pex_fingerprint,
+            jobs: self.jobs,
+            python_constraint: self.tools_python_constraint.clone(),
+        }
+    }
+}
+
 fn maybe_build_components(
+    args: &Args,
     build_context: &BuildContext,
     scie_pants_exe: &Option<PathBuf>,
     tools_pex_file: &Option<PathBuf>,
-    update_lock: bool,
-    dest_dir: &Path,
+    packaging: Packaging,
+    update_public_key: Option<&UpdatePublicKey>,
 ) -> Result<(SciePantsBuild, PathBuf)> {
-    let scie_pants_exe = if let Some(scie_pants_exe) = scie_pants_exe.to_owned() {
-        scie_pants_exe
-    } else {
-        build_context.build_scie_pants()?
-    };
-    let science = fetch_science(build_context)?;
-    let tools_pex_file = if let Some(tools_pex_file) = tools_pex_file.to_owned() {
-        tools_pex_file
-    } else {
-        build_tools_pex(build_context, &science, update_lock, dest_dir)?
-    };
-    let scie_pants_build =
-        build_scie_pants_scie(build_context, &science, &scie_pants_exe, &tools_pex_file)?;
+    // N.B.: The scie-pants Rust binary and the tools.pex are independent of each other until we
+    // lift them into the scie; so we build them concurrently.
+    let (scie_pants_exe, (science, tools_pex_file)) = std::thread::scope(|scope| {
+        let scie_pants_task = scope.spawn(|| {
+            set_build_task("scie-pants");
+            if let Some(scie_pants_exe) = scie_pants_exe.to_owned() {
+                Ok(scie_pants_exe)
+            } else {
+                build_context.build_scie_pants(args.force_scie_pants, true)
+            }
+        });
+
+        set_build_task("tools.pex");
+        let tools_result = fetch_science(build_context).and_then(|science| {
+            let tools_pex_file = if let Some(tools_pex_file) = tools_pex_file.to_owned() {
+                tools_pex_file
+            } else {
+                build_tools_pex(
+                    build_context,
+                    &science,
+                    args.tools_pex_options(),
+                    args.dest_dir.as_path(),
+                )?
+            };
+            Ok((science, tools_pex_file))
+        });
+        let scie_pants_result = scie_pants_task
+            .join()
+            .map_err(|_| anyhow!("The scie-pants Rust binary build task panicked."))?;
+        clear_build_task();
+
+        Ok::<_, anyhow::Error>((
+            scie_pants_result.context("Failed to build the scie-pants Rust binary.")?,
+            tools_result.context("Failed to build the tools.pex.")?,
+        ))
+    })?;
+    if let Some(update_public_key) = update_public_key {
+        update_public_key.check_embedded_in(&scie_pants_exe)?;
+    }
+    let scie_pants_build = build_scie_pants_scie(
+        build_context,
+        &science,
+        &scie_pants_exe,
+        &tools_pex_file,
+        packaging,
+    )?;
     Ok((scie_pants_build, tools_pex_file))
 }
 
-fn maybe_build(args: &Args, build_context: &BuildContext) -> Result<Option<SciePantsBuild>> {
+fn maybe_build(
+    args: &Args,
+    build_context: &BuildContext,
+    update_public_key: Option<&UpdatePublicKey>,
+) -> Result<Option<(SciePantsBuild, PathBuf)>> {
     match &args.command {
         Commands::Test {
             tools_pex,
             scie_pants,
             check,
             tools_pex_mismatch_warn,
+            pants_clone_cache,
+            only,
+            keep_temp_on_failure,
         } => {
             let (scie_pants, tools_pex) = maybe_build_components(
+                args,
                 build_context,
                 scie_pants,
                 tools_pex,
-                args.update_lock,
-                args.dest_dir.as_path(),
+                Packaging::default(),
+                None,
             )?;
+            if build_context.is_cross_build() {
+                log!(
+                    Color::Yellow,
+                    "Skipping the integration tests since the scie-pants scie was built for \
+                    {target} which can't be run on this {host} host.",
+                    target = build_context.target_platform(),
+                    host = *CURRENT_PLATFORM
+                );
+                return Ok(Some((scie_pants, tools_pex)));
+            }
+            if *keep_temp_on_failure {
+                keep_tempdirs_on_failure();
+            }
             run_integration_tests(
                 &build_context.workspace_root,
                 &canonicalize(&tools_pex)?,
                 &canonicalize(&scie_pants.exe)?,
                 *check,
                 *tools_pex_mismatch_warn,
+                pants_clone_cache.as_deref(),
+                only,
             )?;
-            Ok(Some(scie_pants))
+            Ok(Some((scie_pants, tools_pex)))
         }
         Commands::Scie {
             scie_pants,
             tools_pex,
-        } => {
-            let (scie_pants, _) = maybe_build_components(
-                build_context,
-                scie_pants,
-                tools_pex,
-                args.update_lock,
-                args.dest_dir.as_path(),
-            )?;
-            Ok(Some(scie_pants))
-        }
-        Commands::SciePants => {
-            let scie_pants = build_context.build_scie_pants()?;
+            packaging,
+            ..
+        } => maybe_build_components(
+            args,
+            build_context,
+            scie_pants,
+            tools_pex,
+            *packaging,
+            update_public_key,
+        )
+        .map(Some),
+        Commands::SciePants { release } => {
+            let update_public_key = if *release {
+                Some(UpdatePublicKey::required()?)
+            } else {
+                None
+            };
+            let scie_pants = build_context.build_scie_pants(args.force_scie_pants, false)?;
+            if let Some(update_public_key) = update_public_key {
+                update_public_key.check_embedded_in(&scie_pants)?;
+            }
             copy(&scie_pants, &args.dest_dir.join(base_name(&scie_pants)?))?;
             Ok(None)
         }
+        Commands::Clean { dry_run } => {
+            clean(*dry_run)?;
+            Ok(None)
+        }
+        Commands::Verify { scie } => {
+            verify_scie(scie)?;
+            Ok(None)
+        }
         Commands::Tools => {
             let science = fetch_science(build_context)?;
             build_tools_pex(
                 build_context,
                 &science,
-                args.update_lock,
+                args.tools_pex_options(),
                 args.dest_dir.as_path(),
             )?;
             Ok(None)
@@ -230,6 +539,11 @@ fn main() -> Result<()> {
     pretty_env_logger::init();
 
     let args = Args::parse();
+    set_verbose(args.verbose);
+    set_print_command(args.print_command);
+    if args.output == OutputFormat::Json {
+        suppress_logging();
+    }
 
     let dest_dir = &args.dest_dir;
     if dest_dir.is_file() {
@@ -238,11 +552,33 @@ fn main() -> Result<()> {
             dest_dir = dest_dir.display()
         );
     }
+    ensure_writable_directory(dest_dir)?;
 
-    let build_context = BuildContext::new(args.target.as_deref(), args.science.as_deref())?;
-    if let Some(scie_pants) = maybe_build(&args, &build_context)? {
-        ensure_directory(dest_dir, false)?;
+    // N.B.: We check for the release keys up front to fail fast instead of after a long build.
+    let release_keys = if let Commands::Scie { release: true, .. } = args.command {
+        let update_public_key = UpdatePublicKey::required()?;
+        let update_signer = UpdateSigner::required(&update_public_key)?;
+        Some((update_public_key, update_signer))
+    } else {
+        None
+    };
 
+    let build_context = BuildContext::new(
+        args.target.as_deref(),
+        args.science.as_deref(),
+        args.science_tag.as_deref(),
+        args.ptex_tag.as_deref(),
+        args.scie_manifest.as_deref(),
+        args.offline,
+        args.reproducible,
+    )?;
+    if let Some((scie_pants, tools_pex)) = maybe_build(
+        &args,
+        &build_context,
+        release_keys
+            .as_ref()
+            .map(|(update_public_key, _)| update_public_key),
+    )? {
         let dest_file_name = fs::base_name(&scie_pants.exe)?;
         let dest_file = dest_dir.join(dest_file_name);
         copy(&scie_pants.exe, &dest_file)?;
@@ -252,12 +588,42 @@ fn main() -> Result<()> {
         )?;
 
         check_sha256(&dest_file)?;
+        let signature_file = release_keys
+            .as_ref()
+            .map(|(_, update_signer)| update_signer.sign(&dest_file))
+            .transpose()?;
 
-        log!(
-            Color::Yellow,
-            "Wrote {dest_file_name} to {dest_file}",
-            dest_file = dest_file.display()
-        );
+        match args.output {
+            OutputFormat::Text => {
+                log!(
+                    Color::Yellow,
+                    "Wrote {dest_file_name} to {dest_file}",
+                    dest_file = dest_file.display()
+                );
+                if let Some(signature_file) = &signature_file {
+                    log!(
+                        Color::Yellow,
+                        "Wrote its signature to {signature_file}",
+                        signature_file = signature_file.display()
+                    );
+                }
+            }
+            OutputFormat::Json => {
+                let report = json!({
+                    "scie": canonicalize(&dest_file)?,
+                    "sha256": fingerprint(&dest_file)?,
+                    "tools_pex": canonicalize(&tools_pex)?,
+                    "signature": signature_file.as_deref().map(canonicalize).transpose()?,
+                    "target": build_context.target(),
+                    "science_tag": build_context.science_tag(),
+                });
+                println!(
+                    "{report}",
+                    report = serde_json::to_string_pretty(&report)
+                        .context("Failed to serialize the build report.")?
+                );
+            }
+        }
     }
 
     Ok(())
@@ -1,13 +1,70 @@
-use std::io::Write;
+use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 
-use anyhow::Result;
-use termcolor::WriteColor;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use termcolor::Color;
 
 use crate::utils::build::{BuildContext, Science};
-use crate::utils::exe::{binary_full_name, execute};
+use crate::utils::exe::{binary_full_name_for, execute_streaming, print_command};
 use crate::utils::fs::{ensure_directory, path_as_str};
-use crate::{build_step, BINARY};
+use crate::{build_step, log, BINARY};
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub(crate) enum Packaging {
+    /// Fetch the CPython interpreters on first use; this keeps the scie small.
+    #[default]
+    Lazy,
+    /// Embed the CPython interpreters in the scie; this makes for a much larger scie that can
+    /// install Pants without fetching an interpreter.
+    Eager,
+}
+
+impl Display for Packaging {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Packaging::Lazy => "lazy",
+            Packaging::Eager => "eager",
+        })
+    }
+}
+
+/// Returns the ids of the `lazy = true` `[[lift.interpreters]]` in the given lift manifest.
+fn lazy_interpreter_ids(manifest: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(manifest)
+        .with_context(|| format!("Failed to read {path}", path = manifest.display()))?;
+    let lift_manifest = contents
+        .parse::<toml_edit::Document>()
+        .with_context(|| format!("Failed to parse {path}", path = manifest.display()))?;
+    let Some(interpreters) = lift_manifest
+        .get("lift")
+        .and_then(|lift| lift.get("interpreters"))
+        .and_then(toml_edit::Item::as_array_of_tables)
+    else {
+        return Ok(vec![]);
+    };
+    interpreters
+        .iter()
+        .filter(|interpreter| {
+            interpreter
+                .get("lazy")
+                .and_then(toml_edit::Item::as_bool)
+                .unwrap_or(false)
+        })
+        .map(|interpreter| {
+            interpreter
+                .get("id")
+                .and_then(toml_edit::Item::as_str)
+                .map(str::to_owned)
+                .with_context(|| {
+                    format!(
+                        "A lazy interpreter in {path} has no `id` to un-lazy it by.",
+                        path = manifest.display()
+                    )
+                })
+        })
+        .collect()
+}
 
 pub(crate) struct SciePantsBuild {
     pub(crate) exe: PathBuf,
@@ -19,26 +76,45 @@ pub(crate) fn build_scie_pants_scie(
     science: &Science,
     scie_pants_exe: &Path,
     tools_pex_file: &Path,
+    packaging: Packaging,
 ) -> Result<SciePantsBuild> {
     build_step!("Building the `scie-pants` scie");
 
     let scie_pants_package_dir = build_context.cargo_output_root.join("scie-pants");
     ensure_directory(&scie_pants_package_dir, true)?;
 
-    let scie_pants_manifest = build_context
-        .package_crate_root
-        .join("scie-pants.toml")
-        .strip_prefix(&build_context.workspace_root)?
-        .to_owned();
+    let scie_pants_manifest = build_context.lift_manifest(
+        build_context.scie_manifest(),
+        &build_context.cargo_output_root,
+    )?;
+    // N.B.: The manifest path is recorded in the scie's provenance; so we keep it relative to the
+    // workspace root when we can.
+    let scie_pants_manifest = scie_pants_manifest
+        .strip_prefix(&build_context.workspace_root)
+        .map(Path::to_path_buf)
+        .unwrap_or(scie_pants_manifest);
+
+    let mut command = science.command();
+    command.args(["lift", "--include-provenance"]);
+    if build_context.is_cross_build() {
+        command.args([
+            "--platform",
+            build_context.target_platform().scie_platform(),
+        ]);
+    }
+    if let Packaging::Eager = packaging {
+        for interpreter_id in
+            lazy_interpreter_ids(&build_context.workspace_root.join(&scie_pants_manifest))?
+        {
+            command.args(["--invert-lazy", &interpreter_id]);
+        }
+    }
 
     // N.B.: We name the scie-pants binary scie-pants.bin since the scie itself is named scie-pants
     // which would conflict when packaging.
-    execute(
-        science
-            .command()
+    execute_streaming(print_command(
+        command
             .args([
-                "lift",
-                "--include-provenance",
                 "--file",
                 &format!(
                     "scie-pants.bin={scie_pants_exe}",
@@ -58,10 +134,55 @@ pub(crate) fn build_scie_pants_scie(
                 path_as_str(&scie_pants_manifest)?,
             ])
             .current_dir(&build_context.workspace_root),
-    )?;
-    let exe_full_name = binary_full_name(BINARY);
+    )?)?;
+    let exe_full_name = binary_full_name_for(BINARY, build_context.target_platform());
+    let exe = scie_pants_package_dir.join(exe_full_name.clone());
+    let size = std::fs::metadata(&exe)
+        .with_context(|| format!("Failed to stat {exe}", exe = exe.display()))?
+        .len();
+    log!(
+        Color::Yellow,
+        "Built the {packaging} `scie-pants` scie: {size} bytes",
+    );
     Ok(SciePantsBuild {
-        exe: scie_pants_package_dir.join(exe_full_name.clone()),
+        exe,
         sha256: scie_pants_package_dir.join(format!("{exe_full_name}.sha256")),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::lazy_interpreter_ids;
+
+    #[test]
+    fn lazy_interpreters() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let manifest = tmpdir.path().join("lift.toml");
+        std::fs::write(
+            &manifest,
+            r#"
+[lift]
+name = "example"
+
+[[lift.interpreters]]
+id = "cpython38"
+lazy = true
+
+[[lift.interpreters]]
+id = "cpython39"
+
+[[lift.interpreters]]
+id = "cpython311"
+lazy = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["cpython38".to_string(), "cpython311".to_string()],
+            lazy_interpreter_ids(&manifest).unwrap()
+        );
+
+        std::fs::write(&manifest, "[lift]\nname = \"example\"\n").unwrap();
+        assert!(lazy_interpreter_ids(&manifest).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use ring::signature::Ed25519KeyPair;
+
+use crate::utils::fs::write_file;
+
+const PUBLIC_KEY_ENV_VAR: &str = "SCIE_PANTS_UPDATE_PUBLIC_KEY";
+const SIGNING_KEY_ENV_VAR: &str = "SCIE_PANTS_UPDATE_SIGNING_KEY";
+
+/// The hex-encoded ed25519 public key the `scie-pants` binary embeds to verify the signatures of
+/// the binaries it self-updates to.
+pub(crate) struct UpdatePublicKey(String);
+
+impl UpdatePublicKey {
+    /// Release builds must embed an update public key; so this fails if none is configured.
+    pub(crate) fn required() -> Result<Self> {
+        let public_key = env::var(PUBLIC_KEY_ENV_VAR).unwrap_or_default();
+        let public_key = public_key.trim();
+        if public_key.is_empty() {
+            bail!(
+                "A release build of scie-pants must embed the public key used to verify its \
+                self-updates; export {PUBLIC_KEY_ENV_VAR} with the hex-encoded ed25519 public key."
+            );
+        }
+        if decode_hex(public_key)?.len() != 32 {
+            bail!(
+                "Expected {PUBLIC_KEY_ENV_VAR} to be a 32 byte hex-encoded ed25519 public key, \
+                got: {public_key}"
+            );
+        }
+        Ok(Self(public_key.to_owned()))
+    }
+
+    /// Checks the given `scie-pants` binary, which may have been built elsewhere, embeds this key.
+    pub(crate) fn check_embedded_in(&self, binary: &Path) -> Result<()> {
+        let contents = std::fs::read(binary)
+            .with_context(|| format!("Failed to read {binary}", binary = binary.display()))?;
+        if !contents
+            .windows(self.0.len())
+            .any(|window| window == self.0.as_bytes())
+        {
+            bail!(
+                "The scie-pants binary at {binary} does not embed the update public key from \
+                {PUBLIC_KEY_ENV_VAR}; re-build it with {PUBLIC_KEY_ENV_VAR} exported.",
+                binary = binary.display()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Signs release binaries with the private half of the [`UpdatePublicKey`].
+pub(crate) struct UpdateSigner(Ed25519KeyPair);
+
+impl UpdateSigner {
+    pub(crate) fn required(public_key: &UpdatePublicKey) -> Result<Self> {
+        let seed = env::var(SIGNING_KEY_ENV_VAR).unwrap_or_default();
+        if seed.trim().is_empty() {
+            bail!(
+                "A release build of scie-pants must be signed; export {SIGNING_KEY_ENV_VAR} with \
+                the hex-encoded 32 byte ed25519 private key seed matching {PUBLIC_KEY_ENV_VAR}."
+            );
+        }
+        Self::new(&decode_hex(&seed)?, &decode_hex(&public_key.0)?)
+    }
+
+    fn new(seed: &[u8], public_key: &[u8]) -> Result<Self> {
+        let key_pair =
+            Ed25519KeyPair::from_seed_and_public_key(seed, public_key).map_err(|_| {
+                anyhow!(
+                "The {SIGNING_KEY_ENV_VAR} private key does not match the {PUBLIC_KEY_ENV_VAR} \
+                public key."
+            )
+            })?;
+        Ok(Self(key_pair))
+    }
+
+    /// Writes the hex-encoded detached signature of `file` to `<file>.sig` and returns its path.
+    pub(crate) fn sign(&self, file: &Path) -> Result<PathBuf> {
+        let contents = std::fs::read(file)
+            .with_context(|| format!("Failed to read {file}", file = file.display()))?;
+        let signature = encode_hex(self.0.sign(&contents).as_ref());
+        let mut signature_file = file.as_os_str().to_owned();
+        signature_file.push(".sig");
+        let signature_file = PathBuf::from(signature_file);
+        write_file(&signature_file, false, signature)?;
+        Ok(signature_file)
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(hex, "{byte:02x}").expect("Writing to a String cannot fail.");
+    }
+    hex
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.trim();
+    if hex.len() % 2 != 0 {
+        bail!(
+            "Expected an even number of hex digits, found {len}.",
+            len = hex.len()
+        );
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| {
+            hex.get(index..index + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .with_context(|| format!("Invalid hex byte at offset {index}."))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+
+    use super::{decode_hex, encode_hex, UpdatePublicKey, UpdateSigner};
+
+    #[test]
+    fn sign_and_check_embedded() {
+        let seed = [7; 32];
+        let public_key = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .unwrap()
+            .public_key()
+            .as_ref()
+            .to_vec();
+        assert!(UpdateSigner::new(&seed, &[0; 32]).is_err());
+        let signer = UpdateSigner::new(&seed, &public_key).unwrap();
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let binary = tmpdir.path().join("scie-pants");
+        let embedded_key = UpdatePublicKey(encode_hex(&public_key));
+        std::fs::write(&binary, format!("prefix{key}suffix", key = embedded_key.0)).unwrap();
+        embedded_key.check_embedded_in(&binary).unwrap();
+
+        let signature = std::fs::read_to_string(signer.sign(&binary).unwrap()).unwrap();
+        UnparsedPublicKey::new(&ED25519, &public_key)
+            .verify(
+                &std::fs::read(&binary).unwrap(),
+                &decode_hex(&signature).unwrap(),
+            )
+            .unwrap();
+
+        std::fs::write(&binary, "no key here").unwrap();
+        assert!(embedded_key.check_embedded_in(&binary).is_err());
+    }
+}
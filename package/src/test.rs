@@ -6,20 +6,24 @@ use std::ffi::OsString;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use regex::Regex;
 use tempfile::TempDir;
-use termcolor::{Color, WriteColor};
+use termcolor::Color;
 
-use crate::utils::build::fingerprint;
+use crate::utils::build::{check_sha256, fingerprint};
 use crate::utils::exe::{execute, execute_with_input, Platform, CURRENT_PLATFORM};
 use crate::utils::fs::{
-    copy, create_tempdir, ensure_directory, remove_dir, rename, softlink, touch, write_file,
+    canonicalize, copy, ensure_directory, remove_dir, rename, softlink, touch, write_file,
 };
 use crate::utils::os::{EOL, PATHSEP};
 use crate::{build_step, log};
 
+// N.B.: This is the exit code scie-pants documents for a missing Pants version.
+const EXIT_CODE_NO_PANTS_VERSION: i32 = 3;
+
 macro_rules! integration_test {
     ($msg:expr $(,)?) => {
         log!(::termcolor::Color::Magenta, ">> {}", format!($msg));
@@ -29,6 +33,60 @@ macro_rules! integration_test {
     };
 }
 
+static KEEP_TEMP_ON_FAILURE: AtomicBool = AtomicBool::new(false);
+
+/// A temporary directory for an integration test that is deleted when dropped unless the test is
+/// failing and `--keep-temp-on-failure` was passed.
+#[derive(Debug)]
+struct TestTempDir(Option<TempDir>);
+
+impl TestTempDir {
+    fn path(&self) -> &Path {
+        self.0
+            .as_ref()
+            .expect("The TempDir is only taken on drop or by into_path.")
+            .path()
+    }
+
+    fn into_path(mut self) -> PathBuf {
+        self.0
+            .take()
+            .expect("The TempDir is only taken on drop or by into_path.")
+            .into_path()
+    }
+}
+
+impl AsRef<Path> for TestTempDir {
+    fn as_ref(&self) -> &Path {
+        self.path()
+    }
+}
+
+impl Drop for TestTempDir {
+    fn drop(&mut self) {
+        // N.B.: A failing integration test panics; so its temporary directories are dropped while
+        // unwinding.
+        if std::thread::panicking() && KEEP_TEMP_ON_FAILURE.load(Ordering::SeqCst) {
+            if let Some(tempdir) = self.0.take() {
+                log!(
+                    Color::Yellow,
+                    "Kept the temporary directory of the failed test at {path}",
+                    path = tempdir.into_path().display()
+                );
+            }
+        }
+    }
+}
+
+/// Keeps the temporary directories of a failing integration test for post-mortem debugging.
+pub(crate) fn keep_tempdirs_on_failure() {
+    KEEP_TEMP_ON_FAILURE.store(true, Ordering::SeqCst);
+}
+
+fn create_tempdir() -> Result<TestTempDir> {
+    crate::utils::fs::create_tempdir().map(|tempdir| TestTempDir(Some(tempdir)))
+}
+
 macro_rules! issue_link {
     ($issue: expr) => {
         issue_link($issue, "pantsbuild/scie-pants")
@@ -38,6 +96,89 @@ macro_rules! issue_link {
     };
 }
 
+// Registers an integration test under its function name; e.g.:
+// `register!(tests, test_tools(scie_pants_scie, check))` registers "test_tools".
+macro_rules! register {
+    ($tests:ident, $test:ident($($arg:expr),* $(,)?)) => {
+        $tests.register(stringify!($test), Box::new(|| $test($($arg),*)))
+    };
+}
+
+struct IntegrationTest<'a> {
+    name: &'static str,
+    pantsd: bool,
+    run: Box<dyn Fn() + 'a>,
+}
+
+/// The integration tests in the order they run, keyed by their function names for `--only`.
+struct IntegrationTests<'a> {
+    tests: Vec<IntegrationTest<'a>>,
+    pantsd: bool,
+}
+
+impl Default for IntegrationTests<'_> {
+    fn default() -> Self {
+        Self {
+            tests: vec![],
+            pantsd: true,
+        }
+    }
+}
+
+impl<'a> IntegrationTests<'a> {
+    fn register(&mut self, name: &'static str, run: Box<dyn Fn() + 'a>) {
+        self.tests.push(IntegrationTest {
+            name,
+            pantsd: self.pantsd,
+            run,
+        });
+    }
+
+    /// Runs the tests registered from here on out with pantsd turned off.
+    fn disable_pantsd(&mut self) {
+        self.pantsd = false;
+    }
+
+    fn run(self, only: &[String]) -> Result<()> {
+        let unknown = only
+            .iter()
+            .filter(|name| !self.tests.iter().any(|test| test.name == name.as_str()))
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            bail!(
+                "There is no integration test named {unknown} on {platform}. The available \
+                tests are:\n{available}",
+                unknown = unknown
+                    .iter()
+                    .map(|name| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                platform = CURRENT_PLATFORM.to_str(),
+                available = self
+                    .tests
+                    .iter()
+                    .map(|test| format!("  {name}", name = test.name))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+
+        let mut pantsd = true;
+        for test in self.tests {
+            if !only.is_empty() && !only.iter().any(|name| name == test.name) {
+                continue;
+            }
+            if pantsd && !test.pantsd {
+                log!(Color::Yellow, "Turning off pantsd for remaining tests.");
+                env::set_var("PANTS_PANTSD", "False");
+                pantsd = false;
+            }
+            (test.run)();
+        }
+        Ok(())
+    }
+}
+
 fn issue_link(issue: usize, repo: &str) -> String {
     format!("https://github.com/{repo}/issues/{issue}")
 }
@@ -125,7 +266,14 @@ pub(crate) fn run_integration_tests(
     scie_pants_scie: &Path,
     check: bool,
     tools_pex_mismatch_warn: bool,
+    pants_clone_cache: Option<&Path>,
+    only: &[String],
 ) -> Result<()> {
+    let pants_clone_cache = match pants_clone_cache {
+        Some(pants_clone_cache) => validate_pants_clone_cache(pants_clone_cache)?,
+        None => crate::utils::fs::dev_cache_dir()?,
+    };
+
     build_step!("Running smoke tests");
     log!(
         Color::Yellow,
@@ -136,66 +284,157 @@ pub(crate) fn run_integration_tests(
     // Our `.pants.bootstrap` uses `tput` which requires TERM be set: ensure it is.
     env::set_var("TERM", env::var_os("TERM").unwrap_or_else(|| "dumb".into()));
 
+    let clone_root = create_tempdir()?;
+    let clone_dir = pants_clone_cache.join("clones");
+    let pants_2_21_0_dev6_clone_dir = clone_dir.join(PANTS_2_21_0_DEV6);
+    let venv_dir = pants_clone_cache.join("venvs");
+    let pants_2_21_0_dev6_venv_dir = venv_dir.join(PANTS_2_21_0_DEV6);
+
+    let mut tests = IntegrationTests::default();
     // Max Python supported is 3.9 and only Linux x86_64 and macOS aarch64 and x86_64 wheels were
     // released.
     if matches!(
         *CURRENT_PLATFORM,
         Platform::LinuxX86_64 | Platform::MacOSAarch64 | Platform::MacOSX86_64
     ) {
-        test_tools(scie_pants_scie, check);
-        test_pants_bin_name_handling(scie_pants_scie);
-        test_pants_bootstrap_handling(scie_pants_scie);
-        test_pants_bootstrap_stdout_silent(scie_pants_scie);
-        test_tools_pex_reproducibility(workspace_root, tools_pex_path, tools_pex_mismatch_warn);
-        test_pants_bootstrap_tools(scie_pants_scie);
-
-        log!(Color::Yellow, "Turning off pantsd for remaining tests.");
-        env::set_var("PANTS_PANTSD", "False");
-
-        test_pants_2_25_using_python_3_11(scie_pants_scie);
-        test_python_repos_repos(scie_pants_scie);
-        test_initialize_new_pants_project(scie_pants_scie);
-        test_set_pants_version(scie_pants_scie);
-        test_ignore_empty_pants_version(scie_pants_scie);
-
-        test_pants_from_pex_version(scie_pants_scie);
-        test_pants_from_bad_pex_version(scie_pants_scie);
-
-        let clone_root = create_tempdir()?;
-        test_use_in_repo_with_pants_script(scie_pants_scie, &clone_root);
-        test_dot_env_loading(scie_pants_scie, &clone_root);
-        test_dot_env_error(scie_pants_scie);
-
-        let dev_cache_dir = crate::utils::fs::dev_cache_dir()?;
-        let clone_dir = dev_cache_dir.join("clones");
-        let pants_2_21_0_dev6_clone_dir = clone_dir.join("pants-2.21.0.dev6");
-        let venv_dir = dev_cache_dir.join("venvs");
-        let pants_2_21_0_dev6_venv_dir = venv_dir.join("pants-2.21.0.dev6");
-
-        test_pants_source_mode(
-            scie_pants_scie,
-            &clone_dir,
-            &pants_2_21_0_dev6_clone_dir,
-            &venv_dir,
-            &pants_2_21_0_dev6_venv_dir,
+        register!(tests, test_tools(scie_pants_scie, check));
+        register!(tests, test_pants_bin_name_handling(scie_pants_scie));
+        register!(tests, test_scie_pants_extra_env(scie_pants_scie));
+        register!(tests, test_pants_bootstrap_handling(scie_pants_scie));
+        register!(tests, test_pants_bootstrap_stdout_silent(scie_pants_scie));
+        register!(
+            tests,
+            test_tools_pex_reproducibility(workspace_root, tools_pex_path, tools_pex_mismatch_warn)
+        );
+        register!(tests, test_pants_bootstrap_tools(scie_pants_scie));
+
+        tests.disable_pantsd();
+
+        register!(tests, test_pants_2_25_using_python_3_11(scie_pants_scie));
+        register!(tests, test_python_repos_repos(scie_pants_scie));
+        register!(tests, test_initialize_new_pants_project(scie_pants_scie));
+        register!(tests, test_init_dry_run(scie_pants_scie));
+        register!(tests, test_set_pants_version(scie_pants_scie));
+        register!(tests, test_no_prompt(scie_pants_scie));
+        register!(tests, test_decline_prompt(scie_pants_scie));
+        register!(tests, test_ignore_empty_pants_version(scie_pants_scie));
+        register!(
+            tests,
+            test_pants_toml_default_interpolation(scie_pants_scie)
+        );
+        register!(tests, test_pants_config_files(scie_pants_scie));
+        register!(tests, test_pants_bootstrap_explain(scie_pants_scie));
+        register!(tests, test_config_name(scie_pants_scie));
+        register!(tests, test_pants_version_range(scie_pants_scie));
+        register!(tests, test_dump_env(scie_pants_scie));
+        register!(tests, test_no_system_python(scie_pants_scie));
+        register!(
+            tests,
+            test_pants_version_env_override_warning(scie_pants_scie)
+        );
+        register!(tests, test_scie_pants_config(scie_pants_scie));
+        register!(tests, test_scie_pants_log(scie_pants_scie));
+        register!(tests, test_pants_sha(scie_pants_scie));
+        register!(tests, test_offline(scie_pants_scie));
+        register!(tests, test_debugpy_version_range(scie_pants_scie));
+        register!(tests, test_pants_debug_port(scie_pants_scie));
+        register!(tests, test_pants_bootstrap_fragments(scie_pants_scie));
+        register!(tests, test_static_pants_bootstrap_cache(scie_pants_scie));
+        register!(tests, test_pants_buildroot_markers(scie_pants_scie));
+        register!(tests, test_pants_buildroot_override(scie_pants_scie));
+        register!(tests, test_pants_toml_directory(scie_pants_scie));
+        register!(
+            tests,
+            test_pants_version_env_var_validation(scie_pants_scie)
+        );
+        register!(tests, test_pants_version_git_sha(scie_pants_scie));
+        register!(tests, test_download_retries_validation(scie_pants_scie));
+        register!(tests, test_download_proxy(scie_pants_scie));
+        register!(tests, test_bootstrap_timeout(scie_pants_scie));
+        register!(tests, test_user_config_default_version(scie_pants_scie));
+        register!(tests, test_event_log(scie_pants_scie));
+        register!(tests, test_doctor(scie_pants_scie));
+        register!(tests, test_scie_pants_version(scie_pants_scie));
+
+        register!(tests, test_pants_from_pex_version(scie_pants_scie));
+        register!(tests, test_pants_from_bad_pex_version(scie_pants_scie));
+
+        register!(
+            tests,
+            test_use_in_repo_with_pants_script(scie_pants_scie, &clone_root)
+        );
+        register!(tests, test_dot_env_loading(scie_pants_scie, &clone_root));
+        register!(tests, test_dot_env_error(scie_pants_scie));
+        register!(tests, test_dot_env_discovery(scie_pants_scie));
+
+        register!(
+            tests,
+            test_pants_source_mode(
+                scie_pants_scie,
+                &clone_dir,
+                &pants_2_21_0_dev6_clone_dir,
+                &venv_dir,
+                &pants_2_21_0_dev6_venv_dir,
+            )
+        );
+        register!(
+            tests,
+            test_pants_source_mode_ignores_pants_version(
+                scie_pants_scie,
+                &pants_2_21_0_dev6_clone_dir,
+                &pants_2_21_0_dev6_venv_dir,
+            )
+        );
+        register!(
+            tests,
+            test_pants_from_sources_mode(
+                scie_pants_scie,
+                &pants_2_21_0_dev6_clone_dir,
+                &pants_2_21_0_dev6_venv_dir,
+            )
+        );
+        register!(
+            tests,
+            test_delegate_pants_in_pants_repo(scie_pants_scie, &pants_2_21_0_dev6_clone_dir)
         );
-        test_pants_from_sources_mode(
-            scie_pants_scie,
-            &pants_2_21_0_dev6_clone_dir,
-            &pants_2_21_0_dev6_venv_dir,
+        register!(
+            tests,
+            test_use_pants_release_in_pants_repo(scie_pants_scie, &pants_2_21_0_dev6_clone_dir)
         );
-        test_delegate_pants_in_pants_repo(scie_pants_scie, &pants_2_21_0_dev6_clone_dir);
-        test_use_pants_release_in_pants_repo(scie_pants_scie, &pants_2_21_0_dev6_clone_dir);
 
-        test_caching_issue_129(scie_pants_scie);
-        test_custom_pants_toml_issue_153(scie_pants_scie);
-        test_pants_native_client_perms_issue_182(scie_pants_scie);
+        register!(tests, test_caching_issue_129(scie_pants_scie));
+        register!(tests, test_custom_pants_toml_issue_153(scie_pants_scie));
+        register!(
+            tests,
+            test_pants_native_client_perms_issue_182(scie_pants_scie)
+        );
 
         #[cfg(unix)]
-        test_non_utf8_env_vars_issue_198(scie_pants_scie);
+        register!(tests, test_non_utf8_env_vars_issue_198(scie_pants_scie));
+        #[cfg(unix)]
+        register!(tests, test_non_utf8_pants_bootstrap_env(scie_pants_scie));
+        #[cfg(unix)]
+        register!(tests, test_unreadable_pants_toml(scie_pants_scie));
+        #[cfg(unix)]
+        register!(tests, test_scie_pants_bash(scie_pants_scie));
+        #[cfg(unix)]
+        register!(tests, test_delegate_bootstrap_script(scie_pants_scie));
+        #[cfg(unix)]
+        register!(tests, test_env_allowlist(scie_pants_scie));
+        #[cfg(unix)]
+        register!(tests, test_prepend_args(scie_pants_scie));
+        #[cfg(unix)]
+        register!(tests, test_metrics_file(scie_pants_scie));
+        #[cfg(target_os = "linux")]
+        register!(tests, test_xdg_cache_home_scie_base(scie_pants_scie));
+        #[cfg(unix)]
+        register!(
+            tests,
+            test_pants_from_sources_symlink_chain(scie_pants_scie)
+        );
 
-        test_bad_boot_error_text(scie_pants_scie);
-        test_pants_bootstrap_urls(scie_pants_scie);
+        register!(tests, test_bad_boot_error_text(scie_pants_scie));
+        register!(tests, test_pants_bootstrap_urls(scie_pants_scie));
     }
 
     // Max Python supported is 3.8 and only Linux and macOS x86_64 wheels were released.
@@ -203,12 +442,84 @@ pub(crate) fn run_integration_tests(
         *CURRENT_PLATFORM,
         Platform::LinuxX86_64 | Platform::MacOSX86_64
     ) {
-        test_python38_used_for_old_pants(scie_pants_scie);
+        register!(tests, test_python38_used_for_old_pants(scie_pants_scie));
+    }
+
+    register!(tests, test_self_update(scie_pants_scie));
+    register!(tests, test_self_update_edge_channel(scie_pants_scie));
+    register!(tests, test_self_downgrade(scie_pants_scie));
+
+    tests.run(only)
+}
+
+/// Re-checks the fingerprint of an already built scie-pants scie and puts it through a few quick
+/// smoke checks that need neither Pants nor the network, reporting PASS or FAIL for each.
+pub(crate) fn verify_scie(scie_pants_scie: &Path) -> Result<()> {
+    build_step!("Verifying {scie}", scie = scie_pants_scie.display());
+    let checks: [(&str, &dyn Fn() -> Result<()>); 3] = [
+        ("sha256 fingerprint", &|| check_sha256(scie_pants_scie)),
+        ("scie-pants version report", &|| {
+            verify_version_report(scie_pants_scie)
+        }),
+        ("Pants version resolution", &|| {
+            verify_version_resolution(scie_pants_scie)
+        }),
+    ];
+    let mut failures = 0;
+    for (name, check) in &checks {
+        match check() {
+            Ok(()) => log!(Color::Green, "PASS {name}"),
+            Err(err) => {
+                failures += 1;
+                log!(Color::Red, "FAIL {name}: {err:#}");
+            }
+        }
+    }
+    if failures > 0 {
+        bail!(
+            "{failures} of {total} checks failed for {scie}",
+            total = checks.len(),
+            scie = scie_pants_scie.display()
+        );
     }
+    Ok(())
+}
 
-    test_self_update(scie_pants_scie);
-    test_self_downgrade(scie_pants_scie);
+fn verify_version_report(scie_pants_scie: &Path) -> Result<()> {
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("--scie-pants-version")
+            .stdout(Stdio::piped()),
+    )?;
+    let version = decode_output(output.stdout)?;
+    if version.trim().is_empty() {
+        bail!("The scie-pants version reported was empty.");
+    }
+    Ok(())
+}
 
+fn verify_version_resolution(scie_pants_scie: &Path) -> Result<()> {
+    let tmpdir = create_tempdir()?;
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )?;
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+    )?;
+    let explanation: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .context("Failed to parse the PANTS_BOOTSTRAP_EXPLAIN output as JSON.")?;
+    if explanation["pants_version"] != "2.18.0" || explanation["source"] != "config" {
+        bail!("Expected Pants 2.18.0 to be resolved from the config, got: {explanation}");
+    }
     Ok(())
 }
 
@@ -310,6 +621,94 @@ fn test_pants_bin_name_handling(scie_pants_scie: &Path) {
     let absolute_argv0 = absolute_argv0_path.to_str().unwrap();
     assert_pants_bin_name(absolute_argv0, absolute_argv0, vec![]);
     assert_pants_bin_name(absolute_argv0, "spam", vec![("PANTS_BIN_NAME", "spam")]);
+
+    assert_stderr_output(
+        Command::new(absolute_argv0)
+            .arg("-V")
+            .env("PATH", &path)
+            .env("PANTS_BIN_NAME", "spam")
+            .current_dir(&project_dir),
+        vec!["PANTS_BIN_NAME is set to \"spam\", which is not a file and is not found on the PATH"],
+        ExpectedResult::Success,
+    );
+    let (_, stderr) = assert_stderr_output(
+        Command::new(absolute_argv0)
+            .arg("-V")
+            .env("PATH", &path)
+            .env("PANTS_BIN_NAME", "foo")
+            .current_dir(&project_dir),
+        vec![],
+        ExpectedResult::Success,
+    );
+    assert!(
+        !stderr.contains("PANTS_BIN_NAME is set to"),
+        "STDERR unexpectedly warned about PANTS_BIN_NAME:\n{stderr}"
+    );
+}
+
+fn test_scie_pants_extra_env(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_PANTS_EXTRA_ENV vars reach Pants");
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        [anonymous-telemetry]
+        enabled = false
+        "#,
+    )
+    .unwrap();
+    let execution_root_dir = tmpdir.path().join("execution-root");
+    let logdir = tmpdir.path().join("logs");
+    let extra_env = tmpdir.path().join("extra.env");
+    write_file(
+        &extra_env,
+        false,
+        format!(
+            r#"
+            # Just for this run.
+            PANTS_LOCAL_EXECUTION_ROOT_DIR={execution_root_dir}
+            export PANTS_LOGDIR="{logdir}"
+            PANTS_LEVEL=warn
+            not a KEY=VALUE line
+            "#,
+            execution_root_dir = execution_root_dir.display(),
+            logdir = logdir.display()
+        ),
+    )
+    .unwrap();
+
+    let (output, _) = assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("help-advanced")
+            .arg("global")
+            .env("SCIE_PANTS_EXTRA_ENV", &extra_env)
+            .env("PANTS_LEVEL", "info")
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+        vec!["Skipping malformed line 6 of the SCIE_PANTS_EXTRA_ENV file"],
+        ExpectedResult::Success,
+    );
+    let stdout = decode_output(output.stdout).unwrap();
+    for expected in [
+        format!(
+            "current value: {execution_root_dir} (from env var PANTS_LOCAL_EXECUTION_ROOT_DIR)",
+            execution_root_dir = execution_root_dir.display()
+        ),
+        format!(
+            "current value: {logdir} (from env var PANTS_LOGDIR)",
+            logdir = logdir.display()
+        ),
+        // The user's environment wins over the SCIE_PANTS_EXTRA_ENV file.
+        "current value: info (from env var PANTS_LEVEL)".to_string(),
+    ] {
+        assert!(
+            stdout.contains(&expected),
+            "Expected:{EOL}{expected}{EOL}STDOUT was:{EOL}{stdout}",
+        );
+    }
 }
 
 fn test_pants_bootstrap_handling(scie_pants_scie: &Path) {
@@ -331,6 +730,57 @@ fn test_pants_bootstrap_handling(scie_pants_scie: &Path) {
     );
 }
 
+// N.B.: This names the Pants clone and venv in the clones and venvs dirs of the Pants clone cache.
+const PANTS_2_21_0_DEV6: &str = "pants-2.21.0.dev6";
+
+// The test patch applied to the Pants clone bumps its version to this.
+const PANTS_2_21_0_DEV6_PATCHED_VERSION: &str = "2.21.0.dev6+Custom-Local";
+
+fn validate_pants_clone_cache(pants_clone_cache: &Path) -> Result<PathBuf> {
+    if !pants_clone_cache.is_dir() {
+        bail!(
+            "The --pants-clone-cache {path} is not a directory.",
+            path = pants_clone_cache.display()
+        );
+    }
+    let pants_clone_cache = canonicalize(pants_clone_cache)?;
+    let clone_dir = pants_clone_cache.join("clones").join(PANTS_2_21_0_DEV6);
+    let venv_dir = pants_clone_cache.join("venvs").join(PANTS_2_21_0_DEV6);
+    if !clone_dir.exists() && !venv_dir.exists() {
+        log!(
+            Color::Yellow,
+            "The --pants-clone-cache {path} is empty; it will be populated by the Pants from \
+            sources tests.",
+            path = pants_clone_cache.display()
+        );
+        return Ok(pants_clone_cache);
+    }
+    let version_file = clone_dir
+        .join("src")
+        .join("python")
+        .join("pants")
+        .join("VERSION");
+    let version = std::fs::read_to_string(&version_file).unwrap_or_default();
+    if !clone_dir.join("pants").is_file() || version.trim() != PANTS_2_21_0_DEV6_PATCHED_VERSION {
+        bail!(
+            "The --pants-clone-cache {path} does not hold a patched Pants clone at {clone_dir}: \
+            expected a `pants` script and a {version_file} containing \
+            {PANTS_2_21_0_DEV6_PATCHED_VERSION}.",
+            path = pants_clone_cache.display(),
+            clone_dir = clone_dir.display(),
+            version_file = version_file.display()
+        );
+    }
+    if !venv_dir.is_dir() {
+        bail!(
+            "The --pants-clone-cache {path} does not hold a Pants venv directory at {venv_dir}.",
+            path = pants_clone_cache.display(),
+            venv_dir = venv_dir.display()
+        );
+    }
+    Ok(pants_clone_cache)
+}
+
 fn test_tools_pex_reproducibility(
     workspace_root: &Path,
     tools_pex_path: &Path,
@@ -364,12 +814,24 @@ fn test_tools_pex_reproducibility(
 
 fn test_pants_bootstrap_tools(scie_pants_scie: &Path) {
     integration_test!("Verifying PANTS_BOOTSTRAP_TOOLS works");
-    execute(
+    let output = execute(
         Command::new(scie_pants_scie)
             .env("PANTS_BOOTSTRAP_TOOLS", "1")
-            .args(["bootstrap-cache-key"]),
+            .args(["bootstrap-cache-key"])
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    let cache_key = decode_output(output.stdout).unwrap();
+    assert!(!cache_key.trim().is_empty());
+
+    // The `cache-key` boot command is a shortcut for the same.
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .env("SCIE_BOOT", "cache-key")
+            .stdout(Stdio::piped()),
     )
     .unwrap();
+    assert_eq!(cache_key, decode_output(output.stdout).unwrap());
 }
 
 fn test_pants_2_25_using_python_3_11(scie_pants_scie: &Path) {
@@ -421,6 +883,7 @@ fn test_initialize_new_pants_project(scie_pants_scie: &Path) {
     execute_with_input(
         Command::new(scie_pants_scie)
             .arg("-V")
+            .env("SCIE_PANTS_NO_PROMPT", "0")
             .current_dir(project_subdir),
         "yes".as_bytes(),
     )
@@ -428,6 +891,26 @@ fn test_initialize_new_pants_project(scie_pants_scie: &Path) {
     assert!(new_project_dir.path().join("pants.toml").is_file());
 }
 
+fn test_init_dry_run(scie_pants_scie: &Path) {
+    integration_test!("Verifying --init-dry-run previews the new Pants project without writing it");
+    let new_project_dir = create_tempdir().unwrap();
+    execute(Command::new("git").arg("init").arg(new_project_dir.path())).unwrap();
+    let project_subdir = new_project_dir.path().join("subdir");
+    ensure_directory(&project_subdir, false).unwrap();
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("--init-dry-run")
+            .current_dir(&project_subdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    assert_eq!(
+        "[GLOBAL]\npants_version = \"<latest stable Pants release>\"\n",
+        decode_output(output.stdout).unwrap()
+    );
+    assert!(!new_project_dir.path().join("pants.toml").exists());
+}
+
 fn test_set_pants_version(scie_pants_scie: &Path) {
     integration_test!("Verifying setting the Pants version on an existing Pants project works");
     let existing_project_dir = create_tempdir().unwrap();
@@ -435,12 +918,87 @@ fn test_set_pants_version(scie_pants_scie: &Path) {
     execute_with_input(
         Command::new(scie_pants_scie)
             .arg("-V")
+            .env("SCIE_PANTS_NO_PROMPT", "0")
             .current_dir(existing_project_dir.path()),
         "Y".as_bytes(),
     )
     .unwrap();
 }
 
+fn test_no_prompt(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying a missing Pants version fails fast when prompting is not possible"
+    );
+    let existing_project_dir = create_tempdir().unwrap();
+    let pants_toml = existing_project_dir.path().join("pants.toml");
+    touch(&pants_toml).unwrap();
+    let fix = "pants.toml or export PANTS_VERSION.";
+
+    let (output, _) = assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env_remove("SCIE_PANTS_NO_PROMPT")
+            .current_dir(existing_project_dir.path())
+            .stdin(Stdio::null()),
+        vec![
+            "No Pants version is configured and scie-pants will not prompt for one since STDIN is \
+            not a terminal.",
+            fix,
+        ],
+        ExpectedResult::Failure,
+    );
+    assert_eq!(Some(EXIT_CODE_NO_PANTS_VERSION), output.status.code());
+    let (output, _) = assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_PANTS_NO_PROMPT", "1")
+            .current_dir(existing_project_dir.path()),
+        vec![
+            "No Pants version is configured and scie-pants will not prompt for one since \
+            SCIE_PANTS_NO_PROMPT=1 is set.",
+            fix,
+        ],
+        ExpectedResult::Failure,
+    );
+    assert_eq!(Some(EXIT_CODE_NO_PANTS_VERSION), output.status.code());
+    assert_eq!("", std::fs::read_to_string(&pants_toml).unwrap());
+}
+
+fn test_decline_prompt(scie_pants_scie: &Path) {
+    integration_test!("Verifying declining the Pants version prompt exits with a dedicated code");
+
+    let decline = |command: &mut Command| {
+        let mut child = command
+            .arg("-V")
+            .env("SCIE_PANTS_NO_PROMPT", "0")
+            .stdin(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b"n\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(
+            Some(EXIT_CODE_NO_PANTS_VERSION),
+            output.status.code(),
+            "STDERR:\n{stderr}",
+            stderr = decode_output(output.stderr).unwrap()
+        );
+    };
+
+    // Declining to set the Pants version of an existing Pants project.
+    let existing_project_dir = create_tempdir().unwrap();
+    let pants_toml = existing_project_dir.path().join("pants.toml");
+    touch(&pants_toml).unwrap();
+    decline(Command::new(scie_pants_scie).current_dir(existing_project_dir.path()));
+    assert_eq!("", std::fs::read_to_string(&pants_toml).unwrap());
+
+    // Declining to set up a new Pants project.
+    let new_project_dir = create_tempdir().unwrap();
+    execute(Command::new("git").arg("init").arg(new_project_dir.path())).unwrap();
+    decline(Command::new(scie_pants_scie).current_dir(new_project_dir.path()));
+    assert!(!new_project_dir.path().join("pants.toml").exists());
+}
+
 fn test_ignore_empty_pants_version(scie_pants_scie: &Path) {
     integration_test!("Verifying ignoring PANTS_VERSION when set to empty string");
 
@@ -469,395 +1027,2147 @@ fn test_ignore_empty_pants_version(scie_pants_scie: &Path) {
     );
 }
 
-fn test_pants_from_pex_version(scie_pants_scie: &Path) {
-    integration_test!("Verify scie-pants can use Pants released as a 'local' PEX");
+fn test_pants_toml_default_interpolation(scie_pants_scie: &Path) {
+    integration_test!("Verifying `%(name)s` interpolation of `[DEFAULT]` values in pants.toml");
 
     let tmpdir = create_tempdir().unwrap();
-
-    let pants_release = "2.18.0";
-    let pants_toml_content = format!(
+    let pants_toml = tmpdir.path().join("pants.toml");
+    write_file(
+        &pants_toml,
+        false,
         r#"
+        [DEFAULT]
+        pants_minor = "18"
+        pants_rev = "2.%(pants_minor)s.%(env.SCIE_PANTS_TEST_PATCH)s"
         [GLOBAL]
-        pants_version = "{pants_release}"
-        "#
-    );
-    let pants_toml = tmpdir.path().join("pants.toml");
-    write_file(&pants_toml, false, pants_toml_content).unwrap();
-
+        pants_version = "%(pants_rev)s"
+        "#,
+    )
+    .unwrap();
     let output = execute(
         Command::new(scie_pants_scie)
             .arg("-V")
+            .env("SCIE_PANTS_TEST_PATCH", "0")
             .current_dir(&tmpdir)
             .stdout(Stdio::piped()),
     );
-    let expected_message = pants_release;
-    let stdout = decode_output(output.unwrap().stdout).unwrap();
-    assert!(
-        stdout.contains(expected_message),
-        "STDOUT did not contain '{expected_message}':\n{stdout}"
+    assert_eq!(
+        "2.18.0",
+        decode_output(output.unwrap().stdout).unwrap().trim()
     );
-}
 
-fn test_pants_from_bad_pex_version(scie_pants_scie: &Path) {
-    integration_test!(
-        "Verify the output of scie-pants is user-friendly if they provide an invalid pants version"
+    write_file(
+        &pants_toml,
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "%(pants_rev)s"
+        "#,
+    )
+    .unwrap();
+    assert_stderr_output(
+        Command::new(scie_pants_scie).arg("-V").current_dir(&tmpdir),
+        vec!["There is no `pants_rev` key defined in the `[DEFAULT]` section to interpolate."],
+        ExpectedResult::Failure,
     );
+}
 
-    let tmpdir = create_tempdir().unwrap();
+fn test_pants_config_files(scie_pants_scie: &Path) {
+    integration_test!("Verifying PANTS_CONFIG_FILES is honored when determining the Pants version");
 
-    let pants_release = "2.19";
-    let pants_toml_content = format!(
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
         r#"
         [GLOBAL]
-        pants_version = "{pants_release}"
-        "#
-    );
-    let pants_toml = tmpdir.path().join("pants.toml");
-    write_file(&pants_toml, false, pants_toml_content).unwrap();
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let pants_ci_toml = tmpdir.path().join("ci").join("pants.ci.toml");
+    write_file(
+        &pants_ci_toml,
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.19.1"
+        "#,
+    )
+    .unwrap();
+
+    for pants_config_files in [
+        "ci/pants.ci.toml".to_string(),
+        format!("['pants.toml', '{}']", pants_ci_toml.display()),
+    ] {
+        let output = execute(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("PANTS_CONFIG_FILES", &pants_config_files)
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
+        );
+        assert_eq!(
+            "2.19.1",
+            decode_output(output.unwrap().stdout).unwrap().trim(),
+            "Expected the version from ci/pants.ci.toml to win with \
+            PANTS_CONFIG_FILES={pants_config_files}"
+        );
+    }
 
-    let err = execute(
+    assert_stderr_output(
         Command::new(scie_pants_scie)
             .arg("-V")
-            .current_dir(&tmpdir)
-            .stderr(Stdio::piped()),
-    )
-    .unwrap_err();
-
-    let error_text = err.to_string();
-    assert!(error_text
-        .contains("Pants version must be a full version, including patch level, got: `2.19`."));
-    assert!(error_text.contains(
-        "Please add `.<patch_version>` to the end of the version. For example: `2.18` -> `2.18.0`."
-    ));
+            .env("PANTS_CONFIG_FILES", "does-not-exist.toml")
+            .current_dir(&tmpdir),
+        vec!["does-not-exist.toml (via PANTS_CONFIG_FILES env var)"],
+        ExpectedResult::Failure,
+    );
 }
 
-fn test_use_in_repo_with_pants_script(scie_pants_scie: &Path, clone_root: &TempDir) {
-    integration_test!("Verify scie-pants can be used as `pants` in a repo with the `pants` script");
-    // This verifies a fix for https://github.com/pantsbuild/scie-pants/issues/28.
-    execute(
-        Command::new("git")
-            .args(["clone", "https://github.com/pantsbuild/example-django"])
-            .current_dir(clone_root.path()),
-    )
-    .unwrap();
+fn test_pants_bootstrap_explain(scie_pants_scie: &Path) {
+    integration_test!("Verifying PANTS_BOOTSTRAP_EXPLAIN reports the version resolution");
 
-    let django_dir = clone_root.path().join("example-django");
-    execute(
-        Command::new("git")
-            .args(["checkout", "ff20d1126b5d67b6a77f7d6a39f3063d1897ceb4"])
-            .current_dir(&django_dir),
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
     )
     .unwrap();
+    // N.B.: We use a fresh SCIE_BASE to prove no bootstrapping is done.
+    let scie_base = tmpdir.path().join("scie-base");
 
-    let bin_dir = clone_root.path().join("bin");
-    ensure_directory(&bin_dir, false).unwrap();
-    copy(scie_pants_scie, bin_dir.join("pants").as_path()).unwrap();
-    let new_path = if let Ok(existing_path) = env::var("PATH") {
-        format!(
-            "{bin_dir}{path_sep}{existing_path}",
-            bin_dir = bin_dir.display(),
-            path_sep = PATHSEP
+    let explain = |extra_env: Vec<(&str, &str)>| -> serde_json::Value {
+        let output = execute(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+                .env("SCIE_BASE", &scie_base)
+                .envs(extra_env)
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
         )
-    } else {
-        format!("{bin_dir}", bin_dir = bin_dir.display())
+        .unwrap();
+        serde_json::from_slice(&output.stdout).unwrap()
     };
-    execute(
-        Command::new("pants")
+
+    let build_root = tmpdir.path().canonicalize().unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "pants_version": "2.18.0",
+            "source": "config",
+            "build_root": build_root,
+            "scie_boot": "pants",
+        }),
+        explain(vec![])
+    );
+    assert_eq!(
+        serde_json::json!({
+            "pants_version": "2.19.1",
+            "source": "env",
+            "build_root": build_root,
+            "scie_boot": "pants-debug",
+        }),
+        explain(vec![("PANTS_VERSION", "2.19.1"), ("PANTS_DEBUG", "1")])
+    );
+    assert_no_bindings(&scie_base);
+}
+
+fn test_config_name(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_PANTS_CONFIG_NAME names the Pants config to use");
+
+    let tmpdir = create_tempdir().unwrap();
+    let project = tmpdir.path().join("project");
+    write_file(
+        &project.join("pants.ci.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let subdir = project.join("src").join("python");
+    ensure_directory(&subdir, false).unwrap();
+
+    // N.B.: There is no pants.toml; so the alternate config must serve as the build root marker
+    // too.
+    let output = execute(
+        Command::new(scie_pants_scie)
             .arg("-V")
-            .env("PATH", new_path)
-            .current_dir(django_dir),
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .env("SCIE_PANTS_CONFIG_NAME", "pants.ci.toml")
+            .current_dir(&subdir)
+            .stdout(Stdio::piped()),
     )
     .unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "pants_version": "2.18.0",
+            "source": "config",
+            "build_root": project.canonicalize().unwrap(),
+            "scie_boot": "pants",
+        }),
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    );
+
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_PANTS_CONFIG_NAME", "ci/pants.toml")
+            .current_dir(&subdir),
+        vec!["SCIE_PANTS_CONFIG_NAME must be the bare file name of the Pants config"],
+        ExpectedResult::Failure,
+    );
 }
 
-fn test_dot_env_loading(scie_pants_scie: &Path, clone_root: &TempDir) {
+fn test_pants_version_range(scie_pants_scie: &Path) {
     integration_test!(
-        "Verify `.env` loading works (example-django should down grade to Pants 2.12.1)"
+        "Verifying a `[GLOBAL] pants_version` range resolves to the newest matching release"
     );
+
+    let tmpdir = create_tempdir().unwrap();
+    let range_project = tmpdir.path().join("range");
     write_file(
-        &clone_root.path().join(".env"),
+        &range_project.join("pants.toml"),
         false,
-        "PANTS_VERSION=2.12.1",
+        r#"
+        [GLOBAL]
+        pants_version = { min = "2.17.0", max = "2.18.0" }
+        "#,
     )
     .unwrap();
-    execute(
+    let output = execute(
         Command::new(scie_pants_scie)
             .arg("-V")
-            .current_dir(clone_root.path().join("example-django")),
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .current_dir(&range_project)
+            .stdout(Stdio::piped()),
     )
     .unwrap();
-}
-
-fn test_dot_env_error(scie_pants_scie: &Path) {
-    integration_test!("Verify `.env` loading emits errors if invalid");
+    assert_eq!(
+        serde_json::json!({
+            "pants_version": null,
+            "pants_version_specifier": ">=2.17.0,<=2.18.0",
+            "source": "config",
+            "build_root": range_project.canonicalize().unwrap(),
+            "scie_boot": "pants",
+        }),
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    );
+    // N.B.: The max bound is inclusive; so this resolves to 2.18.0 and not a 2.17.x release.
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .current_dir(&range_project)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    assert_eq!("2.18.0", decode_output(output.stdout).unwrap().trim());
 
-    let tempdir = create_tempdir().unwrap();
+    let bad_bound_project = tmpdir.path().join("bad-bound");
     write_file(
-        &tempdir.path().join(".env"),
+        &bad_bound_project.join("pants.toml"),
         false,
-        "CABBAGE=cabbagee\ntotally invalid line\nPOTATO=potato",
+        r#"
+        [GLOBAL]
+        pants_version = { min = "2.18" }
+        "#,
     )
     .unwrap();
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .current_dir(&bad_bound_project),
+        vec!["Pants version must be a full version, including patch level, got: `2.18`."],
+        ExpectedResult::Failure,
+    );
 
+    let bad_table_project = tmpdir.path().join("bad-table");
+    write_file(
+        &bad_table_project.join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = { minimum = "2.18.0" }
+        "#,
+    )
+    .unwrap();
     assert_stderr_output(
         Command::new(scie_pants_scie)
             .arg("-V")
-            .current_dir(tempdir.path()),
-        vec!["requested .env files be loaded but there was an error doing so: Parsing Error: Error { input: \"invalid line"],
-        ExpectedResult::Failure
+            .current_dir(&bad_table_project),
+        vec!["unknown field `minimum`"],
+        ExpectedResult::Failure,
     );
 }
 
-fn test_pants_source_mode(
-    scie_pants_scie: &Path,
-    clone_dir: &Path,
-    pants_2_21_0_dev6_clone_dir: &Path,
-    venv_dir: &Path,
-    pants_2_21_0_dev6_venv_dir: &Path,
-) {
-    integration_test!("Verify PANTS_SOURCE mode.");
-    // NB. we assume that these directories are setup perfectly if they exist. A possible failure
-    // mode is the symlinks to python interpreters in the venv; if the system changes to make them
-    // invalid, we start getting errors like `${pants_2_21_0_dev6_venv_dir}/.../bin/python: No such file
-    // or directory`. This can occur in practice with cross-runner caching and the runner updating,
-    // but our cache key is designed to avoid this (see `build_it_cache_key` step in ci.yml).
-    if !pants_2_21_0_dev6_clone_dir.exists() || !pants_2_21_0_dev6_venv_dir.exists() {
-        let clone_root_tmp = create_tempdir().unwrap();
-        let clone_root_path = clone_root_tmp
+fn test_pants_version_env_override_warning(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying a PANTS_VERSION that disagrees with the configured version is warned about"
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+    let warning = format!(
+        "Using Pants 2.19.0 from the PANTS_VERSION env var instead of Pants 2.18.0 from \
+        `[GLOBAL] pants_version` in {pants_toml}.",
+        pants_toml = tmpdir
             .path()
-            .to_str()
-            .with_context(|| {
-                format!("Failed to convert clone root path to UTF-8 string: {clone_root_tmp:?}")
-            })
-            .unwrap();
-        execute(Command::new("git").args(["init", clone_root_path])).unwrap();
-        // N.B.: The release_2.21.0.dev6 tag has sha 202d9214866d9e67ec7242f1b202cbf5e1164fa5 and we
-        // must pass a full sha to use the shallow fetch trick.
-        const PANTS_2_21_0_DEV6_SHA: &str = "202d9214866d9e67ec7242f1b202cbf5e1164fa5";
-        execute(
-            Command::new("git")
-                .args([
-                    "fetch",
-                    "--depth",
-                    "1",
-                    "https://github.com/pantsbuild/pants",
-                    PANTS_2_21_0_DEV6_SHA,
-                ])
-                .current_dir(clone_root_tmp.path()),
-        )
-        .unwrap();
-        execute(
-            Command::new("git")
-                .args(["reset", "--hard", PANTS_2_21_0_DEV6_SHA])
-                .current_dir(clone_root_tmp.path()),
-        )
-        .unwrap();
-        write_file(
-            clone_root_tmp.path().join("patch").as_path(),
-            false,
-            r#"
-diff --git a/build-support/pants_venv b/build-support/pants_venv
-index 90fa82f6d3..e4f7e97a95 100755
---- a/build-support/pants_venv
-+++ b/build-support/pants_venv
-@@ -13,6 +13,8 @@ REQUIREMENTS=(
+            .canonicalize()
+            .unwrap()
+            .join("pants.toml")
+            .display()
+    );
 
- platform=$(uname -mps)
+    let dump_env = |quiet: Option<&str>| {
+        let mut command = Command::new(scie_pants_scie);
+        command
+            .arg("-V")
+            .env("SCIE_PANTS_DUMP_ENV", "1")
+            .env("PANTS_VERSION", "2.19.0")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        match quiet {
+            Some(quiet) => command.env("SCIE_PANTS_QUIET", quiet),
+            None => command.env_remove("SCIE_PANTS_QUIET"),
+        };
+        let output = execute(&mut command).unwrap();
+        // The env var still wins.
+        assert!(decode_output(output.stdout)
+            .unwrap()
+            .lines()
+            .any(|line| line == "PANTS_VERSION=2.19.0"));
+        decode_output(output.stderr).unwrap()
+    };
 
-+echo >&2 "The ${SCIE_PANTS_TEST_MODE:-Pants 2.21.0.dev6 clone} is working."
-+
- function venv_dir() {
-   # Include the entire version string in order to differentiate e.g. PyPy from CPython.
-   # Fingerprinting uname and python output avoids shebang length limits and any odd chars.
-@@ -23,7 +25,7 @@ function venv_dir() {
+    let stderr = dump_env(None);
+    assert!(
+        stderr.contains(&warning),
+        "STDERR did not contain '{warning}':\n{stderr}"
+    );
+    let stderr = dump_env(Some("1"));
+    assert!(
+        !stderr.contains(&warning),
+        "STDERR unexpectedly contained '{warning}':\n{stderr}"
+    );
+    assert_no_bindings(&scie_base);
+}
 
-   # NB: We house these outside the working copy to avoid needing to gitignore them, but also to
-   # dodge https://github.com/hashicorp/vagrant/issues/12057.
--  echo "${HOME}/.cache/pants/pants_dev_deps/${venv_fingerprint}.venv"
-+  echo "${PANTS_VENV_DIR_PREFIX:-${HOME}/.cache/pants/pants_dev_deps}/${venv_fingerprint}.venv"
- }
+fn test_scie_pants_config(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying the `.scie-pants.toml` settings are used unless overridden by env vars"
+    );
 
- function activate_venv() {
-diff --git a/pants b/pants
-index ba49cc133f..870a35f028 100755
---- a/pants
-+++ b/pants
-@@ -76,4 +76,5 @@ function exec_pants_bare() {
-     exec ${PANTS_PREPEND_ARGS:-} "$(venv_dir)/bin/python" ${DEBUG_ARGS} "${PANTS_PY_EXE}" "$@"
- }
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    write_file(
+        &tmpdir.path().join(".scie-pants.toml"),
+        false,
+        r#"
+        quiet = true
+        download_retries = 5
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+    let warning = "Using Pants 2.19.0 from the PANTS_VERSION env var instead of Pants 2.18.0";
 
-+echo >&2 "Pants from sources argv: $@."
- exec_pants_bare "$@"
-diff --git a/src/python/pants/VERSION b/src/python/pants/VERSION
-index 796b3cddd2..aef0e649bb 100644
---- a/src/python/pants/VERSION
-+++ b/src/python/pants/VERSION
-@@ -1 +1 @@
--2.21.0.dev6
-+2.21.0.dev6+Custom-Local
-"#,
-        )
-        .unwrap();
-        execute(
-            Command::new("git")
-                .args(["apply", "patch"])
-                .current_dir(clone_root_tmp.path()),
-        )
-        .unwrap();
-        let venv_root_tmp = create_tempdir().unwrap();
-        execute(
-            Command::new("./pants")
-                .arg("-V")
-                .env("PANTS_VENV_DIR_PREFIX", venv_root_tmp.path())
-                .current_dir(clone_root_tmp.path()),
-        )
-        .unwrap();
+    let dump_env = |quiet: Option<&str>| {
+        let mut command = Command::new(scie_pants_scie);
+        command
+            .arg("-V")
+            .env("SCIE_PANTS_DUMP_ENV", "1")
+            .env("PANTS_VERSION", "2.19.0")
+            .env("SCIE_BASE", &scie_base)
+            .env_remove("SCIE_PANTS_DOWNLOAD_RETRIES")
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        match quiet {
+            Some(quiet) => command.env("SCIE_PANTS_QUIET", quiet),
+            None => command.env_remove("SCIE_PANTS_QUIET"),
+        };
+        let output = execute(&mut command).unwrap();
+        assert!(decode_output(output.stdout)
+            .unwrap()
+            .lines()
+            .any(|line| line == "SCIE_PANTS_DOWNLOAD_RETRIES=5"));
+        decode_output(output.stderr).unwrap()
+    };
 
-        remove_dir(
-            clone_root_tmp
-                .path()
-                .join("src")
-                .join("rust")
-                .join("engine")
-                .join("target")
-                .as_path(),
-        )
-        .unwrap();
-        ensure_directory(clone_dir, true).unwrap();
-        rename(&clone_root_tmp.into_path(), pants_2_21_0_dev6_clone_dir).unwrap();
-        ensure_directory(venv_dir, true).unwrap();
-        rename(&venv_root_tmp.into_path(), pants_2_21_0_dev6_venv_dir).unwrap();
-    }
+    let stderr = dump_env(None);
+    assert!(
+        !stderr.contains(warning),
+        "STDERR unexpectedly contained '{warning}':\n{stderr}"
+    );
+    let stderr = dump_env(Some("0"));
+    assert!(
+        stderr.contains(warning),
+        "STDERR did not contain '{warning}':\n{stderr}"
+    );
+    assert_no_bindings(&scie_base);
+}
 
-    assert_stderr_output(
-        Command::new(scie_pants_scie)
+fn test_scie_pants_log(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_PANTS_LOG turns on the launcher's debug logging");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    let dump_env_stderr = |log: Option<&str>| {
+        let mut command = Command::new(scie_pants_scie);
+        command
             .arg("-V")
-            .env("PANTS_SOURCE", pants_2_21_0_dev6_clone_dir)
-            .env("SCIE_PANTS_TEST_MODE", "PANTS_SOURCE mode")
-            .env("PANTS_VENV_DIR_PREFIX", pants_2_21_0_dev6_venv_dir),
-        vec![
-            "The PANTS_SOURCE mode is working.",
-            "Pants from sources argv: --no-verify-config -V.",
-        ],
-        ExpectedResult::Success,
+            .env("SCIE_PANTS_DUMP_ENV", "1")
+            .env("SCIE_BASE", &scie_base)
+            .env("SCIE_PANTS_LOG_COLOR", "never")
+            .env_remove("RUST_LOG")
+            .current_dir(&tmpdir)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        match log {
+            Some(log) => command.env("SCIE_PANTS_LOG", log),
+            None => command.env_remove("SCIE_PANTS_LOG"),
+        };
+        decode_output(execute(&mut command).unwrap().stderr).unwrap()
+    };
+
+    let timer_line = "TimerFinished] scie-pants::get_pants_process(), Elapsed=";
+    let stderr = dump_env_stderr(Some("trace"));
+    assert!(
+        stderr.contains(timer_line),
+        "STDERR did not contain '{timer_line}':\n{stderr}"
     );
+    let stderr = dump_env_stderr(None);
+    assert!(
+        !stderr.contains(timer_line),
+        "STDERR unexpectedly contained '{timer_line}':\n{stderr}"
+    );
+    assert_no_bindings(&scie_base);
 }
 
-fn test_pants_from_sources_mode(
-    scie_pants_scie: &Path,
-    pants_2_21_0_dev6_clone_dir: &Path,
-    pants_2_21_0_dev6_venv_dir: &Path,
-) {
-    integration_test!("Verify pants_from_sources mode.");
-    let side_by_side_root = create_tempdir().unwrap();
-    let pants_dir = side_by_side_root.path().join("pants");
-    softlink(pants_2_21_0_dev6_clone_dir, &pants_dir).unwrap();
-    let user_repo_dir = side_by_side_root.path().join("user-repo");
-    ensure_directory(&user_repo_dir, true).unwrap();
-    touch(user_repo_dir.join("pants.toml").as_path()).unwrap();
-    touch(user_repo_dir.join("BUILD_ROOT").as_path()).unwrap();
+fn test_pants_sha(scie_pants_scie: &Path) {
+    integration_test!("Verifying PANTS_SHA selects Pants built at a commit of the Pants repo");
 
-    let pants_from_sources = side_by_side_root.path().join("pants_from_sources");
-    softlink(scie_pants_scie, &pants_from_sources).unwrap();
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+    let sha = "1A2B3C4D5E6F1A2B3C4D5E6F1A2B3C4D5E6F1A2B";
+
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_PANTS_DUMP_ENV", "1")
+            .env("PANTS_SHA", sha)
+            .env_remove("PANTS_VERSION")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    let stdout = decode_output(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    let expected = format!("PANTS_SHA={sha}", sha = sha.to_ascii_lowercase());
+    assert!(
+        lines.contains(&expected.as_str()),
+        "Expected the line {expected:?} in:\n{stdout}"
+    );
+    assert!(
+        !lines.iter().any(|line| line.starts_with("PANTS_VERSION=")),
+        "Expected no PANTS_VERSION in:\n{stdout}"
+    );
 
     assert_stderr_output(
-        Command::new(pants_from_sources)
+        Command::new(scie_pants_scie)
             .arg("-V")
-            .env("SCIE_PANTS_TEST_MODE", "pants_from_sources mode")
-            .env("PANTS_VENV_DIR_PREFIX", pants_2_21_0_dev6_venv_dir)
-            .current_dir(user_repo_dir),
+            .env("PANTS_SHA", sha)
+            .env("PANTS_VERSION", "2.18.0")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir),
         vec![
-            "The pants_from_sources mode is working.",
-            "Pants from sources argv: --no-verify-config -V.",
+            "PANTS_VERSION=2.18.0 and PANTS_SHA=",
+            "are mutually exclusive",
         ],
-        ExpectedResult::Success,
+        ExpectedResult::Failure,
+    );
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_SHA", "1a2b3c4d")
+            .env_remove("PANTS_VERSION")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir),
+        vec!["PANTS_SHA=1a2b3c4d must be a full 40 character git commit sha of the Pants repo."],
+        ExpectedResult::Failure,
     );
+    assert_no_bindings(&scie_base);
 }
 
-fn test_delegate_pants_in_pants_repo(
-    scie_pants_scie: &Path,
-    pants_2_21_0_dev6_clone_dir: &PathBuf,
-) {
-    integration_test!("Verify delegating to `./pants`.");
+fn test_offline(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_PANTS_OFFLINE=1 fails fast instead of downloading");
+
+    let tmpdir = create_tempdir().unwrap();
+    touch(&tmpdir.path().join("pants.toml")).unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
     assert_stderr_output(
         Command::new(scie_pants_scie)
             .arg("-V")
-            .env("SCIE_PANTS_TEST_MODE", "delegate_bootstrap mode")
-            .current_dir(pants_2_21_0_dev6_clone_dir),
+            .env("SCIE_PANTS_OFFLINE", "1")
+            .env_remove("PANTS_VERSION")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir)
+            .stdin(Stdio::null()),
         vec![
-            "The delegate_bootstrap mode is working.",
-            "Pants from sources argv: -V.",
+            "No Pants version is configured and SCIE_PANTS_OFFLINE=1 forbids looking up the \
+            latest Pants release to use.",
         ],
-        ExpectedResult::Success,
+        ExpectedResult::Failure,
     );
+    assert_no_bindings(&scie_base);
 }
 
-fn test_use_pants_release_in_pants_repo(
-    scie_pants_scie: &Path,
-    pants_2_21_0_dev6_clone_dir: &PathBuf,
-) {
-    let pants_release = "2.21.0.dev4";
-    integration_test!("Verify usage of Pants {pants_release} on the pants repo.");
-    let (output, stderr) = assert_stderr_output(
+fn test_debugpy_version_range(scie_pants_scie: &Path) {
+    integration_test!("Verifying a `[debugpy] version` range is passed on as a requirement");
+
+    let tmpdir = create_tempdir().unwrap();
+    let range_project = tmpdir.path().join("range");
+    write_file(
+        &range_project.join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+
+        [debugpy]
+        version = { min = "1.6.0", max = "1.8.0" }
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+    let output = execute(
         Command::new(scie_pants_scie)
-            .arg("help")
-            .env("PANTS_VERSION", pants_release)
-            .env(
-                "PANTS_BACKEND_PACKAGES",
-                "-[\
-                    'internal_plugins.test_lockfile_fixtures',\
-                    'pants_explorer.server',\
-                    ]",
-            )
-            .current_dir(pants_2_21_0_dev6_clone_dir)
+            .arg("-V")
+            .env("SCIE_PANTS_DUMP_ENV", "1")
+            .env("PANTS_DEBUG", "1")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&range_project)
             .stdout(Stdio::piped()),
-        vec![],
-        ExpectedResult::Success,
-    );
-    let expected_message = pants_release;
+    )
+    .unwrap();
     let stdout = decode_output(output.stdout).unwrap();
+    let expected = "PANTS_DEBUGPY_VERSION=debugpy>=1.6.0,<=1.8.0";
     assert!(
-        stdout.contains(expected_message),
-        "STDOUT did not contain '{expected_message}':\n{stdout}"
+        stdout.lines().any(|line| line == expected),
+        "Expected the line {expected:?} in:\n{stdout}"
     );
-    let unexpected_message = "Pants from sources argv";
-    assert!(
-        !stderr.contains(unexpected_message),
-        "STDERR unexpectedly contained '{unexpected_message}':\n{stderr}"
+
+    let empty_range_project = tmpdir.path().join("empty-range");
+    write_file(
+        &empty_range_project.join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+
+        [debugpy]
+        version = {}
+        "#,
+    )
+    .unwrap();
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&empty_range_project),
+        vec!["A `[debugpy] version` table must specify a `min` version, a `max` version or both."],
+        ExpectedResult::Failure,
     );
+    assert_no_bindings(&scie_base);
 }
 
-fn test_python38_used_for_old_pants(scie_pants_scie: &Path) {
-    integration_test!("Verifying Python 3.8 is selected for Pants older than 2.5.0");
-    let mut command = Command::new(scie_pants_scie);
-    command
-        .env("PANTS_VERSION", "1.30.5rc1")
-        .env(
-            "PANTS_BACKEND_PACKAGES",
-            "-[\
-                'pants.backend.python.typecheck.mypy',\
-                'pants.backend.shell',\
-                'pants.backend.shell.lint.shellcheck',\
-                'pants.backend.shell.lint.shfmt',\
-                ]",
+fn test_pants_debug_port(scie_pants_scie: &Path) {
+    integration_test!("Verifying PANTS_DEBUG can name the port for debugpy to listen on");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+    let dump_env = |pants_debug: &str| {
+        let output = execute(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("SCIE_PANTS_DUMP_ENV", "1")
+                .env("PANTS_DEBUG", pants_debug)
+                .env("SCIE_BASE", &scie_base)
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
         )
-        .args(["--no-verify-config", "--version"]);
-    if Platform::MacOSX86_64 == *CURRENT_PLATFORM {
-        // For unknown reasons, macOS x86_64 hangs in CI if this last test, like all prior tests
-        // nonetheless!, is run with pantsd enabled mode.
-        command.arg("--no-pantsd");
+        .unwrap();
+        decode_output(output.stdout).unwrap()
+    };
+    for (pants_debug, port) in [("5679", "5679"), ("1", "5678")] {
+        let stdout = dump_env(pants_debug);
+        let lines = stdout.lines().collect::<Vec<_>>();
+        for expected in [
+            "SCIE_BOOT=pants-debug",
+            "PANTS_DEBUG=1",
+            &format!("PANTS_DEBUG_PORT={port}"),
+        ] {
+            assert!(
+                lines.contains(&expected),
+                "Expected the line {expected:?} for PANTS_DEBUG={pants_debug} in:\n{stdout}"
+            );
+        }
     }
-    execute(&mut command).unwrap();
-}
 
-fn test_self_update(scie_pants_scie: &Path) {
-    integration_test!("Verifying self update works");
-    // N.B.: There should never be a newer release in CI; so this should always gracefully noop
-    // noting no newer release was available.
-    execute(Command::new(scie_pants_scie).env("SCIE_BOOT", "update")).unwrap();
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_DEBUG", "please")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir),
+        vec![
+            "PANTS_DEBUG must be either a boolean, like `1` or `true`, or the port number for the \
+            debugpy server to listen on, like `5678`, got: `please`.",
+        ],
+        ExpectedResult::Failure,
+    );
+    assert_no_bindings(&scie_base);
 }
 
-fn test_self_downgrade(scie_pants_scie: &Path) {
+fn test_dump_env(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_PANTS_DUMP_ENV prints the env Pants would be launched with");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let pants_bootstrap = tmpdir.path().join(".pants.bootstrap");
+    write_file(
+        &pants_bootstrap,
+        false,
+        r#"
+        export FROM_PANTS_BOOTSTRAP="$(echo computed)"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_PANTS_DUMP_ENV", "1")
+            .env("PANTS_BIN_NAME", "./pants")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    let stdout = decode_output(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    for expected in [
+        "SCIE_BOOT=pants",
+        "PANTS_BIN_NAME=./pants",
+        "PANTS_VERSION=2.18.0",
+        &format!(
+            "PANTS_BUILDROOT_OVERRIDE={build_root}",
+            build_root = tmpdir.path().canonicalize().unwrap().display()
+        ),
+        &format!(
+            "# Exported by {pants_bootstrap}:",
+            pants_bootstrap = tmpdir
+                .path()
+                .canonicalize()
+                .unwrap()
+                .join(".pants.bootstrap")
+                .display()
+        ),
+        "FROM_PANTS_BOOTSTRAP=computed",
+    ] {
+        assert!(
+            lines.contains(&expected),
+            "Expected the line {expected:?} in:\n{stdout}"
+        );
+    }
+    assert_no_bindings(&scie_base);
+}
+
+fn test_no_system_python(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying SCIE_PANTS_NO_SYSTEM_PYTHON keeps Pants from searching for system Pythons"
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let dump_env = |no_system_python: &str| {
+        let output = execute(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("SCIE_PANTS_DUMP_ENV", "1")
+                .env("SCIE_PANTS_NO_SYSTEM_PYTHON", no_system_python)
+                .env("SCIE_BASE", tmpdir.path().join("scie-base"))
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
+        )
+        .unwrap();
+        decode_output(output.stdout).unwrap()
+    };
+
+    let expected = "PANTS_PYTHON_BOOTSTRAP_SEARCH_PATH=[]";
+    let stdout = dump_env("1");
+    assert!(
+        stdout.lines().any(|line| line == expected),
+        "Expected the line {expected:?} in:\n{stdout}"
+    );
+    let stdout = dump_env("0");
+    assert!(
+        !stdout.contains("PANTS_PYTHON_BOOTSTRAP_SEARCH_PATH="),
+        "Expected no PANTS_PYTHON_BOOTSTRAP_SEARCH_PATH in:\n{stdout}"
+    );
+}
+
+fn test_pants_bootstrap_fragments(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying .pants.bootstrap.d/*.sh fragments are sourced after .pants.bootstrap"
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+    let build_root = tmpdir.path().canonicalize().unwrap();
+    write_file(
+        &build_root.join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    write_file(
+        &build_root.join(".pants.bootstrap"),
+        false,
+        "export FROM_MAIN=main FRAGMENT_VAR=main\n",
+    )
+    .unwrap();
+    let fragments_dir = build_root.join(".pants.bootstrap.d");
+    // N.B.: We write the fragments out of order to ensure they're sourced in lexical order.
+    write_file(
+        &fragments_dir.join("20-second.sh"),
+        false,
+        "export FRAGMENT_VAR=second\n",
+    )
+    .unwrap();
+    write_file(
+        &fragments_dir.join("10-first.sh"),
+        false,
+        "export FRAGMENT_VAR=first FROM_FIRST=\"${FRAGMENT_VAR}\"\n",
+    )
+    .unwrap();
+    write_file(
+        &fragments_dir.join("README.md"),
+        false,
+        "Only *.sh fragments are sourced.\n",
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    let dump_env = || {
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_PANTS_DUMP_ENV", "1")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&build_root)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap()
+            .wait_with_output()
+            .unwrap()
+    };
+
+    let output = dump_env();
+    assert!(output.status.success());
+    let stdout = decode_output(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    for expected in [
+        format!(
+            "# Exported by {main}, {first}, {second}:",
+            main = build_root.join(".pants.bootstrap").display(),
+            first = fragments_dir.join("10-first.sh").display(),
+            second = fragments_dir.join("20-second.sh").display()
+        )
+        .as_str(),
+        "FROM_MAIN=main",
+        "FROM_FIRST=main",
+        "FRAGMENT_VAR=second",
+    ] {
+        assert!(
+            lines.contains(&expected),
+            "Expected the line {expected:?} in:\n{stdout}"
+        );
+    }
+
+    let bad_fragment = fragments_dir.join("30-bad.sh");
+    write_file(&bad_fragment, false, "if then\n").unwrap();
+    let output = dump_env();
+    assert!(!output.status.success());
+    let stderr = decode_output(output.stderr).unwrap();
+    let expected_message = format!("Failed to source {path}.", path = bad_fragment.display());
+    assert!(
+        stderr.contains(&expected_message),
+        "STDERR did not contain '{expected_message}':\n{stderr}"
+    );
+    assert_no_bindings(&scie_base);
+}
+
+fn test_static_pants_bootstrap_cache(scie_pants_scie: &Path) {
+    integration_test!("Verifying the env exported by a static .pants.bootstrap is cached");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let sourced = tmpdir.path().join("sourced");
+    let pants_bootstrap = tmpdir.path().join(".pants.bootstrap");
+    write_file(
+        &pants_bootstrap,
+        false,
+        format!(
+            "# scie-pants: static-env\necho >> {sourced}\nexport FROM_PANTS_BOOTSTRAP=1\n",
+            sourced = sourced.display()
+        ),
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    let dump_env = || {
+        let output = execute(
+            Command::new(scie_pants_scie)
+                .env("SCIE_PANTS_DUMP_ENV", "1")
+                .env("SCIE_BASE", &scie_base)
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
+        )
+        .unwrap();
+        let stdout = decode_output(output.stdout).unwrap();
+        assert!(
+            stdout.lines().any(|line| line == "FROM_PANTS_BOOTSTRAP=1"),
+            "Expected the .pants.bootstrap env:\n{stdout}"
+        );
+        std::fs::read_to_string(&sourced).unwrap().lines().count()
+    };
+    assert_eq!(1, dump_env());
+    assert_eq!(1, dump_env(), "Expected the cached env to be used.");
+
+    write_file(&pants_bootstrap, true, "export ANOTHER=1\n").unwrap();
+    assert_eq!(
+        2,
+        dump_env(),
+        "Expected a changed .pants.bootstrap to be sourced."
+    );
+}
+
+// N.B.: The scie-jump installs bindings under `<SCIE_BASE>/<lift hash>/bindings`.
+fn assert_no_bindings(scie_base: &Path) {
+    let Ok(entries) = std::fs::read_dir(scie_base) else {
+        return;
+    };
+    for entry in entries {
+        let bindings = entry.unwrap().path().join("bindings");
+        assert!(
+            !bindings.exists(),
+            "Expected no bindings to be installed but found {bindings}",
+            bindings = bindings.display()
+        );
+    }
+}
+
+fn test_pants_buildroot_markers(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying PANTS_BUILDROOT_MARKERS controls where the build root search halts"
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+    let pants_toml = tmpdir.path().join("pants.toml");
+    write_file(
+        &pants_toml,
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let nested = tmpdir.path().join("nested");
+    write_file(&nested.join("PROJECT_ROOT"), false, "").unwrap();
+    let cwd = nested.join("subdir");
+    ensure_directory(&cwd, false).unwrap();
+
+    let build_root = |markers: Option<&str>| -> PathBuf {
+        let mut command = Command::new(scie_pants_scie);
+        command
+            .arg("-V")
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .env("PANTS_TOML", &pants_toml)
+            .current_dir(&cwd)
+            .stdout(Stdio::piped());
+        if let Some(markers) = markers {
+            command.env("PANTS_BUILDROOT_MARKERS", markers);
+        }
+        let output = execute(&mut command).unwrap();
+        let resolution: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        PathBuf::from(resolution["build_root"].as_str().unwrap())
+    };
+
+    let tmpdir_root = tmpdir.path().canonicalize().unwrap();
+    let nested_root = nested.canonicalize().unwrap();
+    assert_eq!(tmpdir_root, build_root(None));
+    assert_eq!(tmpdir_root, build_root(Some("")));
+    assert_eq!(nested_root, build_root(Some("PROJECT_ROOT")));
+    assert_eq!(nested_root, build_root(Some("+PROJECT_ROOT")));
+    assert_eq!(tmpdir_root, build_root(Some("BUILD_ROOT, pants.toml")));
+}
+
+fn test_pants_toml_directory(scie_pants_scie: &Path) {
+    integration_test!("Verifying a pants.toml directory is skipped as a build root marker");
+
+    let tmpdir = create_tempdir().unwrap();
+    let outer = tmpdir.path().join("outer");
+    write_file(
+        &outer.join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let inner = outer.join("inner");
+    std::fs::create_dir_all(inner.join("pants.toml")).unwrap();
+
+    let (output, _) = assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .current_dir(&inner)
+            .stdout(Stdio::piped()),
+        vec!["since it is a directory and build root markers must be files."],
+        ExpectedResult::Success,
+    );
+    let resolution: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        serde_json::json!(outer.canonicalize().unwrap()),
+        resolution["build_root"]
+    );
+
+    let build_root = tmpdir.path().join("build-root");
+    write_file(&build_root.join("BUILDROOT"), false, "").unwrap();
+    std::fs::create_dir_all(build_root.join("pants.toml")).unwrap();
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .current_dir(&build_root),
+        vec![&format!(
+            "The path {path} is a directory, not a file.",
+            path = build_root
+                .canonicalize()
+                .unwrap()
+                .join("pants.toml")
+                .display()
+        )],
+        ExpectedResult::Failure,
+    );
+}
+
+fn test_pants_buildroot_override(scie_pants_scie: &Path) {
+    integration_test!("Verifying an explicit PANTS_BUILDROOT_OVERRIDE is respected");
+
+    let tmpdir = create_tempdir().unwrap();
+    let build_root = tmpdir.path().join("build-root");
+    write_file(
+        &build_root.join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let elsewhere = tmpdir.path().join("elsewhere");
+    ensure_directory(&elsewhere, false).unwrap();
+
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .env("PANTS_BUILDROOT_OVERRIDE", &build_root)
+            .current_dir(&elsewhere)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    let resolution: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!("2.18.0", resolution["pants_version"]);
+    assert_eq!(
+        build_root.canonicalize().unwrap(),
+        PathBuf::from(resolution["build_root"].as_str().unwrap())
+    );
+
+    let missing = tmpdir.path().join("missing");
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_BUILDROOT_OVERRIDE", &missing)
+            .current_dir(&elsewhere),
+        vec![&format!(
+            "The PANTS_BUILDROOT_OVERRIDE env var is set to {missing} but that is not a directory.",
+            missing = missing.display()
+        )],
+        ExpectedResult::Failure,
+    );
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_BUILDROOT_OVERRIDE", &elsewhere)
+            .current_dir(&elsewhere),
+        vec![&format!(
+            "The PANTS_BUILDROOT_OVERRIDE env var is set to {elsewhere} but there is no \
+            pants.toml in that directory.",
+            elsewhere = elsewhere.display()
+        )],
+        ExpectedResult::Failure,
+    );
+}
+
+fn test_pants_version_env_var_validation(scie_pants_scie: &Path) {
+    integration_test!("Verifying the PANTS_VERSION env var is validated before launching Pants");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(&tmpdir.path().join("pants.toml"), false, "").unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    let explain = |pants_version: &str| -> Command {
+        let mut command = Command::new(scie_pants_scie);
+        command
+            .arg("-V")
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .env("PANTS_VERSION", pants_version)
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped());
+        command
+    };
+
+    for valid in [
+        "2.18.0",
+        "2.17.0a1",
+        "2.18.0rc1",
+        "2.25.0.dev1",
+        "1.30.5rc0",
+        "2.18.0+local",
+    ] {
+        let output = execute(&mut explain(valid)).unwrap();
+        let resolution: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(valid, resolution["pants_version"]);
+    }
+
+    for missing_patch in ["2.19", "2.19a1"] {
+        assert_stderr_output(
+            &mut explain(missing_patch),
+            vec![
+                &format!(
+                    "Pants version must be a full version, including patch level, got: \
+                    `{missing_patch}`."
+                ),
+                "Please add `.<patch_version>` to the end of the version.",
+            ],
+            ExpectedResult::Failure,
+        );
+    }
+
+    for malformed in ["2..18.0", "2.18.0x", "2.18.0.foo", "latest"] {
+        assert_stderr_output(
+            &mut explain(malformed),
+            vec![&format!(
+                "Pants version must be a full version of the form \
+                `<major>.<minor>.<patch_version>`, optionally followed by a pre-release or dev \
+                suffix, got: `{malformed}`."
+            )],
+            ExpectedResult::Failure,
+        );
+    }
+    assert_no_bindings(&scie_base);
+}
+
+fn test_pants_version_git_sha(scie_pants_scie: &Path) {
+    integration_test!("Verifying PANTS_VERSION=git+<sha> runs Pants from a cached clone");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(&tmpdir.path().join("pants.toml"), false, "").unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_VERSION", "git+202d921")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir),
+        vec![
+            "PANTS_VERSION=git+202d921 must name a full 40 character git commit sha of the Pants \
+            repo to run Pants from sources at.",
+        ],
+        ExpectedResult::Failure,
+    );
+
+    // N.B.: Explaining the resolution must not clone Pants.
+    let sha = "0123456789abcdef0123456789abcdef01234567";
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_VERSION", format!("git+{sha}"))
+            .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "pants_version": null,
+            "pants_sha": sha,
+            "source": "env",
+            "build_root": tmpdir.path().canonicalize().unwrap(),
+            "scie_boot": null,
+        }),
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    );
+    assert!(!scie_base
+        .join("scie-pants")
+        .join("pants-clones")
+        .join(sha)
+        .exists());
+
+    // N.B.: We seed the clone cache with a stand-in for a Pants clone to prove an existing clone is
+    // re-used instead of fetched.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        const SHA: &str = "202d9214866d9e67ec7242f1b202cbf5e1164fa5";
+        let clone_dir = scie_base.join("scie-pants").join("pants-clones").join(SHA);
+        write_file(
+            &clone_dir
+                .join("src")
+                .join("python")
+                .join("pants")
+                .join("VERSION"),
+            false,
+            "2.21.0.dev6\n",
+        )
+        .unwrap();
+        let pants = clone_dir.join("pants");
+        write_file(
+            &pants,
+            false,
+            "#!/bin/sh\necho \"The cached clone of ${PANTS_VERSION} is working.\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&pants, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let output = execute(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("PANTS_VERSION", format!("git+{SHA}"))
+                .env("SCIE_BASE", &scie_base)
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
+        )
+        .unwrap();
+        assert_eq!(
+            "The cached clone of 2.21.0.dev6 is working.",
+            decode_output(output.stdout).unwrap().trim()
+        );
+    }
+}
+
+fn test_download_retries_validation(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_PANTS_DOWNLOAD_RETRIES is validated before launching Pants");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    for invalid in ["-1", "three", "1.5"] {
+        assert_stderr_output(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("SCIE_PANTS_DOWNLOAD_RETRIES", invalid)
+                .env("SCIE_BASE", &scie_base)
+                .current_dir(&tmpdir),
+            vec![&format!(
+                "SCIE_PANTS_DOWNLOAD_RETRIES must be a non-negative integer number of times to \
+                retry failed downloads, got: `{invalid}`."
+            )],
+            ExpectedResult::Failure,
+        );
+    }
+    assert_no_bindings(&scie_base);
+}
+
+fn test_download_proxy(scie_pants_scie: &Path) {
+    integration_test!("Verifying bootstrap downloads go through the configured HTTPS_PROXY");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    // A fresh directory to ensure the downloads happen fresh.
+    let scie_base = tmpdir.path().join("scie-base");
+
+    let missing_ca_bundle = tmpdir.path().join("does-not-exist.pem");
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_PANTS_CA_BUNDLE", &missing_ca_bundle)
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir),
+        vec![&format!(
+            "SCIE_PANTS_CA_BUNDLE must be the path of a PEM file of CA certificates, but {path} \
+            is not a file.",
+            path = missing_ca_bundle.display()
+        )],
+        ExpectedResult::Failure,
+    );
+    assert_no_bindings(&scie_base);
+
+    // N.B.: Nothing listens on port 1; so the first download, of the CPython interpreter, fails
+    // trying to connect to the proxy.
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env_remove("https_proxy")
+            .env("HTTPS_PROXY", "http://127.0.0.1:1")
+            .env_remove("no_proxy")
+            .env_remove("NO_PROXY")
+            .env("SCIE_PANTS_DOWNLOAD_RETRIES", "0")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir),
+        vec!["127.0.0.1 port 1"],
+        ExpectedResult::Failure,
+    );
+}
+
+fn test_bootstrap_timeout(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS aborts a stalled bootstrap");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    for invalid in ["0", "-1", "soon"] {
+        assert_stderr_output(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS", invalid)
+                .env("SCIE_BASE", &scie_base)
+                .current_dir(&tmpdir),
+            vec![&format!(
+                "SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS must be a positive integer number of seconds, \
+                got: `{invalid}`."
+            )],
+            ExpectedResult::Failure,
+        );
+    }
+
+    // N.B.: A static .pants.bootstrap is sourced by scie-pants itself before launching Pants; so
+    // we use one that stalls to stand in for a stalled bootstrap. It closes its STDERR first so the
+    // orphaned script does not hold our STDERR pipe open once scie-pants gives up on it.
+    if cfg!(unix) {
+        write_file(
+            &tmpdir.path().join(".pants.bootstrap"),
+            false,
+            "# scie-pants: static-env\nexec 2>/dev/null\nsleep 60\n",
+        )
+        .unwrap();
+        assert_stderr_output(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS", "1")
+                .env("SCIE_BASE", &scie_base)
+                .current_dir(&tmpdir),
+            vec![
+                "Timed out bootstrapping Pants: it took longer than \
+                SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS=1 seconds.",
+            ],
+            ExpectedResult::Failure,
+        );
+    }
+    assert_no_bindings(&scie_base);
+}
+
+fn test_user_config_default_version(scie_pants_scie: &Path) {
+    integration_test!("Verifying the user config default_version is used when none is configured");
+
+    let tmpdir = create_tempdir().unwrap();
+    let write_user_config = |name: &str, content: &str| -> PathBuf {
+        let config_home = tmpdir.path().join(name);
+        write_file(
+            &config_home.join("pants").join("scie-pants.toml"),
+            false,
+            content,
+        )
+        .unwrap();
+        config_home
+    };
+    let valid_config_home = write_user_config("valid", r#"default_version = "2.18.0""#);
+    let malformed_config_home = write_user_config("malformed", "default_version = [");
+
+    let unconfigured_project = tmpdir.path().join("unconfigured");
+    write_file(&unconfigured_project.join("pants.toml"), false, "").unwrap();
+    let configured_project = tmpdir.path().join("configured");
+    write_file(
+        &configured_project.join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.19.0"
+        "#,
+    )
+    .unwrap();
+
+    let explain = |config_home: &Path, project: &Path| -> (serde_json::Value, String) {
+        let (output, stderr) = assert_stderr_output(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+                .env("XDG_CONFIG_HOME", config_home)
+                .env_remove("PANTS_VERSION")
+                .current_dir(project)
+                .stdout(Stdio::piped()),
+            vec![],
+            ExpectedResult::Success,
+        );
+        (serde_json::from_slice(&output.stdout).unwrap(), stderr)
+    };
+
+    let (resolution, _) = explain(&valid_config_home, &unconfigured_project);
+    assert_eq!("2.18.0", resolution["pants_version"]);
+    assert_eq!("user-config", resolution["source"]);
+
+    let (resolution, _) = explain(&valid_config_home, &configured_project);
+    assert_eq!("2.19.0", resolution["pants_version"]);
+    assert_eq!("config", resolution["source"]);
+
+    let (resolution, stderr) = explain(&malformed_config_home, &unconfigured_project);
+    assert_eq!(serde_json::Value::Null, resolution["pants_version"]);
+    assert_eq!("none", resolution["source"]);
+    assert!(
+        stderr.contains("Ignoring the scie-pants user config: Failed to parse"),
+        "STDERR did not warn about the malformed user config:\n{stderr}"
+    );
+}
+
+fn test_event_log(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_PANTS_EVENT_LOG records scie-pants decisions as JSON lines");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let event_log = tmpdir.path().join("events.jsonl");
+
+    // N.B.: We run twice to confirm the event log is appended to and not truncated.
+    for _ in 0..2 {
+        execute(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("SCIE_PANTS_EVENT_LOG", &event_log)
+                .env_remove("PANTS_VERSION")
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
+        )
+        .unwrap();
+    }
+
+    let events = std::fs::read_to_string(&event_log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    let names = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(["build-root", "version", "boot", "exec"].repeat(2), names);
+    assert_eq!(
+        tmpdir.path().canonicalize().unwrap(),
+        PathBuf::from(events[0]["build_root"].as_str().unwrap())
+    );
+    assert_eq!("2.18.0", events[1]["pants_version"]);
+    assert_eq!("config", events[1]["source"]);
+    assert_eq!("pants", events[2]["scie_boot"]);
+    assert_eq!(serde_json::json!(["-V"]), events[3]["args"]);
+    assert_ne!(events[0]["pid"], events[4]["pid"]);
+}
+
+fn test_scie_pants_version(scie_pants_scie: &Path) {
+    integration_test!("Verifying the scie-pants version can be reported without launching Pants");
+
+    let tmpdir = create_tempdir().unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+    let report = |command: &mut Command| -> String {
+        let output = execute(
+            command
+                .env("SCIE_BASE", &scie_base)
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
+        )
+        .unwrap();
+        decode_output(output.stdout).unwrap().trim().to_owned()
+    };
+
+    let expected = report(Command::new(scie_pants_scie).env("PANTS_BOOTSTRAP_VERSION", "report"));
+    assert_eq!(
+        expected,
+        report(Command::new(scie_pants_scie).env("SCIE_BOOT", "version"))
+    );
+    assert_eq!(
+        expected,
+        report(Command::new(scie_pants_scie).arg("--scie-pants-version"))
+    );
+    assert_no_bindings(&scie_base);
+
+    // The flag is passed through to Pants when it isn't the sole argument.
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .args(["--scie-pants-version", "-V"])
+            .env_remove("PANTS_VERSION")
+            .current_dir(&tmpdir),
+        vec!["Unknown flag --scie-pants-version"],
+        ExpectedResult::Failure,
+    );
+}
+
+fn test_doctor(scie_pants_scie: &Path) {
+    integration_test!("Verifying SCIE_BOOT=doctor diagnoses the scie-pants installation");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+
+    // Ensure Pants is installed so all the checks pass.
+    execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env_remove("PANTS_VERSION")
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .env("SCIE_BOOT", "doctor")
+            .env_remove("PANTS_VERSION")
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    let stdout = decode_output(output.stdout).unwrap();
+    for expected in [
+        "PASS SCIE base directory: ",
+        "PASS tools.pex: ",
+        &format!(
+            "PASS Pants build root: {build_root}",
+            build_root = tmpdir.path().canonicalize().unwrap().display()
+        ),
+        "PASS Pants version: 2.18.0 (via `[GLOBAL] pants_version`)",
+        "PASS Pants native client: ",
+    ] {
+        assert!(
+            stdout.contains(expected),
+            "STDOUT did not contain '{expected}':\n{stdout}"
+        );
+    }
+
+    let no_project = create_tempdir().unwrap();
+    let output = Command::new(scie_pants_scie)
+        .env("SCIE_BOOT", "doctor")
+        .env_remove("PANTS_VERSION")
+        .current_dir(&no_project)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap();
+    assert_eq!(Some(1), output.status.code());
+    let stdout = decode_output(output.stdout).unwrap();
+    assert!(
+        stdout.contains("FAIL Pants build root: "),
+        "STDOUT did not report the missing build root:\n{stdout}"
+    );
+}
+
+fn test_pants_from_pex_version(scie_pants_scie: &Path) {
+    integration_test!("Verify scie-pants can use Pants released as a 'local' PEX");
+
+    let tmpdir = create_tempdir().unwrap();
+
+    let pants_release = "2.18.0";
+    let pants_toml_content = format!(
+        r#"
+        [GLOBAL]
+        pants_version = "{pants_release}"
+        "#
+    );
+    let pants_toml = tmpdir.path().join("pants.toml");
+    write_file(&pants_toml, false, pants_toml_content).unwrap();
+
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+    );
+    let expected_message = pants_release;
+    let stdout = decode_output(output.unwrap().stdout).unwrap();
+    assert!(
+        stdout.contains(expected_message),
+        "STDOUT did not contain '{expected_message}':\n{stdout}"
+    );
+}
+
+fn test_pants_from_bad_pex_version(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verify the output of scie-pants is user-friendly if they provide an invalid pants version"
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+
+    let pants_release = "2.19";
+    let pants_toml_content = format!(
+        r#"
+        [GLOBAL]
+        pants_version = "{pants_release}"
+        "#
+    );
+    let pants_toml = tmpdir.path().join("pants.toml");
+    write_file(&pants_toml, false, pants_toml_content).unwrap();
+
+    // N.B.: The version is validated by scie-pants itself before launching anything; so we use a
+    // fresh SCIE_BASE to prove no bindings run.
+    let scie_base = tmpdir.path().join("scie-base");
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir),
+        vec![
+            "Error: Pants version must be a full version, including patch level, got: `2.19`.",
+            "Please add `.<patch_version>` to the end of the version. For example: `2.18` -> \
+            `2.18.0`.",
+        ],
+        ExpectedResult::Failure,
+    );
+    assert_no_bindings(&scie_base);
+}
+
+fn test_use_in_repo_with_pants_script(scie_pants_scie: &Path, clone_root: &TestTempDir) {
+    integration_test!("Verify scie-pants can be used as `pants` in a repo with the `pants` script");
+    // This verifies a fix for https://github.com/pantsbuild/scie-pants/issues/28.
+    execute(
+        Command::new("git")
+            .args(["clone", "https://github.com/pantsbuild/example-django"])
+            .current_dir(clone_root.path()),
+    )
+    .unwrap();
+
+    let django_dir = clone_root.path().join("example-django");
+    execute(
+        Command::new("git")
+            .args(["checkout", "ff20d1126b5d67b6a77f7d6a39f3063d1897ceb4"])
+            .current_dir(&django_dir),
+    )
+    .unwrap();
+
+    let bin_dir = clone_root.path().join("bin");
+    ensure_directory(&bin_dir, false).unwrap();
+    copy(scie_pants_scie, bin_dir.join("pants").as_path()).unwrap();
+    let new_path = if let Ok(existing_path) = env::var("PATH") {
+        format!(
+            "{bin_dir}{path_sep}{existing_path}",
+            bin_dir = bin_dir.display(),
+            path_sep = PATHSEP
+        )
+    } else {
+        format!("{bin_dir}", bin_dir = bin_dir.display())
+    };
+    execute(
+        Command::new("pants")
+            .arg("-V")
+            .env("PATH", new_path)
+            .current_dir(django_dir),
+    )
+    .unwrap();
+}
+
+fn test_dot_env_loading(scie_pants_scie: &Path, clone_root: &TestTempDir) {
+    integration_test!(
+        "Verify `.env` loading works (example-django should down grade to Pants 2.12.1)"
+    );
+    write_file(
+        &clone_root.path().join(".env"),
+        false,
+        "PANTS_VERSION=2.12.1",
+    )
+    .unwrap();
+    execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .current_dir(clone_root.path().join("example-django")),
+    )
+    .unwrap();
+}
+
+fn test_dot_env_error(scie_pants_scie: &Path) {
+    integration_test!("Verify `.env` loading emits errors if invalid");
+
+    let tempdir = create_tempdir().unwrap();
+    write_file(
+        &tempdir.path().join(".env"),
+        false,
+        "CABBAGE=cabbagee\ntotally invalid line\nPOTATO=potato",
+    )
+    .unwrap();
+
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .current_dir(tempdir.path()),
+        vec!["requested .env files be loaded but there was an error doing so: Parsing Error: Error { input: \"invalid line"],
+        ExpectedResult::Failure
+    );
+}
+
+fn test_dot_env_discovery(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verify the nearest `.env` up from a subdirectory of the build root is loaded"
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    write_file(&tmpdir.path().join(".env"), false, "PANTS_VERSION=2.19.1").unwrap();
+    let subdir = tmpdir.path().join("subdir");
+    let sub_subdir = subdir.join("sub-subdir");
+    ensure_directory(&sub_subdir, false).unwrap();
+
+    let explain = || -> serde_json::Value {
+        let output = execute(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("PANTS_BOOTSTRAP_EXPLAIN", "1")
+                .env_remove("PANTS_VERSION")
+                .current_dir(&sub_subdir)
+                .stdout(Stdio::piped()),
+        )
+        .unwrap();
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let resolution = explain();
+    assert_eq!("2.19.1", resolution["pants_version"]);
+    assert_eq!("dotenv", resolution["source"]);
+
+    // The search stops at the first `.env` found.
+    write_file(&subdir.join(".env"), false, "PANTS_VERSION=2.19.0").unwrap();
+    let resolution = explain();
+    assert_eq!("2.19.0", resolution["pants_version"]);
+    assert_eq!("dotenv", resolution["source"]);
+}
+
+fn test_pants_source_mode(
+    scie_pants_scie: &Path,
+    clone_dir: &Path,
+    pants_2_21_0_dev6_clone_dir: &Path,
+    venv_dir: &Path,
+    pants_2_21_0_dev6_venv_dir: &Path,
+) {
+    integration_test!("Verify PANTS_SOURCE mode.");
+    // NB. we assume that these directories are setup perfectly if they exist. A possible failure
+    // mode is the symlinks to python interpreters in the venv; if the system changes to make them
+    // invalid, we start getting errors like `${pants_2_21_0_dev6_venv_dir}/.../bin/python: No such file
+    // or directory`. This can occur in practice with cross-runner caching and the runner updating,
+    // but our cache key is designed to avoid this (see `build_it_cache_key` step in ci.yml).
+    if !pants_2_21_0_dev6_clone_dir.exists() || !pants_2_21_0_dev6_venv_dir.exists() {
+        let clone_root_tmp = create_tempdir().unwrap();
+        let clone_root_path = clone_root_tmp
+            .path()
+            .to_str()
+            .with_context(|| {
+                format!("Failed to convert clone root path to UTF-8 string: {clone_root_tmp:?}")
+            })
+            .unwrap();
+        execute(Command::new("git").args(["init", clone_root_path])).unwrap();
+        // N.B.: The release_2.21.0.dev6 tag has sha 202d9214866d9e67ec7242f1b202cbf5e1164fa5 and we
+        // must pass a full sha to use the shallow fetch trick.
+        const PANTS_2_21_0_DEV6_SHA: &str = "202d9214866d9e67ec7242f1b202cbf5e1164fa5";
+        execute(
+            Command::new("git")
+                .args([
+                    "fetch",
+                    "--depth",
+                    "1",
+                    "https://github.com/pantsbuild/pants",
+                    PANTS_2_21_0_DEV6_SHA,
+                ])
+                .current_dir(clone_root_tmp.path()),
+        )
+        .unwrap();
+        execute(
+            Command::new("git")
+                .args(["reset", "--hard", PANTS_2_21_0_DEV6_SHA])
+                .current_dir(clone_root_tmp.path()),
+        )
+        .unwrap();
+        write_file(
+            clone_root_tmp.path().join("patch").as_path(),
+            false,
+            r#"
+diff --git a/build-support/pants_venv b/build-support/pants_venv
+index 90fa82f6d3..e4f7e97a95 100755
+--- a/build-support/pants_venv
++++ b/build-support/pants_venv
+@@ -13,6 +13,8 @@ REQUIREMENTS=(
+
+ platform=$(uname -mps)
+
++echo >&2 "The ${SCIE_PANTS_TEST_MODE:-Pants 2.21.0.dev6 clone} is working."
++
+ function venv_dir() {
+   # Include the entire version string in order to differentiate e.g. PyPy from CPython.
+   # Fingerprinting uname and python output avoids shebang length limits and any odd chars.
+@@ -23,7 +25,7 @@ function venv_dir() {
+
+   # NB: We house these outside the working copy to avoid needing to gitignore them, but also to
+   # dodge https://github.com/hashicorp/vagrant/issues/12057.
+-  echo "${HOME}/.cache/pants/pants_dev_deps/${venv_fingerprint}.venv"
++  echo "${PANTS_VENV_DIR_PREFIX:-${HOME}/.cache/pants/pants_dev_deps}/${venv_fingerprint}.venv"
+ }
+
+ function activate_venv() {
+diff --git a/pants b/pants
+index ba49cc133f..870a35f028 100755
+--- a/pants
++++ b/pants
+@@ -76,4 +76,5 @@ function exec_pants_bare() {
+     exec ${PANTS_PREPEND_ARGS:-} "$(venv_dir)/bin/python" ${DEBUG_ARGS} "${PANTS_PY_EXE}" "$@"
+ }
+
++echo >&2 "Pants from sources argv: $@."
+ exec_pants_bare "$@"
+diff --git a/src/python/pants/VERSION b/src/python/pants/VERSION
+index 796b3cddd2..aef0e649bb 100644
+--- a/src/python/pants/VERSION
++++ b/src/python/pants/VERSION
+@@ -1 +1 @@
+-2.21.0.dev6
++2.21.0.dev6+Custom-Local
+"#,
+        )
+        .unwrap();
+        execute(
+            Command::new("git")
+                .args(["apply", "patch"])
+                .current_dir(clone_root_tmp.path()),
+        )
+        .unwrap();
+        let venv_root_tmp = create_tempdir().unwrap();
+        execute(
+            Command::new("./pants")
+                .arg("-V")
+                .env("PANTS_VENV_DIR_PREFIX", venv_root_tmp.path())
+                .current_dir(clone_root_tmp.path()),
+        )
+        .unwrap();
+
+        remove_dir(
+            clone_root_tmp
+                .path()
+                .join("src")
+                .join("rust")
+                .join("engine")
+                .join("target")
+                .as_path(),
+        )
+        .unwrap();
+        ensure_directory(clone_dir, true).unwrap();
+        rename(&clone_root_tmp.into_path(), pants_2_21_0_dev6_clone_dir).unwrap();
+        ensure_directory(venv_dir, true).unwrap();
+        rename(&venv_root_tmp.into_path(), pants_2_21_0_dev6_venv_dir).unwrap();
+    }
+
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_SOURCE", pants_2_21_0_dev6_clone_dir)
+            .env("SCIE_PANTS_TEST_MODE", "PANTS_SOURCE mode")
+            .env("PANTS_VENV_DIR_PREFIX", pants_2_21_0_dev6_venv_dir)
+            .env("RUST_LOG", "scie_pants=debug"),
+        vec![
+            "Launch mode: Pants from sources at ",
+            "(via the PANTS_SOURCE env var)",
+            "The PANTS_SOURCE mode is working.",
+            "Pants from sources argv: --no-verify-config -V.",
+        ],
+        ExpectedResult::Success,
+    );
+
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_SOURCE", pants_2_21_0_dev6_clone_dir)
+            .env("PANTS_SOURCE_EXTRA_ARGS", "--no-pantsd '--level=info'")
+            .env("SCIE_PANTS_TEST_MODE", "PANTS_SOURCE mode")
+            .env("PANTS_VENV_DIR_PREFIX", pants_2_21_0_dev6_venv_dir),
+        vec![
+            "The PANTS_SOURCE mode is working.",
+            "Pants from sources argv: --no-verify-config --no-pantsd --level=info -V.",
+        ],
+        ExpectedResult::Success,
+    );
+}
+
+fn test_pants_source_mode_ignores_pants_version(
+    scie_pants_scie: &Path,
+    pants_2_21_0_dev6_clone_dir: &Path,
+    pants_2_21_0_dev6_venv_dir: &Path,
+) {
+    integration_test!("Verify PANTS_SOURCE mode warns when PANTS_VERSION is also set.");
+    let expected_warning = "PANTS_SOURCE takes precedence; so PANTS_VERSION is being ignored";
+
+    let (_, stderr) = assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_SOURCE", pants_2_21_0_dev6_clone_dir)
+            .env("PANTS_VERSION", "2.18.0")
+            .env("SCIE_PANTS_TEST_MODE", "PANTS_SOURCE mode")
+            .env("PANTS_VENV_DIR_PREFIX", pants_2_21_0_dev6_venv_dir),
+        vec![
+            expected_warning,
+            "The PANTS_SOURCE mode is working.",
+            "Pants from sources argv: --no-verify-config -V.",
+        ],
+        ExpectedResult::Success,
+    );
+    assert_eq!(
+        1,
+        stderr.matches(expected_warning).count(),
+        "Expected exactly one warning in STDERR:\n{stderr}"
+    );
+
+    let (_, stderr) = assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_SOURCE", pants_2_21_0_dev6_clone_dir)
+            .env_remove("PANTS_VERSION")
+            .env("SCIE_PANTS_TEST_MODE", "PANTS_SOURCE mode")
+            .env("PANTS_VENV_DIR_PREFIX", pants_2_21_0_dev6_venv_dir),
+        vec!["The PANTS_SOURCE mode is working."],
+        ExpectedResult::Success,
+    );
+    assert!(
+        !stderr.contains(expected_warning),
+        "STDERR unexpectedly contained '{expected_warning}':\n{stderr}"
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let (_, stderr) = assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env_remove("PANTS_SOURCE")
+            .env("PANTS_VERSION", "2.18.0")
+            .current_dir(tmpdir.path()),
+        vec![],
+        ExpectedResult::Success,
+    );
+    assert!(
+        !stderr.contains(expected_warning),
+        "STDERR unexpectedly contained '{expected_warning}':\n{stderr}"
+    );
+}
+
+fn test_pants_from_sources_mode(
+    scie_pants_scie: &Path,
+    pants_2_21_0_dev6_clone_dir: &Path,
+    pants_2_21_0_dev6_venv_dir: &Path,
+) {
+    integration_test!("Verify pants_from_sources mode.");
+    let side_by_side_root = create_tempdir().unwrap();
+    let pants_dir = side_by_side_root.path().join("pants");
+    softlink(pants_2_21_0_dev6_clone_dir, &pants_dir).unwrap();
+    let user_repo_dir = side_by_side_root.path().join("user-repo");
+    ensure_directory(&user_repo_dir, true).unwrap();
+    touch(user_repo_dir.join("pants.toml").as_path()).unwrap();
+    touch(user_repo_dir.join("BUILD_ROOT").as_path()).unwrap();
+
+    let pants_from_sources = side_by_side_root.path().join("pants_from_sources");
+    softlink(scie_pants_scie, &pants_from_sources).unwrap();
+
+    assert_stderr_output(
+        Command::new(pants_from_sources)
+            .arg("-V")
+            .env("SCIE_PANTS_TEST_MODE", "pants_from_sources mode")
+            .env("PANTS_VENV_DIR_PREFIX", pants_2_21_0_dev6_venv_dir)
+            .env("RUST_LOG", "scie_pants=debug")
+            .current_dir(user_repo_dir),
+        vec![
+            "Launch mode: Pants from sources at ../pants (via invocation as `pants_from_sources`)",
+            "The pants_from_sources mode is working.",
+            "Pants from sources argv: --no-verify-config -V.",
+        ],
+        ExpectedResult::Success,
+    );
+}
+
+fn test_delegate_pants_in_pants_repo(
+    scie_pants_scie: &Path,
+    pants_2_21_0_dev6_clone_dir: &PathBuf,
+) {
+    integration_test!("Verify delegating to `./pants`.");
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_PANTS_TEST_MODE", "delegate_bootstrap mode")
+            .env("RUST_LOG", "scie_pants=debug")
+            .current_dir(pants_2_21_0_dev6_clone_dir),
+        vec![
+            "Launch mode: delegating to ",
+            "(via `[DEFAULT] delegate_bootstrap` in the Pants config with no Pants version set)",
+            "The delegate_bootstrap mode is working.",
+            "Pants from sources argv: -V.",
+        ],
+        ExpectedResult::Success,
+    );
+}
+
+fn test_use_pants_release_in_pants_repo(
+    scie_pants_scie: &Path,
+    pants_2_21_0_dev6_clone_dir: &PathBuf,
+) {
+    let pants_release = "2.21.0.dev4";
+    integration_test!("Verify usage of Pants {pants_release} on the pants repo.");
+    let (output, stderr) = assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("help")
+            .env("PANTS_VERSION", pants_release)
+            .env(
+                "PANTS_BACKEND_PACKAGES",
+                "-[\
+                    'internal_plugins.test_lockfile_fixtures',\
+                    'pants_explorer.server',\
+                    ]",
+            )
+            .env("RUST_LOG", "scie_pants=debug")
+            .current_dir(pants_2_21_0_dev6_clone_dir)
+            .stdout(Stdio::piped()),
+        vec![&format!(
+            "Launch mode: Pants release {pants_release} (via the PANTS_VERSION env var)"
+        )],
+        ExpectedResult::Success,
+    );
+    let expected_message = pants_release;
+    let stdout = decode_output(output.stdout).unwrap();
+    assert!(
+        stdout.contains(expected_message),
+        "STDOUT did not contain '{expected_message}':\n{stdout}"
+    );
+    let unexpected_message = "Pants from sources argv";
+    assert!(
+        !stderr.contains(unexpected_message),
+        "STDERR unexpectedly contained '{unexpected_message}':\n{stderr}"
+    );
+}
+
+fn test_python38_used_for_old_pants(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying Python 3.8 is selected for Pants older than 2.5.0 and that Pants older than \
+            2.0.0 is warned about"
+    );
+    let mut command = Command::new(scie_pants_scie);
+    command
+        .env("PANTS_VERSION", "1.30.5rc1")
+        .env(
+            "PANTS_BACKEND_PACKAGES",
+            "-[\
+                'pants.backend.python.typecheck.mypy',\
+                'pants.backend.shell',\
+                'pants.backend.shell.lint.shellcheck',\
+                'pants.backend.shell.lint.shfmt',\
+                ]",
+        )
+        .args(["--no-verify-config", "--version"]);
+    if Platform::MacOSX86_64 == *CURRENT_PLATFORM {
+        // For unknown reasons, macOS x86_64 hangs in CI if this last test, like all prior tests
+        // nonetheless!, is run with pantsd enabled mode.
+        command.arg("--no-pantsd");
+    }
+    assert_stderr_output(
+        &mut command,
+        vec![
+            "Pants 1.30.5rc1 is end of life; Pants versions older than 2.0.0 are no longer \
+            supported.",
+        ],
+        ExpectedResult::Success,
+    );
+}
+
+fn test_self_update(scie_pants_scie: &Path) {
+    integration_test!("Verifying self update works");
+    // N.B.: There should never be a newer release in CI; so this should always gracefully noop
+    // noting no newer release was available.
+    execute(Command::new(scie_pants_scie).env("SCIE_BOOT", "update")).unwrap();
+}
+
+fn test_self_update_edge_channel(scie_pants_scie: &Path) {
+    integration_test!("Verifying self update works on the edge channel");
+    // N.B.: Unlike the stable channel, there may be a newer pre-release; so we update a copy.
+    let tmpdir = create_tempdir().unwrap();
+    let scie_pants = tmpdir.path().join(scie_pants_scie.file_name().unwrap());
+    copy(scie_pants_scie, &scie_pants).unwrap();
+    execute(
+        Command::new(&scie_pants)
+            .env("SCIE_BOOT", "update")
+            .env("SCIE_PANTS_UPDATE_CHANNEL", "edge"),
+    )
+    .unwrap();
+}
+
+fn test_self_downgrade(scie_pants_scie: &Path) {
     integration_test!("Verifying downgrade works");
     // Additionally, we exercise using a relative path to the scie-jump binary which triggered
     // https://github.com/pantsbuild/scie-pants/issues/38 in the past.
@@ -874,195 +3184,675 @@ fn test_self_downgrade(scie_pants_scie: &Path) {
     .unwrap();
 }
 
-fn test_caching_issue_129(scie_pants_scie: &Path) {
+fn test_caching_issue_129(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying the build root does not influence caching ({issue})",
+        issue = issue_link!(129)
+    );
+    let tmpdir = create_tempdir().unwrap();
+
+    let scie_base = tmpdir.path().join("nce");
+
+    let pants_toml = r#"
+    [GLOBAL]
+    pants_version = "2.18.0"
+    [anonymous-telemetry]
+    enabled = false
+    "#;
+
+    let one = tmpdir.path().join("one");
+    ensure_directory(&one, false).unwrap();
+    write_file(&one.join("pants.toml"), false, pants_toml).unwrap();
+    execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&one),
+    )
+    .unwrap();
+
+    let two = tmpdir.path().join("two");
+    ensure_directory(&two, false).unwrap();
+    write_file(&two.join("pants.toml"), false, pants_toml).unwrap();
+    execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&two),
+    )
+    .unwrap();
+
+    #[derive(Debug, Eq, PartialEq)]
+    enum LockType {
+        Configure,
+        Install,
+    }
+    let binding_locks = walkdir::WalkDir::new(scie_base)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Ok(dir_entry) => {
+                if !dir_entry.file_type().is_file() {
+                    return None;
+                }
+                if let Some(file_name) = dir_entry.file_name().to_str() {
+                    if let Some(parent_dir) = dir_entry.path().parent() {
+                        if let Some(parent_dir_name) = parent_dir.file_name() {
+                            if "locks" != parent_dir_name {
+                                return None;
+                            }
+                        }
+                        if !file_name.ends_with(".lck") {
+                            return None;
+                        }
+                        if file_name.starts_with("configure-") {
+                            return Some(LockType::Configure);
+                        }
+                        if file_name.starts_with("install-") {
+                            return Some(LockType::Install);
+                        }
+                    }
+                }
+                None
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec![LockType::Configure, LockType::Install], binding_locks)
+}
+
+fn test_custom_pants_toml_issue_153(scie_pants_scie: &Path) {
     integration_test!(
-        "Verifying the build root does not influence caching ({issue})",
-        issue = issue_link!(129)
+        "Verifying the PANTS_TOML env var is respected ({issue})",
+        issue = issue_link!(153)
     );
+
     let tmpdir = create_tempdir().unwrap();
 
-    let scie_base = tmpdir.path().join("nce");
+    let buildroot = tmpdir.path().join("buildroot");
+    touch(&buildroot.join("BUILD_ROOT")).unwrap();
 
-    let pants_toml = r#"
+    let pants_toml_content = r#"
     [GLOBAL]
-    pants_version = "2.18.0"
+    pants_version = "2.17.0.dev4"
+    backend_packages = ["pants.backend.python"]
     [anonymous-telemetry]
     enabled = false
     "#;
+    let pants_toml = tmpdir.path().join("elsewhere").join("pants.toml");
+    write_file(&pants_toml, false, pants_toml_content).unwrap();
+
+    let buildroot_subdir = buildroot.join("subdir");
+    ensure_directory(&buildroot_subdir, false).unwrap();
+
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_TOML", &pants_toml)
+            .env("PANTS_CONFIG_FILES", &pants_toml)
+            .current_dir(&buildroot_subdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    assert_eq!(
+        "2.17.0.dev4",
+        String::from_utf8(output.stdout.to_vec()).unwrap().trim()
+    );
+
+    let build_content = r#"
+python_requirement(name="cowsay", requirements=["cowsay==5.0"])
+pex_binary(name="moo", script="cowsay", dependencies=[":cowsay"])
+    "#;
+    write_file(&buildroot_subdir.join("BUILD"), false, build_content).unwrap();
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .args(["list", ":"])
+            .env("PANTS_TOML", &pants_toml)
+            .env("PANTS_CONFIG_FILES", &pants_toml)
+            .current_dir(&buildroot_subdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+
+    let expected_output = r#"
+subdir:cowsay
+subdir:moo
+    "#;
+    assert_eq!(
+        expected_output.trim(),
+        String::from_utf8(output.stdout.to_vec()).unwrap().trim()
+    );
+
+    let dot_env_content = format!(
+        r#"
+export PANTS_TOML={pants_toml}
+export PANTS_CONFIG_FILES=${{PANTS_TOML}}
+        "#,
+        pants_toml = pants_toml.display()
+    );
+    write_file(&buildroot.join(".env"), false, dot_env_content).unwrap();
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .args(["list", ":"])
+            .current_dir(&buildroot_subdir)
+            .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    assert_eq!(
+        expected_output.trim(),
+        String::from_utf8(output.stdout.to_vec()).unwrap().trim()
+    );
+}
+
+fn test_pants_native_client_perms_issue_182(scie_pants_scie: &Path) {
+    integration_test!(
+        "Verifying scie-pants sets executable perms on the Pants native client binary when \
+        present ({issue})",
+        issue = issue_link!(182)
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+
+    let pants_release = "2.17.0a1";
+    let pants_toml_content = format!(
+        r#"
+        [GLOBAL]
+        pants_version = "{pants_release}"
+        "#
+    );
+    let pants_toml = tmpdir.path().join("pants.toml");
+    write_file(&pants_toml, false, pants_toml_content).unwrap();
+
+    let output = execute(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
+    );
+    assert_eq!(
+        pants_release,
+        decode_output(output.unwrap().stdout).unwrap().trim()
+    );
+}
+
+#[cfg(target_os = "linux")]
+fn test_xdg_cache_home_scie_base(scie_pants_scie: &Path) {
+    integration_test!("Verifying Pants is installed under XDG_CACHE_HOME unless SCIE_BASE is set");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let xdg_cache_home = tmpdir.path().join("xdg-cache");
+    let dump_env = |scie_base: Option<&Path>| {
+        let mut command = Command::new(scie_pants_scie);
+        command
+            .arg("-V")
+            .env("SCIE_PANTS_DUMP_ENV", "1")
+            .env("XDG_CACHE_HOME", &xdg_cache_home)
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped());
+        match scie_base {
+            Some(scie_base) => command.env("SCIE_BASE", scie_base),
+            None => command.env_remove("SCIE_BASE"),
+        };
+        decode_output(execute(&mut command).unwrap().stdout).unwrap()
+    };
+
+    let stdout = dump_env(None);
+    let expected = format!(
+        "SCIE_BASE={scie_base}",
+        scie_base = xdg_cache_home.join("scie-pants").display()
+    );
+    assert!(
+        stdout.lines().any(|line| line == expected),
+        "Expected the line {expected:?} in:\n{stdout}"
+    );
+
+    let scie_base = tmpdir.path().join("scie-base");
+    let stdout = dump_env(Some(&scie_base));
+    assert!(
+        !stdout.lines().any(|line| line.starts_with("SCIE_BASE=")),
+        "Expected the explicit SCIE_BASE to be left alone:\n{stdout}"
+    );
+    assert_no_bindings(&scie_base);
+}
+
+#[cfg(unix)]
+fn test_non_utf8_pants_bootstrap_env(scie_pants_scie: &Path) {
+    integration_test!("Verifying non-UTF-8 values exported by .pants.bootstrap survive unchanged");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+
+    let assert_dump_env_contains = |expected_lines: &[&str]| {
+        let output = execute(
+            Command::new(scie_pants_scie)
+                .arg("-V")
+                .env("SCIE_PANTS_DUMP_ENV", "1")
+                .env("SCIE_BASE", &scie_base)
+                .current_dir(&tmpdir)
+                .stdout(Stdio::piped()),
+        )
+        .unwrap();
+        // N.B.: The env dump escapes bytes that aren't UTF-8; so `\xa5` here is the single byte 0xa5.
+        let stdout = decode_output(output.stdout).unwrap();
+        let lines = stdout.lines().collect::<Vec<_>>();
+        for expected in expected_lines {
+            assert!(
+                lines.contains(expected),
+                "Expected the line {expected:?} in:\n{stdout}"
+            );
+        }
+    };
+
+    let pants_bootstrap = tmpdir.path().join(".pants.bootstrap");
+    write_file(&pants_bootstrap, false, "export NON_UTF8=$'B\\xa5R'\n").unwrap();
+    assert_dump_env_contains(&[r"NON_UTF8=B\xa5R"]);
+
+    // A static .pants.bootstrap round-trips the value through the env cache too: the 1st run
+    // sources the script and caches its env and the 2nd loads it from the cache.
+    write_file(
+        &pants_bootstrap,
+        false,
+        "# scie-pants: static-env\nexport NON_UTF8=$'B\\xa5R'\n",
+    )
+    .unwrap();
+    assert_dump_env_contains(&[r"NON_UTF8=B\xa5R"]);
+    assert_dump_env_contains(&[r"NON_UTF8=B\xa5R"]);
+    assert_no_bindings(&scie_base);
+}
+
+#[cfg(unix)]
+fn test_pants_from_sources_symlink_chain(scie_pants_scie: &Path) {
+    use std::ffi::OsStr;
+    use std::os::unix::fs::PermissionsExt;
+
+    integration_test!(
+        "Verifying SCIE_PANTS_RESOLVE_ARGV0 honors a `pants_from_sources` link in a symlink chain"
+    );
+    let side_by_side_root = create_tempdir().unwrap();
+    let pants_dir = side_by_side_root.path().join("pants");
+    let version_dir = pants_dir.join("src").join("python").join("pants");
+    ensure_directory(&version_dir, true).unwrap();
+    write_file(
+        &version_dir.join("VERSION"),
+        false,
+        "2.21.0.dev6+Custom-Local",
+    )
+    .unwrap();
+    let fake_pants = pants_dir.join("pants");
+    write_file(
+        &fake_pants,
+        false,
+        "#!/bin/sh\necho \"Fake Pants from sources argv: $*\" >&2\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&fake_pants, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let user_repo_dir = side_by_side_root.path().join("user-repo");
+    ensure_directory(&user_repo_dir, true).unwrap();
+    touch(user_repo_dir.join("pants.toml").as_path()).unwrap();
+
+    // The chain is: bin/pants -> pants_from_sources -> scie-pants
+    let pants_from_sources = side_by_side_root.path().join("pants_from_sources");
+    softlink(scie_pants_scie, &pants_from_sources).unwrap();
+    let bin_dir = side_by_side_root.path().join("bin");
+    ensure_directory(&bin_dir, true).unwrap();
+    let pants = bin_dir.join("pants");
+    softlink(&pants_from_sources, &pants).unwrap();
+    let existing_path =
+        env::split_paths(&env::var_os("PATH").unwrap_or("".into())).collect::<Vec<_>>();
+    let path = env::join_paths(
+        [bin_dir.as_os_str()]
+            .into_iter()
+            .chain(existing_path.iter().map(|p| p.as_os_str())),
+    )
+    .unwrap();
+
+    for argv0 in [pants.as_os_str(), OsStr::new("pants")] {
+        assert_stderr_output(
+            Command::new(argv0)
+                .arg("-V")
+                .env("PATH", &path)
+                .env("SCIE_PANTS_RESOLVE_ARGV0", "1")
+                .env("RUST_LOG", "scie_pants=debug")
+                .current_dir(&user_repo_dir),
+            vec![
+                "Launch mode: Pants from sources at ../pants (via invocation as \
+                `pants_from_sources`)",
+                "Fake Pants from sources argv: --no-verify-config -V",
+            ],
+            ExpectedResult::Success,
+        );
+    }
+}
+
+#[cfg(unix)]
+fn test_unreadable_pants_toml(scie_pants_scie: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    integration_test!("Verifying an unreadable pants.toml is reported with a permissions hint");
+
+    let tmpdir = create_tempdir().unwrap();
+    let pants_toml = tmpdir.path().join("pants.toml");
+    write_file(
+        &pants_toml,
+        false,
+        r#"
+        [GLOBAL]
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    std::fs::set_permissions(&pants_toml, std::fs::Permissions::from_mode(0o000)).unwrap();
+    if std::fs::read(&pants_toml).is_ok() {
+        log!(
+            Color::Yellow,
+            "The current user can read files regardless of their mode (root?) => skipping"
+        );
+        return;
+    }
+
+    assert_stderr_output(
+        Command::new(scie_pants_scie).arg("-V").current_dir(&tmpdir),
+        vec![
+            "Failed to read Pants config from ",
+            &format!(
+                "Permission was denied reading {path}. Check that the file is owned by, or \
+                readable by, the current user",
+                path = pants_toml.canonicalize().unwrap().display()
+            ),
+        ],
+        ExpectedResult::Failure,
+    );
+}
+
+#[cfg(unix)]
+fn test_delegate_bootstrap_script(scie_pants_scie: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    integration_test!(
+        "Verifying `[DEFAULT] delegate_bootstrap` can name the script to delegate to"
+    );
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [DEFAULT]
+        delegate_bootstrap = "pants-fork"
+        "#,
+    )
+    .unwrap();
+    let scie_base = tmpdir.path().join("scie-base");
+    let script = tmpdir.path().canonicalize().unwrap().join("pants-fork");
 
-    let one = tmpdir.path().join("one");
-    ensure_directory(&one, false).unwrap();
-    write_file(&one.join("pants.toml"), false, pants_toml).unwrap();
-    execute(
+    assert_stderr_output(
         Command::new(scie_pants_scie)
             .arg("-V")
             .env("SCIE_BASE", &scie_base)
-            .current_dir(&one),
+            .current_dir(&tmpdir),
+        vec![&format!(
+            "The `[DEFAULT] delegate_bootstrap` script {script} does not exist.",
+            script = script.display()
+        )],
+        ExpectedResult::Failure,
+    );
+
+    write_file(
+        &script,
+        false,
+        r#"#!/bin/sh
+        echo "Delegated to pants-fork with: $*"
+        "#,
     )
     .unwrap();
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_BASE", &scie_base)
+            .current_dir(&tmpdir),
+        vec![&format!(
+            "The `[DEFAULT] delegate_bootstrap` script {script} is not executable.",
+            script = script.display()
+        )],
+        ExpectedResult::Failure,
+    );
 
-    let two = tmpdir.path().join("two");
-    ensure_directory(&two, false).unwrap();
-    write_file(&two.join("pants.toml"), false, pants_toml).unwrap();
-    execute(
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let output = execute(
         Command::new(scie_pants_scie)
             .arg("-V")
             .env("SCIE_BASE", &scie_base)
-            .current_dir(&two),
+            .current_dir(&tmpdir)
+            .stdout(Stdio::piped()),
     )
     .unwrap();
-
-    #[derive(Debug, Eq, PartialEq)]
-    enum LockType {
-        Configure,
-        Install,
-    }
-    let binding_locks = walkdir::WalkDir::new(scie_base)
-        .sort_by_file_name()
-        .into_iter()
-        .filter_map(|entry| match entry {
-            Ok(dir_entry) => {
-                if !dir_entry.file_type().is_file() {
-                    return None;
-                }
-                if let Some(file_name) = dir_entry.file_name().to_str() {
-                    if let Some(parent_dir) = dir_entry.path().parent() {
-                        if let Some(parent_dir_name) = parent_dir.file_name() {
-                            if "locks" != parent_dir_name {
-                                return None;
-                            }
-                        }
-                        if !file_name.ends_with(".lck") {
-                            return None;
-                        }
-                        if file_name.starts_with("configure-") {
-                            return Some(LockType::Configure);
-                        }
-                        if file_name.starts_with("install-") {
-                            return Some(LockType::Install);
-                        }
-                    }
-                }
-                None
-            }
-            _ => None,
-        })
-        .collect::<Vec<_>>();
-
-    assert_eq!(vec![LockType::Configure, LockType::Install], binding_locks)
-}
-
-fn test_custom_pants_toml_issue_153(scie_pants_scie: &Path) {
-    integration_test!(
-        "Verifying the PANTS_TOML env var is respected ({issue})",
-        issue = issue_link!(153)
+    assert_eq!(
+        "Delegated to pants-fork with: -V",
+        decode_output(output.stdout).unwrap().trim()
     );
+    assert_no_bindings(&scie_base);
+}
 
-    let tmpdir = create_tempdir().unwrap();
-
-    let buildroot = tmpdir.path().join("buildroot");
-    touch(&buildroot.join("BUILD_ROOT")).unwrap();
+#[cfg(unix)]
+fn test_env_allowlist(scie_pants_scie: &Path) {
+    use std::os::unix::fs::PermissionsExt;
 
-    let pants_toml_content = r#"
-    [GLOBAL]
-    pants_version = "2.17.0.dev4"
-    backend_packages = ["pants.backend.python"]
-    [anonymous-telemetry]
-    enabled = false
-    "#;
-    let pants_toml = tmpdir.path().join("elsewhere").join("pants.toml");
-    write_file(&pants_toml, false, pants_toml_content).unwrap();
+    integration_test!("Verifying SCIE_PANTS_ENV_ALLOWLIST scrubs the env Pants is launched with");
 
-    let buildroot_subdir = buildroot.join("subdir");
-    ensure_directory(&buildroot_subdir, false).unwrap();
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [DEFAULT]
+        delegate_bootstrap = true
+        "#,
+    )
+    .unwrap();
+    let script = tmpdir.path().join("pants");
+    write_file(
+        &script,
+        false,
+        r#"#!/bin/sh
+        echo "secret=${SCIE_PANTS_TEST_SECRET:-unset} kept=${SCIE_PANTS_TEST_KEPT:-unset}"
+        "#,
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
 
     let output = execute(
         Command::new(scie_pants_scie)
-            .arg("-V")
-            .env("PANTS_TOML", &pants_toml)
-            .env("PANTS_CONFIG_FILES", &pants_toml)
-            .current_dir(&buildroot_subdir)
+            .env("SCIE_BASE", tmpdir.path().join("scie-base"))
+            .env("SCIE_PANTS_ENV_ALLOWLIST", "PATH,SCIE_PANTS_TEST_KEPT")
+            .env("SCIE_PANTS_TEST_SECRET", "leaked")
+            .env("SCIE_PANTS_TEST_KEPT", "kept")
+            .current_dir(&tmpdir)
             .stdout(Stdio::piped()),
     )
     .unwrap();
     assert_eq!(
-        "2.17.0.dev4",
-        String::from_utf8(output.stdout.to_vec()).unwrap().trim()
+        "secret=unset kept=kept",
+        decode_output(output.stdout).unwrap().trim()
     );
+}
+
+#[cfg(unix)]
+fn test_prepend_args(scie_pants_scie: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    integration_test!("Verifying PANTS_PREPEND_ARGS are passed to Pants before the user's args");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
+        r#"
+        [DEFAULT]
+        delegate_bootstrap = true
+        "#,
+    )
+    .unwrap();
+    let script = tmpdir.path().join("pants");
+    write_file(
+        &script,
+        false,
+        r#"#!/bin/sh
+        for arg in "$@"; do echo "$arg"; done
+        "#,
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
 
-    let build_content = r#"
-python_requirement(name="cowsay", requirements=["cowsay==5.0"])
-pex_binary(name="moo", script="cowsay", dependencies=[":cowsay"])
-    "#;
-    write_file(&buildroot_subdir.join("BUILD"), false, build_content).unwrap();
     let output = execute(
         Command::new(scie_pants_scie)
-            .args(["list", ":"])
-            .env("PANTS_TOML", &pants_toml)
-            .env("PANTS_CONFIG_FILES", &pants_toml)
-            .current_dir(&buildroot_subdir)
+            .args(["--watch-filesystem", "list", "::"])
+            .env("SCIE_BASE", tmpdir.path().join("scie-base"))
+            .env("PANTS_PREPEND_ARGS", "--no-watch-filesystem --tag='a b'")
+            .current_dir(&tmpdir)
             .stdout(Stdio::piped()),
     )
     .unwrap();
-
-    let expected_output = r#"
-subdir:cowsay
-subdir:moo
-    "#;
     assert_eq!(
-        expected_output.trim(),
-        String::from_utf8(output.stdout.to_vec()).unwrap().trim()
+        vec![
+            "--no-watch-filesystem",
+            "--tag=a b",
+            "--watch-filesystem",
+            "list",
+            "::"
+        ],
+        decode_output(output.stdout)
+            .unwrap()
+            .lines()
+            .collect::<Vec<_>>()
     );
+}
 
-    let dot_env_content = format!(
+#[cfg(unix)]
+fn test_metrics_file(scie_pants_scie: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    integration_test!("Verifying SCIE_PANTS_METRICS_FILE records the launch metrics");
+
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
         r#"
-export PANTS_TOML={pants_toml}
-export PANTS_CONFIG_FILES=${{PANTS_TOML}}
+        [DEFAULT]
+        delegate_bootstrap = true
         "#,
-        pants_toml = pants_toml.display()
-    );
-    write_file(&buildroot.join(".env"), false, dot_env_content).unwrap();
-    let output = execute(
+    )
+    .unwrap();
+    let script = tmpdir.path().join("pants");
+    write_file(&script, false, "#!/bin/sh\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let metrics_file = tmpdir.path().join("metrics").join("scie-pants.json");
+    ensure_directory(metrics_file.parent().unwrap(), false).unwrap();
+    execute(
         Command::new(scie_pants_scie)
-            .args(["list", ":"])
-            .current_dir(&buildroot_subdir)
-            .stdout(Stdio::piped()),
+            .env("SCIE_BASE", tmpdir.path().join("scie-base"))
+            .env("SCIE_PANTS_METRICS_FILE", &metrics_file)
+            .current_dir(&tmpdir),
     )
     .unwrap();
-    assert_eq!(
-        expected_output.trim(),
-        String::from_utf8(output.stdout.to_vec()).unwrap().trim()
+    let metrics: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&metrics_file).unwrap()).unwrap();
+    assert_eq!("delegate", metrics["source"], "{metrics}");
+    assert!(
+        metrics["phases"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|phase| phase["phase"] == "build root discovery"),
+        "{metrics}"
     );
+    assert!(metrics["total_millis"].is_number(), "{metrics}");
 }
 
-fn test_pants_native_client_perms_issue_182(scie_pants_scie: &Path) {
-    integration_test!(
-        "Verifying scie-pants sets executable perms on the Pants native client binary when \
-        present ({issue})",
-        issue = issue_link!(182)
-    );
+#[cfg(unix)]
+fn test_scie_pants_bash(scie_pants_scie: &Path) {
+    use std::os::unix::fs::PermissionsExt;
 
-    let tmpdir = create_tempdir().unwrap();
+    integration_test!("Verifying SCIE_PANTS_BASH selects the bash used to source .pants.bootstrap");
 
-    let pants_release = "2.17.0a1";
-    let pants_toml_content = format!(
+    let tmpdir = create_tempdir().unwrap();
+    write_file(
+        &tmpdir.path().join("pants.toml"),
+        false,
         r#"
         [GLOBAL]
-        pants_version = "{pants_release}"
-        "#
-    );
-    let pants_toml = tmpdir.path().join("pants.toml");
-    write_file(&pants_toml, false, pants_toml_content).unwrap();
+        pants_version = "2.18.0"
+        "#,
+    )
+    .unwrap();
+    write_file(
+        &tmpdir.path().join(".pants.bootstrap"),
+        false,
+        "export FROM_PANTS_BOOTSTRAP=1",
+    )
+    .unwrap();
+
+    // N.B.: The custom bash records that it was used and then defers to the bash on the PATH.
+    let used = tmpdir.path().join("custom-bash-used");
+    let custom_bash = tmpdir.path().join("custom-bash");
+    write_file(
+        &custom_bash,
+        false,
+        format!(
+            "#!/bin/sh\ntouch {used}\nexec bash \"$@\"\n",
+            used = used.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&custom_bash, std::fs::Permissions::from_mode(0o755)).unwrap();
 
     let output = execute(
         Command::new(scie_pants_scie)
-            .arg("-V")
+            .env("SCIE_PANTS_BASH", &custom_bash)
+            .env("SCIE_PANTS_DUMP_ENV", "1")
             .current_dir(&tmpdir)
             .stdout(Stdio::piped()),
+    )
+    .unwrap();
+    let stdout = decode_output(output.stdout).unwrap();
+    assert!(
+        stdout.lines().any(|line| line == "FROM_PANTS_BOOTSTRAP=1"),
+        "Expected .pants.bootstrap to be sourced:\n{stdout}"
     );
-    assert_eq!(
-        pants_release,
-        decode_output(output.unwrap().stdout).unwrap().trim()
+    assert!(used.exists(), "Expected {custom_bash:?} to be used.");
+
+    let missing_bash = tmpdir.path().join("missing-bash");
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("SCIE_PANTS_BASH", &missing_bash)
+            .current_dir(&tmpdir),
+        vec![&format!(
+            "Failed to exec {missing_bash}.",
+            missing_bash = missing_bash.display()
+        )],
+        ExpectedResult::Failure,
     );
 }
 
@@ -1088,15 +3878,16 @@ fn test_non_utf8_env_vars_issue_198(scie_pants_scie: &Path) {
     use std::os::unix::ffi::OsStringExt;
     env::set_var("FOO", OsString::from_vec(vec![b'B', 0xa5, b'R']));
 
-    let err = execute(
-        Command::new(scie_pants_scie)
-            .arg("-V")
-            .env("RUST_LOG", "trace")
-            .stderr(Stdio::piped())
-            .current_dir(&tmpdir),
-    )
-    .unwrap_err();
-    let error_text = err.to_string();
+    // N.B.: We gather STDERR ourselves since `execute` only reports its tail on failure.
+    let output = Command::new(scie_pants_scie)
+        .arg("-V")
+        .env("RUST_LOG", "trace")
+        .stderr(Stdio::piped())
+        .current_dir(&tmpdir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let error_text = String::from_utf8_lossy(&output.stderr);
     // N.B.: This is a very hacky way to confirm the `scie-jump` is done processing env vars and has
     // exec'd the `scie-pants` native client; which then proceeds to choke on env vars in the same
     // way scie-jump <= 0.11.0 did using `env::vars()`.
@@ -1112,20 +3903,20 @@ fn test_non_utf8_env_vars_issue_198(scie_pants_scie: &Path) {
         .contains(r#"panicked at 'called `Result::unwrap()` on an `Err` value: "B\xA5R"'"#));
 
     // The error path we test below requires flowing through the pantsd path via PyNailgunClient.
-    let err = execute(
-        Command::new(scie_pants_scie)
-            .arg("--pantsd")
-            .arg("-V")
-            .env("PANTS_NO_NATIVE_CLIENT", "1")
-            .stderr(Stdio::piped())
-            .current_dir(&tmpdir),
-    )
-    .unwrap_err();
+    let output = Command::new(scie_pants_scie)
+        .arg("--pantsd")
+        .arg("-V")
+        .env("PANTS_NO_NATIVE_CLIENT", "1")
+        .stderr(Stdio::piped())
+        .current_dir(&tmpdir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
     // Here we're asking the native client to exit very early before it processed `env::vars()`; so
     // the execution makes it into Python code that calls
     // `PyNailgunClient(...).execute(command, args, modified_env)`. That's Rust code implementing a
     // Python extension object that also wrongly assumes utf8 when converting env vars.
-    assert!(err.to_string().contains(concat!(
+    assert!(String::from_utf8_lossy(&output.stderr).contains(concat!(
         r#"UnicodeEncodeError: 'utf-8' codec can't encode character '\udca5' in "#,
         "position 1: surrogates not allowed"
     )));
@@ -1155,6 +3946,8 @@ fn test_bad_boot_error_text(scie_pants_scie: &Path) {
             // the various boot commands we want users to know about
             "\n<default> ",
             "\nbootstrap-tools ",
+            "\ncache-key ",
+            "\ndoctor ",
             "\nupdate ",
         ],
         ExpectedResult::Failure,
@@ -1176,12 +3969,13 @@ fn test_pants_bootstrap_urls(scie_pants_scie: &Path) {
       issue = issue_link!(243)
     );
 
-    // This test runs in 4 parts:
+    // This test runs in 5 parts:
     //
     // 0. Setup tempdirs, common values etc.
     // 1. Verify interpreter download uses URL (by checking errors with a non-existent URL)
     // 2. The same, but for the Pants PEX
-    // 3. Verify that specifying valid URLs works too (no good if we're just succesfully failing)
+    // 3. Verify that a Pants PEX fetched from a valid URL is checked against a listed sha256
+    // 4. Verify that specifying valid URLs works too (no good if we're just succesfully failing)
 
     // Part 0: Setup
     let tmpdir = create_tempdir().unwrap();
@@ -1206,13 +4000,21 @@ fn test_pants_bootstrap_urls(scie_pants_scie: &Path) {
         .unwrap()
         .retain(|key, _| key == "ptex");
 
-    let write_urls_json =
-        |update_ptex_map: &dyn Fn(&mut serde_json::Map<String, serde_json::Value>)| {
+    let write_urls_json_with_digests =
+        |update_ptex_map: &dyn Fn(&mut serde_json::Map<String, serde_json::Value>),
+         digests: Option<serde_json::Value>| {
             let mut json = ptex_json.clone();
             // Transform the "ptex": {...} map as appropriate.
             update_ptex_map(json["ptex"].as_object_mut().unwrap());
+            if let Some(digests) = digests {
+                json["sha256"] = digests;
+            }
             write_file(&urls_json, false, serde_json::to_vec(&json).unwrap()).unwrap();
         };
+    let write_urls_json =
+        |update_ptex_map: &dyn Fn(&mut serde_json::Map<String, serde_json::Value>)| {
+            write_urls_json_with_digests(update_ptex_map, None)
+        };
 
     // Reference data for the Pants we'll try to install (NB. we have to force new-enough version of
     // Pants to install via PEXes, older versions go via PyPI which isn't managed by
@@ -1276,13 +4078,41 @@ fn test_pants_bootstrap_urls(scie_pants_scie: &Path) {
         ExpectedResult::Failure,
     );
 
-    // Part 3: Validate that we can bootstrap pants fully from these override URLs (by manually
-    // re-specifying the defaults)
-    write_urls_json(&|ptex_map| {
+    // Part 3: Validate that a Pants PEX fetched from a valid URL is rejected when it doesn't match
+    // the sha256 listed for it.
+    let insert_release_urls = |ptex_map: &mut serde_json::Map<String, serde_json::Value>| {
         for pex in &pexes {
             ptex_map.insert(pex.clone(), format!("https://github.com/pantsbuild/pants/releases/download/release_{pants_release}/{pex}").into());
         }
-    });
+    };
+    let wrong_sha256 = "0".repeat(64);
+    write_urls_json_with_digests(
+        &insert_release_urls,
+        Some(
+            pexes
+                .iter()
+                .map(|pex| (pex.clone(), wrong_sha256.clone().into()))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+        ),
+    );
+    assert_stderr_output(
+        Command::new(scie_pants_scie)
+            .arg("-V")
+            .env("PANTS_BOOTSTRAP_URLS", &urls_json)
+            .env("SCIE_BASE", tmpdir.path().join("scie-base-sha256"))
+            .env("PANTS_VERSION", pants_release),
+        vec![
+            "does not match the digest listed for it in the PANTS_BOOTSTRAP_URLS file.",
+            &format!("Expected sha256: {wrong_sha256}"),
+            "Actual sha256:   ",
+        ],
+        ExpectedResult::Failure,
+    );
+
+    // Part 4: Validate that we can bootstrap pants fully from these override URLs (by manually
+    // re-specifying the defaults)
+    write_urls_json(&insert_release_urls);
 
     let output = execute(command.stdout(Stdio::piped())).unwrap();
     let stdout = decode_output(output.stdout).unwrap();
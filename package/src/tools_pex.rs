@@ -1,23 +1,38 @@
 // Copyright 2023 Pants project contributors.
 // Licensed under the Apache License, Version 2.0 (see LICENSE).
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
-use std::io::Write;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use anyhow::Result;
-use termcolor::WriteColor;
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use termcolor::Color;
 
-use crate::build_step;
-use crate::utils::build::{BuildContext, Science};
-use crate::utils::exe::execute;
+use crate::utils::build::{fingerprint, BuildContext, Science};
+use crate::utils::exe::{execute_streaming, print_command};
 use crate::utils::fs::{base_name, copy, ensure_directory, hardlink, path_as_str};
+use crate::utils::os::EOL;
+use crate::{build_step, log};
+
+pub(crate) const DEFAULT_TOOLS_PYTHON_CONSTRAINT: &str = "CPython>=3.8,<3.12";
+
+#[derive(Clone)]
+pub(crate) struct ToolsPexOptions {
+    pub(crate) update_lock: bool,
+    pub(crate) update_lock_only: Vec<String>,
+    pub(crate) tools_lock: Option<PathBuf>,
+    pub(crate) update_fingerprint: bool,
+    pub(crate) jobs: Option<NonZeroUsize>,
+    pub(crate) python_constraint: String,
+}
 
 pub(crate) fn build_tools_pex(
     build_context: &BuildContext,
     science: &Science,
-    update_lock: bool,
+    options: ToolsPexOptions,
     dest_dir: &Path,
 ) -> Result<PathBuf> {
     build_step!("Executing science build of the `pbt` helper binary");
@@ -27,83 +42,148 @@ pub(crate) fn build_tools_pex(
     let pbt_exe = pbt_package_dir
         .join("pbt")
         .with_extension(env::consts::EXE_EXTENSION);
-    let pbt_manifest = build_context.package_crate_root.join("pbt.toml");
+    let pbt_manifest = build_context.lift_manifest(
+        &build_context.package_crate_root.join("pbt.toml"),
+        &build_context.cargo_output_root,
+    )?;
     let pbt_manifest_dst = pbt_package_dir.join("lift.toml");
     hardlink(&pbt_manifest, &pbt_manifest_dst)?;
 
-    execute(
+    execute_streaming(print_command(
         science
             .command()
             .args(["lift", "build"])
             .current_dir(&pbt_package_dir),
-    )?;
+    )?)?;
 
     let tools_path = build_context.workspace_root.join("tools");
-    let lock_path = tools_path.join("lock.json");
+    let lock_path = match options.tools_lock {
+        Some(ref tools_lock) if !tools_lock.is_file() => bail!(
+            "The --tools-lock {path} is not a lock file.",
+            path = tools_lock.display()
+        ),
+        Some(ref tools_lock) => {
+            build_step!(
+                "Using the pinned tools lock file {path}",
+                path = tools_lock.display()
+            );
+            tools_lock.clone()
+        }
+        None => tools_path.join("lock.json"),
+    };
     let lock = path_as_str(&lock_path)?;
     let requirements_path = tools_path.join("requirements.txt");
     let requirements = path_as_str(&requirements_path)?;
     let test_requirements_path = tools_path.join("test-requirements.txt");
     let test_requirements = path_as_str(&test_requirements_path)?;
-    let interpreter_constraints = ["--interpreter-constraint", "CPython>=3.8,<3.12"];
+    let interpreter_constraints = [
+        "--interpreter-constraint",
+        options.python_constraint.as_str(),
+    ];
+    let jobs = options
+        .jobs
+        .map(|jobs| vec!["--jobs".to_string(), jobs.to_string()])
+        .unwrap_or_default();
 
-    if update_lock {
+    if options.update_lock {
         build_step!("Updating the scie_jump tools lock file");
-        execute(
-            Command::new(&pbt_exe).args(
+        execute_streaming(print_command(
+            Command::new(&pbt_exe)
+                .args(
+                    [
+                        "pex3",
+                        "lock",
+                        "create",
+                        "--style",
+                        "universal",
+                        "--pip-version",
+                        "22.3",
+                        "--resolver-version",
+                        "pip-2020-resolver",
+                        "--no-build",
+                        "-o",
+                        lock,
+                        "--indent",
+                        "2",
+                        "-r",
+                        requirements,
+                        "-r",
+                        test_requirements,
+                    ]
+                    .iter()
+                    .chain(interpreter_constraints.iter()),
+                )
+                .args(&jobs),
+        )?)?;
+    } else if !options.update_lock_only.is_empty() {
+        build_step!(
+            "Updating {projects} in the scie_jump tools lock file",
+            projects = options.update_lock_only.join(", ")
+        );
+        let pins_before = pins(&lock_path)?;
+        execute_streaming(print_command(
+            Command::new(&pbt_exe)
+                .args(["pex3", "lock", "update", "--indent", "2"])
+                .args(
+                    options
+                        .update_lock_only
+                        .iter()
+                        .flat_map(|project| ["-p", project.as_str()]),
+                )
+                .args(&jobs)
+                .arg(lock),
+        )?)?;
+        let collateral = changed_pins(&pins_before, &pins(&lock_path)?, &options.update_lock_only);
+        if !collateral.is_empty() {
+            log!(
+                Color::Yellow,
+                "Updating {projects} also changed the pins of:{eol}{collateral}",
+                projects = options.update_lock_only.join(", "),
+                collateral = collateral
+                    .iter()
+                    .map(|(project, before, after)| format!("  {project}: {before} -> {after}"))
+                    .collect::<Vec<_>>()
+                    .join(EOL),
+                eol = EOL,
+            );
+        }
+    }
+
+    build_step!("Building the scie_pants `tools.pex`");
+    let tools_src_path = tools_path.join("src");
+    let tools_src = path_as_str(&tools_src_path)?;
+    let tools_pex_path = build_context.cargo_output_root.join("tools.pex");
+    let tools_pex = path_as_str(&tools_pex_path)?;
+    execute_streaming(print_command(
+        Command::new(&pbt_exe)
+            .args(
                 [
-                    "pex3",
-                    "lock",
-                    "create",
-                    "--style",
-                    "universal",
-                    "--pip-version",
-                    "22.3",
-                    "--resolver-version",
-                    "pip-2020-resolver",
-                    "--no-build",
-                    "-o",
+                    "pex",
+                    "--disable-cache",
+                    "--no-emit-warnings",
+                    "--lock",
                     lock,
-                    "--indent",
-                    "2",
                     "-r",
                     requirements,
-                    "-r",
-                    test_requirements,
+                    "-c",
+                    "conscript",
+                    "-o",
+                    tools_pex,
+                    "--venv",
+                    "prepend",
+                    "-D",
+                    tools_src,
                 ]
                 .iter()
                 .chain(interpreter_constraints.iter()),
-            ),
-        )?;
-    }
+            )
+            .args(&jobs),
+    )?)?;
 
-    build_step!("Building the scie_pants `tools.pex`");
-    let tools_src_path = tools_path.join("src");
-    let tools_src = path_as_str(&tools_src_path)?;
-    let tools_pex_path = build_context.cargo_output_root.join("tools.pex");
-    let tools_pex = path_as_str(&tools_pex_path)?;
-    execute(
-        Command::new(&pbt_exe).args(
-            [
-                "pex",
-                "--disable-cache",
-                "--no-emit-warnings",
-                "--lock",
-                lock,
-                "-r",
-                requirements,
-                "-c",
-                "conscript",
-                "-o",
-                tools_pex,
-                "--venv",
-                "prepend",
-                "-D",
-                tools_src,
-            ]
-            .iter()
-            .chain(interpreter_constraints.iter()),
-        ),
+    check_fingerprint(
+        &tools_path.join("tools.pex.sha256"),
+        &tools_pex_path,
+        options.update_fingerprint,
     )?;
 
     let tools_pex_dest = dest_dir.join(base_name(&tools_pex_path)?);
@@ -111,3 +191,179 @@ pub(crate) fn build_tools_pex(
     copy(&tools_pex_path, &tools_pex_dest)?;
     Ok(tools_pex_dest)
 }
+
+// N.B.: Pex stores normalized project names in locks; so we normalize the project names given to
+// `--update-lock-only`, which may also be full requirements like `cowsay==6.1`, to match.
+fn project_name(requirement: &str) -> String {
+    requirement
+        .split(|c: char| "<>=!~;[@ ".contains(c))
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+        .replace(['_', '.'], "-")
+}
+
+/// Returns the pinned versions in a Pex lock by project name.
+fn pins(lock_path: &Path) -> Result<BTreeMap<String, String>> {
+    let contents = std::fs::read_to_string(lock_path)
+        .with_context(|| format!("Failed to read {path}", path = lock_path.display()))?;
+    let lock: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse {path}", path = lock_path.display()))?;
+    Ok(lock["locked_resolves"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|resolve| {
+            resolve["locked_requirements"]
+                .as_array()
+                .into_iter()
+                .flatten()
+        })
+        .filter_map(|requirement| {
+            Some((
+                requirement["project_name"].as_str()?.to_owned(),
+                requirement["version"].as_str()?.to_owned(),
+            ))
+        })
+        .collect())
+}
+
+/// Returns the pins other than those of the `updated` projects that differ between the two locks
+/// as `(project, before, after)` triples; where a missing pin is reported as `-`.
+fn changed_pins(
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+    updated: &[String],
+) -> Vec<(String, String, String)> {
+    let updated = updated
+        .iter()
+        .map(|requirement| project_name(requirement))
+        .collect::<Vec<_>>();
+    let pin = |pins: &BTreeMap<String, String>, project: &str| {
+        pins.get(project).cloned().unwrap_or_else(|| "-".to_owned())
+    };
+    before
+        .keys()
+        .chain(after.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|project| !updated.contains(project))
+        .filter(|project| before.get(*project) != after.get(*project))
+        .map(|project| (project.clone(), pin(before, project), pin(after, project)))
+        .collect()
+}
+
+// N.B.: The expected fingerprint is optional; when present, it guards against the tools.pex
+// changing without anyone noticing, say due to a drifting `tools/lock.json`.
+fn check_fingerprint(
+    expected_fingerprint_file: &Path,
+    tools_pex: &Path,
+    update_fingerprint: bool,
+) -> Result<()> {
+    let actual = fingerprint(tools_pex)?;
+    if update_fingerprint {
+        build_step!(
+            "Recording the tools.pex fingerprint in {path}",
+            path = expected_fingerprint_file.display()
+        );
+        std::fs::write(
+            expected_fingerprint_file,
+            format!("{actual}  {name}\n", name = base_name(tools_pex)?),
+        )
+        .with_context(|| {
+            format!(
+                "Failed to write {path}",
+                path = expected_fingerprint_file.display()
+            )
+        })?;
+        return Ok(());
+    }
+    if !expected_fingerprint_file.is_file() {
+        return Ok(());
+    }
+
+    build_step!(
+        "Verifying the tools.pex fingerprint against {path}",
+        path = expected_fingerprint_file.display()
+    );
+    let contents = std::fs::read_to_string(expected_fingerprint_file).with_context(|| {
+        format!(
+            "Failed to read {path}",
+            path = expected_fingerprint_file.display()
+        )
+    })?;
+    let expected = contents.split_whitespace().next().unwrap_or_default();
+    if expected != actual {
+        bail!(
+            "The tools.pex built at {tools_pex} has fingerprint {actual} but {path} expects \
+            {expected}.{eol}\
+            If the change is expected, say because you updated the tools lock, re-run with \
+            --update-tools-pex-fingerprint to record the new fingerprint.",
+            tools_pex = tools_pex.display(),
+            path = expected_fingerprint_file.display(),
+            eol = EOL,
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{changed_pins, pins};
+
+    fn write_lock(path: &std::path::Path, pins: &[(&str, &str)]) {
+        let locked_requirements = pins
+            .iter()
+            .map(|(project, version)| {
+                serde_json::json!({"project_name": project, "version": version, "artifacts": []})
+            })
+            .collect::<Vec<_>>();
+        std::fs::write(
+            path,
+            serde_json::to_string_pretty(&serde_json::json!({
+                "locked_resolves": [{"locked_requirements": locked_requirements}]
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn update_lock_only() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let before = tmpdir.path().join("before.json");
+        write_lock(&before, &[("ansicolors", "1.1.8"), ("cowsay", "5.0")]);
+        let after = tmpdir.path().join("after.json");
+        write_lock(&after, &[("ansicolors", "1.1.8"), ("cowsay", "6.1")]);
+
+        assert_eq!(
+            BTreeMap::from([
+                ("ansicolors".to_owned(), "1.1.8".to_owned()),
+                ("cowsay".to_owned(), "5.0".to_owned())
+            ]),
+            pins(&before).unwrap()
+        );
+        assert!(changed_pins(
+            &pins(&before).unwrap(),
+            &pins(&after).unwrap(),
+            &["Cowsay>=6".to_owned()]
+        )
+        .is_empty());
+
+        write_lock(&after, &[("ansicolors", "1.1.9"), ("cowsay", "6.1")]);
+        assert_eq!(
+            vec![(
+                "ansicolors".to_owned(),
+                "1.1.8".to_owned(),
+                "1.1.9".to_owned()
+            )],
+            changed_pins(
+                &pins(&before).unwrap(),
+                &pins(&after).unwrap(),
+                &["cowsay".to_owned()]
+            )
+        );
+    }
+}
@@ -1,22 +1,25 @@
 // Copyright 2023 Pants project contributors.
 // Licensed under the Apache License, Version 2.0 (see LICENSE).
 
-use std::cell::Cell;
 use std::env;
 use std::fs::File;
-use std::io::Write;
+use std::io::{IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use log::info;
 use sha2::{Digest, Sha256};
-use termcolor::WriteColor;
+use walkdir::WalkDir;
 
-use crate::utils::exe::{binary_full_name, execute, prepare_exe};
-use crate::utils::fs::{copy, ensure_directory, path_as_str, rename};
+use crate::utils::exe::{
+    binary_full_name, execute, execute_streaming, prepare_exe, Platform, CURRENT_PLATFORM,
+};
+use crate::utils::fs::{base_name, copy, ensure_directory, path_as_str, rename, write_file};
 use crate::utils::os::PATHSEP;
-use crate::{build_step, BINARY, SCIENCE_TAG};
+use crate::{build_step, log, BINARY, SCIENCE_TAG};
 
 const CARGO: &str = env!("CARGO");
 const CARGO_MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
@@ -48,18 +51,108 @@ pub(crate) fn check_sha256(path: &Path) -> Result<()> {
             sha256_file = sha256_file.display()
         )
     })?;
-    assert_eq!(expected_sha256, fingerprint(path)?.as_str());
+    let actual_sha256 = fingerprint(path)?;
+    if expected_sha256 != actual_sha256 {
+        bail!(
+            "The sha256 of {path} is {actual_sha256} but {sha256_file} records {expected_sha256}.",
+            path = path.display(),
+            sha256_file = sha256_file.display()
+        );
+    }
     Ok(())
 }
 
-fn fetch_file(url: &str, dest_file: &Path) -> Result<()> {
+fn fetch_file(url: &str, dest_file: &Path, show_progress: bool) -> Result<()> {
     let mut file = File::create(dest_file)?;
-    std::io::copy(&mut ureq::get(url).call()?.into_reader(), &mut file)?;
+    let response = ureq::get(url).call()?;
+    if !show_progress {
+        std::io::copy(&mut response.into_reader(), &mut file)?;
+        return Ok(());
+    }
+
+    let total_size = response
+        .header("Content-Length")
+        .and_then(|value| value.parse::<u64>().ok());
+    let mut progress = Progress::new(base_name(dest_file)?, total_size);
+    let mut reader = response.into_reader();
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .with_context(|| format!("Failed to read from {url}"))?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])?;
+        progress.advance(read as u64);
+    }
+    progress.finish();
     Ok(())
 }
 
+// N.B.: On a terminal we re-draw a single line as the download progresses. Otherwise, say in CI
+// logs, we emit a line every so often instead since carriage returns would just pile up.
+struct Progress<'a> {
+    name: &'a str,
+    total_size: Option<u64>,
+    downloaded: u64,
+    is_terminal: bool,
+    last_report: Instant,
+}
+
+impl<'a> Progress<'a> {
+    const TERMINAL_INTERVAL: Duration = Duration::from_millis(100);
+    const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+    fn new(name: &'a str, total_size: Option<u64>) -> Self {
+        Self {
+            name,
+            total_size,
+            downloaded: 0,
+            is_terminal: std::io::stderr().is_terminal(),
+            last_report: Instant::now(),
+        }
+    }
+
+    fn advance(&mut self, size: u64) {
+        self.downloaded += size;
+        let interval = if self.is_terminal {
+            Self::TERMINAL_INTERVAL
+        } else {
+            Self::LOG_INTERVAL
+        };
+        if self.last_report.elapsed() >= interval {
+            self.report();
+            self.last_report = Instant::now();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.report();
+        if self.is_terminal {
+            eprintln!();
+        }
+    }
+
+    fn report(&self) {
+        let status = match self.total_size {
+            Some(total_size) if total_size > 0 => format!(
+                "{percent}% ({downloaded} of {total_size} bytes)",
+                percent = self.downloaded * 100 / total_size,
+                downloaded = self.downloaded
+            ),
+            _ => format!("{downloaded} bytes", downloaded = self.downloaded),
+        };
+        if self.is_terminal {
+            eprint!("\r    Downloaded {name}: {status}", name = self.name);
+        } else {
+            eprintln!("    Downloaded {name}: {status}", name = self.name);
+        }
+    }
+}
+
 fn fetch_and_check_trusted_sha256(url: &str, dest_file: &Path) -> Result<()> {
-    fetch_file(url, dest_file)?;
+    fetch_file(url, dest_file, true)?;
 
     let mut sha256_dest_file = dest_file.to_owned();
     // Add the additional .sha256 extension, to whatever the base file
@@ -67,7 +160,7 @@ fn fetch_and_check_trusted_sha256(url: &str, dest_file: &Path) -> Result<()> {
     sha256_dest_file.as_mut_os_string().push(".sha256");
     let sha256_url = format!("{url}.sha256");
 
-    fetch_file(&sha256_url, &sha256_dest_file)?;
+    fetch_file(&sha256_url, &sha256_dest_file, false)?;
 
     info!("Checking downloaded {url} has sha256 reported in {sha256_url}");
     check_sha256(dest_file)
@@ -78,20 +171,53 @@ pub(crate) struct BuildContext {
     pub(crate) package_crate_root: PathBuf,
     pub(crate) cargo_output_root: PathBuf,
     target: String,
-    target_prepared: Cell<bool>,
+    target_platform: Platform,
+    target_prepared: Mutex<bool>,
     science_repo: Option<PathBuf>,
+    science_tag: String,
+    ptex_tag: Option<String>,
+    scie_manifest: PathBuf,
+    offline: bool,
+    reproducible: bool,
     cargo_output_bin_dir: PathBuf,
 }
 
 impl BuildContext {
-    pub(crate) fn new(target: Option<&str>, science_repo: Option<&Path>) -> Result<Self> {
+    pub(crate) fn new(
+        target: Option<&str>,
+        science_repo: Option<&Path>,
+        science_tag: Option<&str>,
+        ptex_tag: Option<&str>,
+        scie_manifest: Option<&Path>,
+        offline: bool,
+        reproducible: bool,
+    ) -> Result<Self> {
         let target = target.unwrap_or(TARGET).to_string();
+        let target_platform = Platform::for_target(&target)?;
         let package_crate_root = PathBuf::from(CARGO_MANIFEST_DIR);
         let workspace_root = package_crate_root
             .join("..")
             .canonicalize()
             .context("Failed to canonicalize workspace root")?;
 
+        let scie_manifest = match scie_manifest {
+            Some(scie_manifest) => {
+                if !scie_manifest.is_file() {
+                    bail!(
+                        "The --scie-manifest {path} is not a file.",
+                        path = scie_manifest.display()
+                    );
+                }
+                scie_manifest.canonicalize().with_context(|| {
+                    format!(
+                        "Failed to canonicalize the --scie-manifest {path}",
+                        path = scie_manifest.display()
+                    )
+                })?
+            }
+            None => package_crate_root.join("scie-pants.toml"),
+        };
+
         let output_root = PathBuf::from(OUT_DIR).join("dist");
         let output_bin_dir = output_root.join("bin");
         Ok(Self {
@@ -99,20 +225,92 @@ impl BuildContext {
             package_crate_root,
             cargo_output_root: output_root,
             target,
-            target_prepared: Cell::new(false),
+            target_platform,
+            target_prepared: Mutex::new(false),
             science_repo: science_repo.map(Path::to_path_buf),
+            science_tag: science_tag.unwrap_or(SCIENCE_TAG).to_string(),
+            ptex_tag: ptex_tag.map(str::to_string),
+            scie_manifest,
+            offline,
+            reproducible,
             cargo_output_bin_dir: output_bin_dir,
         })
     }
 
+    pub(crate) fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// The released science tag used; or `None` if science is built from a local repo.
+    pub(crate) fn science_tag(&self) -> Option<&str> {
+        if self.science_repo.is_some() {
+            None
+        } else {
+            Some(&self.science_tag)
+        }
+    }
+
+    /// The lift manifest of the `scie-pants` scie; package/scie-pants.toml unless overridden.
+    pub(crate) fn scie_manifest(&self) -> &Path {
+        &self.scie_manifest
+    }
+
+    /// Returns the lift manifest to build with: the given one or, when a ptex tag was requested, a
+    /// copy of it written to `dest_dir` with its `[lift.ptex] version` pinned to that tag.
+    pub(crate) fn lift_manifest(&self, manifest: &Path, dest_dir: &Path) -> Result<PathBuf> {
+        let Some(ref ptex_tag) = self.ptex_tag else {
+            return Ok(manifest.to_path_buf());
+        };
+        let contents = std::fs::read_to_string(manifest)
+            .with_context(|| format!("Failed to read {path}", path = manifest.display()))?;
+        let mut lift_manifest = contents
+            .parse::<toml_edit::Document>()
+            .with_context(|| format!("Failed to parse {path}", path = manifest.display()))?;
+        let ptex = lift_manifest
+            .get_mut("lift")
+            .and_then(|lift| lift.get_mut("ptex"))
+            .and_then(toml_edit::Item::as_table_like_mut)
+            .with_context(|| {
+                format!(
+                    "The lift manifest {path} has no `[lift.ptex]` table to pin the ptex version in.",
+                    path = manifest.display()
+                )
+            })?;
+        let version = ptex_tag.trim_start_matches('v');
+        ptex.insert("version", toml_edit::value(version));
+        log!(
+            termcolor::Color::Yellow,
+            "Pinning ptex to {ptex_tag} in {manifest}.",
+            manifest = manifest.display()
+        );
+        let dest = dest_dir.join(base_name(manifest)?);
+        write_file(&dest, false, lift_manifest.to_string())?;
+        Ok(dest)
+    }
+
+    pub(crate) fn target_platform(&self) -> Platform {
+        self.target_platform
+    }
+
+    /// Returns true if the binaries built can't be run on this host.
+    pub(crate) fn is_cross_build(&self) -> bool {
+        self.target_platform != *CURRENT_PLATFORM
+    }
+
     fn ensure_target(&self) -> Result<()> {
-        if !self.target_prepared.get() {
+        // N.B.: We hold the lock while preparing the target; so concurrent build tasks prepare it
+        // just once.
+        let mut target_prepared = self
+            .target_prepared
+            .lock()
+            .map_err(|_| anyhow!("Another build task failed preparing the target."))?;
+        if !*target_prepared {
             build_step!(
                 "Ensuring --target {target} is available",
                 target = self.target
             );
             execute(Command::new("rustup").args(["target", "add", &self.target]))?;
-            self.target_prepared.set(true);
+            *target_prepared = true;
         }
         Ok(())
     }
@@ -124,7 +322,7 @@ impl BuildContext {
                 "Building the `science` binary from the source at {science_from}",
                 science_from = science_from.display()
             );
-            execute(
+            execute_streaming(
                 Command::new("nox")
                     .args(["-e", "package"])
                     .env(
@@ -134,7 +332,7 @@ impl BuildContext {
                     .current_dir(science_from),
             )?;
         } else {
-            fetch_a_scie_project("lift", SCIENCE_TAG, "science", dest_dir)?;
+            fetch_a_scie_project("lift", &self.science_tag, "science", dest_dir, self.offline)?;
         }
         let science_exe_path = dest_dir.join(binary_full_name("science"));
         prepare_exe(&science_exe_path)?;
@@ -143,12 +341,95 @@ impl BuildContext {
         Ok(science_exe)
     }
 
-    pub(crate) fn build_scie_pants(&self) -> Result<PathBuf> {
-        build_step!("Building the scie-pants Rust binary.");
+    fn scie_pants_fingerprint(&self) -> Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.target.as_bytes());
+        if self.reproducible {
+            hasher.update(b"reproducible");
+        }
+        // N.B.: The scie-pants binary embeds this at compile time.
+        if let Some(public_key) = env::var_os("SCIE_PANTS_UPDATE_PUBLIC_KEY") {
+            hasher.update(public_key.as_encoded_bytes());
+        }
+        let inputs = ["Cargo.toml", "Cargo.lock", "rust-toolchain"]
+            .into_iter()
+            .map(|file_name| Ok(self.workspace_root.join(file_name)))
+            .chain(
+                WalkDir::new(self.workspace_root.join("src"))
+                    .sort_by_file_name()
+                    .into_iter()
+                    .filter_map(|entry| match entry {
+                        Ok(dir_entry) if !dir_entry.file_type().is_file() => None,
+                        Ok(dir_entry) => Some(Ok(dir_entry.into_path())),
+                        Err(e) => Some(Err(e)),
+                    }),
+            );
+        for input in inputs {
+            let path = input.context("Failed to walk the scie-pants sources")?;
+            if !path.is_file() {
+                continue;
+            }
+            hasher.update(
+                path.strip_prefix(&self.workspace_root)?
+                    .as_os_str()
+                    .as_encoded_bytes(),
+            );
+            let mut reader = File::open(&path).with_context(|| {
+                format!("Failed to open {path} for hashing.", path = path.display())
+            })?;
+            std::io::copy(&mut reader, &mut hasher).context("Failed to digest stream")?;
+        }
+        Ok(format!("{digest:x}", digest = hasher.finalize()))
+    }
+
+    /// Builds the scie-pants Rust binary, or re-uses a previous build from the same sources.
+    ///
+    /// When `capture_output` is true, cargo's output is only shown if the build fails; so it does
+    /// not interleave with the output of other build tasks run concurrently.
+    pub(crate) fn build_scie_pants(&self, force: bool, capture_output: bool) -> Result<PathBuf> {
+        let scie_pants_exe = self.cargo_output_bin_dir.join(format!(
+            "{BINARY}{exe}",
+            exe = self.target_platform.exe_suffix()
+        ));
+        let fingerprint_file = self.cargo_output_root.join(format!("{BINARY}.fingerprint"));
+        let fingerprint = self.scie_pants_fingerprint()?;
+        if !force
+            && scie_pants_exe.is_file()
+            && std::fs::read_to_string(&fingerprint_file).ok().as_ref() == Some(&fingerprint)
+        {
+            build_step!(
+                "Re-using the scie-pants Rust binary previously built from the same sources \
+                (use --force-scie-pants to re-build)"
+            );
+            return Ok(scie_pants_exe);
+        }
+
+        if self.is_cross_build() {
+            self.ensure_target()?;
+        }
+        let mut command = Command::new(CARGO);
+        command.arg("install");
+        if self.reproducible {
+            build_step!(
+                "Building the scie-pants Rust binary reproducibly (build paths are remapped and \
+                Cargo.lock must be up to date)."
+            );
+            command
+                .args(["--locked", "--target-dir"])
+                .arg(self.reproducible_target_dir())
+                .envs(self.reproducible_env()?);
+        } else {
+            build_step!("Building the scie-pants Rust binary.");
+        }
+        let execute = if capture_output {
+            command.stdout(Stdio::piped()).stderr(Stdio::piped());
+            execute
+        } else {
+            execute_streaming
+        };
         execute(
-            Command::new(CARGO)
+            command
                 .args([
-                    "install",
                     "--path",
                     path_as_str(&self.workspace_root)?,
                     "--target",
@@ -162,10 +443,54 @@ impl BuildContext {
                     [self.cargo_output_bin_dir.to_str().unwrap(), env!("PATH")].join(PATHSEP),
                 ),
         )?;
-        Ok(self
-            .cargo_output_bin_dir
-            .join(BINARY)
-            .with_extension(env::consts::EXE_EXTENSION))
+        write_file(&fingerprint_file, false, fingerprint)?;
+        Ok(scie_pants_exe)
+    }
+
+    // N.B.: By default `cargo install` builds in a fresh temporary directory; so we use a fixed one
+    // that we can remap.
+    fn reproducible_target_dir(&self) -> PathBuf {
+        self.cargo_output_root.join("reproducible-target")
+    }
+
+    /// The env vars that make `cargo install` produce the same binary on any machine.
+    ///
+    /// Our release profile already builds with a single codegen unit and strips symbols; so this
+    /// just keeps the absolute paths of the machine doing the build out of panic messages and the
+    /// like.
+    fn reproducible_env(&self) -> Result<Vec<(&'static str, String)>> {
+        let cargo_home = match env::var_os("CARGO_HOME") {
+            Some(cargo_home) => PathBuf::from(cargo_home),
+            None => dirs::home_dir()
+                .context("Failed to determine the home directory to find CARGO_HOME in.")?
+                .join(".cargo"),
+        };
+        let mut rustflags = env::var("CARGO_ENCODED_RUSTFLAGS")
+            .ok()
+            .filter(|flags| !flags.is_empty())
+            .map(|flags| flags.split('\x1f').map(str::to_string).collect::<Vec<_>>())
+            .or_else(|| {
+                env::var("RUSTFLAGS")
+                    .ok()
+                    .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+            })
+            .unwrap_or_default();
+        for (path, remapped) in [
+            (self.reproducible_target_dir(), "/target"),
+            (self.workspace_root.clone(), "/scie-pants"),
+            (cargo_home, "/cargo"),
+        ] {
+            rustflags.push(format!(
+                "--remap-path-prefix={path}={remapped}",
+                path = path_as_str(&path)?
+            ));
+        }
+        // N.B.: The encoded form separates flags with the ASCII unit separator; so paths with
+        // spaces in them survive.
+        Ok(vec![
+            ("CARGO_ENCODED_RUSTFLAGS", rustflags.join("\x1f")),
+            ("CARGO_INCREMENTAL", "0".to_string()),
+        ])
     }
 }
 
@@ -174,6 +499,7 @@ fn fetch_a_scie_project(
     tag: &str,
     binary_name: &str,
     dest_dir: &Path,
+    offline: bool,
 ) -> Result<()> {
     let file_name = binary_full_name(binary_name);
     let cache_dir = crate::utils::fs::dev_cache_dir()?
@@ -194,16 +520,19 @@ fn fetch_a_scie_project(
     let mut lock = fd_lock::RwLock::new(lock_fd);
     let _write_lock = lock.write();
     if !target_dir.exists() {
+        let url =
+            format!("https://github.com/a-scie/{project_name}/releases/download/{tag}/{file_name}");
+        if offline {
+            bail!(
+                "The `{project_name}` {tag} binary {file_name} is not in the dev cache at \
+                {target_dir} and --offline forbids downloading it from {url}.",
+                target_dir = target_dir.display()
+            );
+        }
         build_step!(format!("Fetching the `{project_name}` {tag} binary"));
         let work_dir = cache_dir.join(format!("{tag}.work"));
         ensure_directory(&work_dir, true)?;
-        fetch_and_check_trusted_sha256(
-            format!(
-                "https://github.com/a-scie/{project_name}/releases/download/{tag}/{file_name}",
-            )
-                .as_str(),
-            &work_dir.join(&file_name),
-        )?;
+        fetch_and_check_trusted_sha256(&url, &work_dir.join(&file_name))?;
         rename(&work_dir, &target_dir)?;
     } else {
         build_step!(format!(
@@ -227,3 +556,97 @@ pub(crate) fn fetch_science(build_context: &BuildContext) -> Result<Science> {
     let science_exe = build_context.obtain_science(&dest_dir)?;
     Ok(Science(science_exe))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{fetch_a_scie_project, BuildContext};
+    use crate::utils::exe::binary_full_name;
+
+    #[test]
+    fn fetch_a_scie_project_offline() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        std::env::set_var("SCIE_PANTS_DEV_CACHE", cache_dir.path());
+        let dest_dir = tempfile::tempdir().unwrap();
+
+        let error = fetch_a_scie_project("lift", "v0.3.1", "science", dest_dir.path(), true)
+            .unwrap_err()
+            .to_string();
+        let file_name = binary_full_name("science");
+        assert!(
+            error.contains(&format!(
+                "The `lift` v0.3.1 binary {file_name} is not in the dev cache"
+            )),
+            "{error}"
+        );
+        assert!(!dest_dir.path().join(file_name).exists());
+    }
+
+    #[test]
+    fn reproducible_env() {
+        let build_context = BuildContext::new(None, None, None, None, None, true, true).unwrap();
+        let env = build_context.reproducible_env().unwrap();
+        let (_, rustflags) = env
+            .iter()
+            .find(|(name, _)| *name == "CARGO_ENCODED_RUSTFLAGS")
+            .unwrap();
+        let rustflags = rustflags.split('\x1f').collect::<Vec<_>>();
+        for (path, remapped) in [
+            (build_context.reproducible_target_dir(), "/target"),
+            (build_context.workspace_root.clone(), "/scie-pants"),
+        ] {
+            let remap = format!(
+                "--remap-path-prefix={path}={remapped}",
+                path = path.display()
+            );
+            assert!(rustflags.contains(&remap.as_str()), "{rustflags:?}");
+        }
+    }
+
+    #[test]
+    fn scie_manifest() {
+        let build_context = BuildContext::new(None, None, None, None, None, true, false).unwrap();
+        assert_eq!(
+            build_context.package_crate_root.join("scie-pants.toml"),
+            build_context.scie_manifest()
+        );
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let manifest = tmpdir.path().join("experiment.toml");
+        assert!(BuildContext::new(None, None, None, None, Some(&manifest), true, false).is_err());
+
+        std::fs::copy(build_context.scie_manifest(), &manifest).unwrap();
+        let build_context =
+            BuildContext::new(None, None, None, None, Some(&manifest), true, false).unwrap();
+        assert_eq!(
+            manifest.canonicalize().unwrap(),
+            build_context.scie_manifest()
+        );
+    }
+
+    #[test]
+    fn lift_manifest_ptex_tag() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let manifest = tmpdir.path().join("lift.toml");
+        std::fs::write(
+            &manifest,
+            "[lift]\nname = \"example\"\n\n[lift.ptex]\nid = \"ptex\"\nversion = \"0.7.0\"\n",
+        )
+        .unwrap();
+        let dest_dir = tmpdir.path().join("dest");
+
+        let build_context = BuildContext::new(None, None, None, None, None, true, false).unwrap();
+        assert_eq!(
+            manifest,
+            build_context.lift_manifest(&manifest, &dest_dir).unwrap()
+        );
+
+        let build_context =
+            BuildContext::new(None, None, None, Some("v0.7.1"), None, true, false).unwrap();
+        let pinned_manifest = build_context.lift_manifest(&manifest, &dest_dir).unwrap();
+        assert_eq!(dest_dir.join("lift.toml"), pinned_manifest);
+        assert_eq!(
+            "[lift]\nname = \"example\"\n\n[lift.ptex]\nid = \"ptex\"\nversion = \"0.7.1\"\n",
+            std::fs::read_to_string(pinned_manifest).unwrap()
+        );
+    }
+}
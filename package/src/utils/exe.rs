@@ -2,34 +2,43 @@
 // Licensed under the Apache License, Version 2.0 (see LICENSE).
 
 use std::env;
+use std::ffi::OsStr;
 use std::fmt::{Display, Formatter};
 use std::fs::Permissions;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::Path;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::{bail, Context, Result};
 use lazy_static::lazy_static;
-use log::info;
+use log::{info, log_enabled, Level};
+use regex::Regex;
 
 use super::os::EOL;
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq)]
 pub(crate) enum Platform {
     LinuxAarch64,
+    LinuxAarch64Musl,
     LinuxX86_64,
+    LinuxX86_64Musl,
     MacOSAarch64,
     MacOSX86_64,
+    WindowsAarch64,
     WindowsX86_64,
 }
 
 impl Platform {
     pub(crate) fn current() -> Result<Self> {
         match (env::consts::OS, env::consts::ARCH) {
+            ("linux", "aarch64") if is_musl() => Ok(Self::LinuxAarch64Musl),
             ("linux", "aarch64") => Ok(Self::LinuxAarch64),
+            ("linux", "x86_64") if is_musl() => Ok(Self::LinuxX86_64Musl),
             ("linux", "x86_64") => Ok(Self::LinuxX86_64),
             ("macos", "aarch64") => Ok(Self::MacOSAarch64),
             ("macos", "x86_64") => Ok(Self::MacOSX86_64),
+            ("windows", "aarch64") => Ok(Self::WindowsAarch64),
             ("windows", "x86_64") => Ok(Self::WindowsX86_64),
             _ => bail!(
                 "Unsupported platform: os={os} arch={arch}",
@@ -39,12 +48,51 @@ impl Platform {
         }
     }
 
-    pub(crate) fn to_str(&self) -> &str {
+    /// Returns the platform binaries built for the given Rust target triple run on.
+    pub(crate) fn for_target(target: &str) -> Result<Self> {
+        let mut components = target.split('-');
+        let arch = components.next().unwrap_or_default();
+        let os = components.collect::<Vec<_>>();
+        match (arch, os.as_slice()) {
+            ("aarch64", [.., "linux", env]) if env.starts_with("musl") => {
+                Ok(Self::LinuxAarch64Musl)
+            }
+            ("aarch64", [.., "linux", _]) => Ok(Self::LinuxAarch64),
+            ("x86_64", [.., "linux", env]) if env.starts_with("musl") => Ok(Self::LinuxX86_64Musl),
+            ("x86_64", [.., "linux", _]) => Ok(Self::LinuxX86_64),
+            ("aarch64", ["apple", "darwin"]) => Ok(Self::MacOSAarch64),
+            ("x86_64", ["apple", "darwin"]) => Ok(Self::MacOSX86_64),
+            ("aarch64", ["pc", "windows", _]) => Ok(Self::WindowsAarch64),
+            ("x86_64", ["pc", "windows", _]) => Ok(Self::WindowsX86_64),
+            _ => bail!("Unsupported --target: {target}"),
+        }
+    }
+
+    pub(crate) fn exe_suffix(self) -> &'static str {
+        match self {
+            Platform::WindowsAarch64 | Platform::WindowsX86_64 => ".exe",
+            _ => "",
+        }
+    }
+
+    pub(crate) fn to_str(self) -> &'static str {
+        match self {
+            Platform::LinuxAarch64Musl => "linux-aarch64-musl",
+            Platform::LinuxX86_64Musl => "linux-x86_64-musl",
+            _ => self.scie_platform(),
+        }
+    }
+
+    /// Returns the platform name science and the other a-scie projects use.
+    ///
+    /// N.B.: These don't distinguish musl from glibc Linux.
+    pub(crate) fn scie_platform(self) -> &'static str {
         match self {
-            Platform::LinuxAarch64 => "linux-aarch64",
-            Platform::LinuxX86_64 => "linux-x86_64",
+            Platform::LinuxAarch64 | Platform::LinuxAarch64Musl => "linux-aarch64",
+            Platform::LinuxX86_64 | Platform::LinuxX86_64Musl => "linux-x86_64",
             Platform::MacOSAarch64 => "macos-aarch64",
             Platform::MacOSX86_64 => "macos-x86_64",
+            Platform::WindowsAarch64 => "windows-aarch64",
             Platform::WindowsX86_64 => "windows-x86_64",
         }
     }
@@ -58,6 +106,98 @@ impl Display for Platform {
 
 lazy_static! {
     pub(crate) static ref CURRENT_PLATFORM: Platform = Platform::current().unwrap();
+    static ref VERBOSE: AtomicBool = AtomicBool::new(false);
+    static ref PRINT_COMMAND: AtomicBool = AtomicBool::new(false);
+    static ref ERROR_LINE: Regex =
+        Regex::new(r"(?i)\b(error|exception|traceback|failed|fatal)\b").unwrap();
+}
+
+// N.B.: The science and Pex errors that explain a failure are generally at the end of STDERR.
+const FAILURE_TAIL_LINES: usize = 20;
+
+/// Report the full command and output of failed commands instead of just the tail of STDERR.
+pub(crate) fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Print the build commands passed through `print_command` before running them.
+pub(crate) fn set_print_command(print_command: bool) {
+    PRINT_COMMAND.store(print_command, Ordering::Relaxed);
+}
+
+/// Prints the given command as a shell command line that can be copy-pasted to run it by hand from
+/// any directory, if `--print-command` was passed.
+pub(crate) fn print_command(command: &mut Command) -> Result<&mut Command> {
+    if PRINT_COMMAND.load(Ordering::Relaxed) {
+        eprintln!("{command_line}", command_line = command_line(command)?);
+    }
+    Ok(command)
+}
+
+// N.B.: We `cd` to the directory the command runs in first; so any relative paths in the command
+// resolve just as they do for us.
+fn command_line(command: &Command) -> Result<String> {
+    let cwd = match command.get_current_dir() {
+        Some(cwd) if cwd.is_absolute() => cwd.to_path_buf(),
+        Some(cwd) => env::current_dir()?.join(cwd),
+        None => env::current_dir()?,
+    };
+    let mut words = vec![
+        "cd".to_string(),
+        shell_quote(cwd.as_os_str()),
+        "&&".to_string(),
+    ];
+    let envs = command
+        .get_envs()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect::<Vec<_>>();
+    if !envs.is_empty() {
+        words.push("env".to_string());
+        words.extend(envs.into_iter().map(|(name, value)| {
+            let mut assignment = name.to_os_string();
+            assignment.push("=");
+            assignment.push(value);
+            shell_quote(&assignment)
+        }));
+    }
+    words.push(shell_quote(command.get_program()));
+    words.extend(command.get_args().map(shell_quote));
+    Ok(words.join(" "))
+}
+
+fn shell_quote(word: &OsStr) -> String {
+    let word = word.to_string_lossy();
+    if !word.is_empty()
+        && word
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || "_-./=:,+@%".contains(char))
+    {
+        word.into_owned()
+    } else {
+        format!("'{word}'", word = word.replace('\'', r#"'\''"#))
+    }
+}
+
+fn verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed) || log_enabled!(Level::Debug)
+}
+
+// N.B.: musl's dynamic linker is installed as /lib/ld-musl-<arch>.so.1; failing that, musl's ldd
+// identifies itself when asked for its version.
+fn is_musl() -> bool {
+    let has_musl_loader = std::fs::read_dir("/lib").is_ok_and(|entries| {
+        entries
+            .flatten()
+            .any(|entry| entry.file_name().to_string_lossy().starts_with("ld-musl-"))
+    });
+    has_musl_loader
+        || Command::new("ldd")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| {
+                String::from_utf8_lossy(&output.stdout).contains("musl")
+                    || String::from_utf8_lossy(&output.stderr).contains("musl")
+            })
 }
 
 #[cfg(windows)]
@@ -81,17 +221,49 @@ pub(crate) fn prepare_exe(path: &Path) -> Result<()> {
 }
 
 pub(crate) fn execute_with_input(command: &mut Command, stdin_data: &[u8]) -> Result<Output> {
-    _execute_with_input(command, Some(stdin_data))
+    _execute_with_input(command, Some(stdin_data), false)
 }
 
 pub(crate) fn execute(command: &mut Command) -> Result<Output> {
-    _execute_with_input(command, None)
+    _execute_with_input(command, None, false)
+}
+
+/// Like `execute`, but copies the command's STDOUT and STDERR to our STDERR as they are produced
+/// while still capturing them for the error message if the command fails.
+///
+/// This is for long-running build steps that would otherwise appear to hang.
+pub(crate) fn execute_streaming(command: &mut Command) -> Result<Output> {
+    _execute_with_input(command, None, true)
 }
 
-fn _execute_with_input(command: &mut Command, stdin_data: Option<&[u8]>) -> Result<Output> {
+// N.B.: We copy to our STDERR and not our STDOUT since the build reserves STDOUT for its
+// `--output json` report.
+fn tee(mut source: impl Read) -> std::io::Result<Vec<u8>> {
+    let mut captured = vec![];
+    let mut buffer = [0; 8192];
+    loop {
+        let count = source.read(&mut buffer)?;
+        if count == 0 {
+            return Ok(captured);
+        }
+        let mut stderr = std::io::stderr().lock();
+        stderr.write_all(&buffer[..count])?;
+        stderr.flush()?;
+        captured.extend_from_slice(&buffer[..count]);
+    }
+}
+
+fn _execute_with_input(
+    command: &mut Command,
+    stdin_data: Option<&[u8]>,
+    stream: bool,
+) -> Result<Output> {
     info!("Executing {command:#?}");
     if stdin_data.is_some() {
-        command.stdin(std::process::Stdio::piped());
+        command.stdin(Stdio::piped());
+    }
+    if stream {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
     }
     let mut child = command
         .spawn()
@@ -104,10 +276,70 @@ fn _execute_with_input(command: &mut Command, stdin_data: Option<&[u8]>) -> Resu
             .write(stdin_data)
             .with_context(|| format!("Failed to write {stdin_data:?} to sub-process stdin"))?;
     }
-    let output = child
-        .wait_with_output()
-        .with_context(|| format!("Failed to gather exit status of command: {command:?}"))?;
-    if !output.status.success() {
+    let output = if stream {
+        let stdout = child
+            .stdout
+            .take()
+            .expect("We just set a stdout pipe above");
+        let stderr = child
+            .stderr
+            .take()
+            .expect("We just set a stderr pipe above");
+        let (stdout, stderr) = std::thread::scope(|scope| {
+            let stdout = scope.spawn(|| tee(stdout));
+            let stderr = tee(stderr);
+            (
+                stdout
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+                stderr,
+            )
+        });
+        let stdout =
+            stdout.with_context(|| format!("Failed to stream STDOUT of command: {command:?}"))?;
+        let stderr =
+            stderr.with_context(|| format!("Failed to stream STDERR of command: {command:?}"))?;
+        let status = child
+            .wait()
+            .with_context(|| format!("Failed to gather exit status of command: {command:?}"))?;
+        Output {
+            status,
+            stdout,
+            stderr,
+        }
+    } else {
+        child
+            .wait_with_output()
+            .with_context(|| format!("Failed to gather exit status of command: {command:?}"))?
+    };
+    if !output.status.success() && !verbose() {
+        let mut message_lines = vec![format!(
+            "Command {program:?} failed with exit code: {code:?}",
+            program = command.get_program(),
+            code = output.status.code()
+        )];
+        let stderr = String::from_utf8_lossy(output.stderr.as_slice());
+        let lines = stderr.lines().collect::<Vec<_>>();
+        let tail = &lines[lines.len().saturating_sub(FAILURE_TAIL_LINES)..];
+        if tail.is_empty() {
+            message_lines.push("STDERR not captured.".to_string());
+        } else {
+            message_lines.push(format!(
+                "Last {count} of {total} STDERR lines (lines that look like errors are marked >):",
+                count = tail.len(),
+                total = lines.len()
+            ));
+            message_lines.extend(tail.iter().map(|line| {
+                let marker = if ERROR_LINE.is_match(line) { ">" } else { " " };
+                format!("{marker} {line}")
+            }));
+        }
+        message_lines.push(
+            "Re-run with --verbose or RUST_LOG=debug to see the full command and its output."
+                .to_string(),
+        );
+        bail!(message_lines.join(EOL));
+    } else if !output.status.success() {
         let mut message_lines = vec![format!(
             "Command {command:?} failed with exit code: {code:?}",
             code = output.status.code()
@@ -130,9 +362,92 @@ fn _execute_with_input(command: &mut Command, stdin_data: Option<&[u8]>) -> Resu
 }
 
 pub(crate) fn binary_full_name(name: &str) -> String {
+    binary_full_name_for(name, *CURRENT_PLATFORM)
+}
+
+pub(crate) fn binary_full_name_for(name: &str, platform: Platform) -> String {
     format!(
         "{name}-{platform}{exe}",
-        platform = *CURRENT_PLATFORM,
-        exe = env::consts::EXE_SUFFIX
+        platform = platform.scie_platform(),
+        exe = platform.exe_suffix()
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Platform;
+
+    #[test]
+    fn for_target() {
+        for (target, platform) in [
+            ("x86_64-unknown-linux-gnu", Platform::LinuxX86_64),
+            ("x86_64-unknown-linux-musl", Platform::LinuxX86_64Musl),
+            ("aarch64-unknown-linux-gnu", Platform::LinuxAarch64),
+            ("aarch64-unknown-linux-musl", Platform::LinuxAarch64Musl),
+            ("aarch64-apple-darwin", Platform::MacOSAarch64),
+            ("aarch64-pc-windows-msvc", Platform::WindowsAarch64),
+            ("x86_64-pc-windows-msvc", Platform::WindowsX86_64),
+        ] {
+            assert!(
+                Platform::for_target(target).unwrap() == platform,
+                "{target}"
+            );
+        }
+    }
+
+    #[test]
+    fn windows_aarch64() {
+        let platform = Platform::for_target("aarch64-pc-windows-msvc").unwrap();
+        assert_eq!("windows-aarch64", platform.to_str());
+        assert_eq!("windows-aarch64", platform.scie_platform());
+        assert_eq!(".exe", platform.exe_suffix());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_line() {
+        let cwd = std::env::current_dir().unwrap();
+        let mut command = std::process::Command::new("/opt/science");
+        command
+            .args(["lift", "--file", "tools.pex=/tmp/a dir/tools.pex", "it's"])
+            .env("SCIENCE_CACHE", "/tmp/cache")
+            .current_dir("dist");
+        assert_eq!(
+            format!(
+                "cd {cwd} && env SCIENCE_CACHE=/tmp/cache /opt/science lift --file \
+                'tools.pex=/tmp/a dir/tools.pex' 'it'\\''s'",
+                cwd = cwd.join("dist").display()
+            ),
+            super::command_line(&command).unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute_streaming() {
+        let output = super::execute_streaming(
+            std::process::Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+        )
+        .unwrap();
+        assert_eq!(b"out\n".as_slice(), output.stdout);
+        assert_eq!(b"err\n".as_slice(), output.stderr);
+
+        let error = super::execute_streaming(
+            std::process::Command::new("sh").args(["-c", "echo boom: failed >&2; exit 1"]),
+        )
+        .unwrap_err();
+        assert!(format!("{error}").contains("> boom: failed"), "{error}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn current_libc() {
+        let platform = Platform::current().unwrap();
+        assert_eq!(
+            super::is_musl(),
+            platform.to_str().ends_with("-musl"),
+            "{platform}"
+        );
+        assert!(!platform.scie_platform().ends_with("-musl"));
+    }
+}
@@ -5,7 +5,7 @@ use std::env;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use log::{info, warn};
 use tempfile::TempDir;
 
@@ -111,6 +111,61 @@ pub(crate) fn ensure_directory(path: &Path, clean: bool) -> Result<()> {
     })
 }
 
+/// Ensures the directory at `path` exists and that files can be written to it.
+///
+/// Unlike [`ensure_directory`], failures are reported as the destination not being writable,
+/// naming the resolved path and, where available, its owner.
+pub(crate) fn ensure_writable_directory(path: &Path) -> Result<()> {
+    let resolved = env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    if let Err(e) = std::fs::create_dir_all(&resolved) {
+        let existing = resolved
+            .ancestors()
+            .find(|ancestor| ancestor.is_dir())
+            .unwrap_or(&resolved);
+        bail!(
+            "The destination directory {path} could not be created; {existing} is not \
+            writable{owner}: {e}",
+            path = resolved.display(),
+            existing = existing.display(),
+            owner = describe_owner(existing)
+        );
+    }
+    let resolved = canonicalize(&resolved)?;
+    tempfile::tempfile_in(&resolved).map_err(|e| {
+        anyhow!(
+            "The destination directory {path} is not writable{owner}: {e}",
+            path = resolved.display(),
+            owner = describe_owner(&resolved)
+        )
+    })?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn describe_owner(path: &Path) -> String {
+    use std::os::unix::fs::MetadataExt;
+
+    match path.metadata() {
+        Ok(metadata) => format!(
+            " (owned by uid {uid}, gid {gid} with mode {mode:o})",
+            uid = metadata.uid(),
+            gid = metadata.gid(),
+            mode = metadata.mode() & 0o7777
+        ),
+        Err(_) => String::new(),
+    }
+}
+
+#[cfg(windows)]
+fn describe_owner(path: &Path) -> String {
+    match path.metadata() {
+        Ok(metadata) if metadata.permissions().readonly() => " (it is read-only)".to_string(),
+        _ => String::new(),
+    }
+}
+
 pub(crate) fn create_tempdir() -> Result<TempDir> {
     tempfile::tempdir().context("Failed to create a new temporary directory")
 }
@@ -168,3 +223,37 @@ pub(crate) fn dev_cache_dir() -> Result<PathBuf> {
     ensure_directory(&cache_dir, false)?;
     Ok(cache_dir)
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    use super::ensure_writable_directory;
+
+    #[test]
+    fn read_only_dest_dir() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let dest_dir = tmpdir.path().join("dist");
+        ensure_writable_directory(&dest_dir).unwrap();
+
+        std::fs::set_permissions(&dest_dir, std::fs::Permissions::from_mode(0o555)).unwrap();
+        if dest_dir.metadata().unwrap().uid() == 0 {
+            // N.B.: Root can write to read-only directories; so there is nothing to test.
+            return;
+        }
+        let error = ensure_writable_directory(&dest_dir).unwrap_err();
+        assert!(
+            format!("{error}").contains(&format!(
+                "The destination directory {path} is not writable (owned by uid",
+                path = dest_dir.canonicalize().unwrap().display()
+            )),
+            "{error}"
+        );
+
+        let error = ensure_writable_directory(&dest_dir.join("nested")).unwrap_err();
+        assert!(
+            format!("{error}").contains("could not be created"),
+            "{error}"
+        );
+    }
+}
@@ -1,48 +1,131 @@
 // Copyright 2023 Pants project contributors.
 // Licensed under the Apache License, Version 2.0 (see LICENSE).
 
-use std::sync::atomic::AtomicU8;
+use std::cell::Cell;
+use std::env;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 use lazy_static::lazy_static;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
 #[macro_export]
 macro_rules! log {
     ($color:expr, $msg:expr $(,)?) => {
-        let mut stderr = ::termcolor::StandardStream::stderr(::termcolor::ColorChoice::Always);
-        stderr
-            .set_color(::termcolor::ColorSpec::new().set_fg(Some($color))).unwrap();
-        writeln!(&mut stderr, $msg).unwrap();
-        stderr.reset().unwrap();
+        if !$crate::utils::logging::logging_suppressed() {
+            $crate::utils::logging::log_line($color, &format!($msg));
+        }
     };
     ($color:expr, $msg:expr, $($arg:tt)*) => {
-        let mut stderr = ::termcolor::StandardStream::stderr(::termcolor::ColorChoice::Always);
-        stderr
-            .set_color(::termcolor::ColorSpec::new().set_fg(Some($color))).unwrap();
-        writeln!(&mut stderr, "{}", format!($msg, $($arg)*)).unwrap();
-        stderr.reset().unwrap();
+        if !$crate::utils::logging::logging_suppressed() {
+            $crate::utils::logging::log_line($color, &format!($msg, $($arg)*));
+        }
     };
 }
 
+/// Decides whether the build logging is colored: never when `NO_COLOR` is set to a non-empty value
+/// (see https://no-color.org) or when STDERR is not a terminal.
+pub(crate) fn color_choice() -> ColorChoice {
+    if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        ColorChoice::Never
+    } else if std::io::stderr().is_terminal() {
+        ColorChoice::Auto
+    } else {
+        ColorChoice::Never
+    }
+}
+
+pub(crate) fn log_line(color: Color, line: &str) {
+    let mut stderr = StandardStream::stderr(color_choice());
+    write_line(&mut stderr, color, line).unwrap();
+}
+
+fn write_line(stream: &mut impl WriteColor, color: Color, line: &str) -> std::io::Result<()> {
+    stream.set_color(ColorSpec::new().set_fg(Some(color)))?;
+    writeln!(stream, "{line}")?;
+    stream.reset()
+}
+
+static LOGGING_SUPPRESSED: AtomicBool = AtomicBool::new(false);
+
+/// Turns off the colored build logging; e.g.: when the final report is machine readable.
+pub(crate) fn suppress_logging() {
+    LOGGING_SUPPRESSED.store(true, Ordering::Relaxed);
+}
+
+pub(crate) fn logging_suppressed() -> bool {
+    LOGGING_SUPPRESSED.load(Ordering::Relaxed)
+}
+
 lazy_static! {
     pub(crate) static ref BUILD_STEP: AtomicU8 = AtomicU8::new(1);
 }
 
+thread_local! {
+    static BUILD_TASK: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Labels the build steps logged by the current thread with the given task name; so steps from
+/// tasks run concurrently can be told apart.
+pub(crate) fn set_build_task(task: &'static str) {
+    BUILD_TASK.with(|build_task| build_task.set(Some(task)));
+}
+
+pub(crate) fn clear_build_task() {
+    BUILD_TASK.with(|build_task| build_task.set(None));
+}
+
+pub(crate) fn build_task_prefix() -> String {
+    BUILD_TASK.with(|build_task| {
+        build_task
+            .get()
+            .map(|task| format!("[{task}] "))
+            .unwrap_or_default()
+    })
+}
+
 #[macro_export]
 macro_rules! build_step {
     ($msg:expr $(,)?) => {
         $crate::log!(
             ::termcolor::Color::Cyan,
-            "{:>2}.) {}...",
+            "{:>2}.) {}{}...",
             $crate::utils::logging::BUILD_STEP.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed),
+            $crate::utils::logging::build_task_prefix(),
             $msg
         );
     };
     ($msg:expr, $($arg:tt)*) => {
         $crate::log!(
             ::termcolor::Color::Cyan,
-            "{:>2}.) {}...",
+            "{:>2}.) {}{}...",
             $crate::utils::logging::BUILD_STEP.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed),
+            $crate::utils::logging::build_task_prefix(),
             format!($msg, $($arg)*)
         );
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use termcolor::{BufferWriter, Color};
+
+    use super::{color_choice, write_line};
+
+    #[test]
+    fn no_color() {
+        std::env::set_var("NO_COLOR", "1");
+        let writer = BufferWriter::stderr(color_choice());
+        let mut buffer = writer.buffer();
+        write_line(
+            &mut buffer,
+            Color::Cyan,
+            " 1.) Building the scie-pants Rust binary...",
+        )
+        .unwrap();
+        assert_eq!(
+            " 1.) Building the scie-pants Rust binary...\n",
+            String::from_utf8(buffer.into_inner()).unwrap()
+        );
+    }
+}
@@ -1,37 +1,299 @@
 // Copyright 2022 Pants project contributors.
 // Licensed under the Apache License, Version 2.0 (see LICENSE).
 
+use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use log::{debug, warn};
 use logging_timer::time;
 
-pub(crate) struct BuildRoot(PathBuf);
+use crate::config;
+
+// N.B.: The Pants config file, `pants.toml` or `SCIE_PANTS_CONFIG_NAME`, is a marker as well.
+const DEFAULT_MARKERS: [&str; 2] = ["BUILDROOT", "BUILD_ROOT"];
+
+const MAX_DEPTH_ENV_VAR: &str = "SCIE_PANTS_BUILDROOT_MAX_DEPTH";
+// N.B.: This is generous enough for any real project layout while keeping a search from a deep
+// path with no marker, say on a slow network mount, from stat-ing its way all the way up to `/`.
+const DEFAULT_MAX_DEPTH: usize = 50;
+
+// N.B.: The build root is looked up several times over the course of a launch; so we memoize the
+// search results by start directory, markers and max depth to walk the filesystem just once per
+// process.
+type SearchKey = (PathBuf, Vec<String>, usize);
+static SEARCH_RESULTS: Mutex<BTreeMap<SearchKey, PathBuf>> = Mutex::new(BTreeMap::new());
+
+pub struct BuildRoot(PathBuf);
 
 impl BuildRoot {
+    pub fn new(build_root: PathBuf) -> BuildRoot {
+        BuildRoot(build_root)
+    }
+
+    // N.B.: We set PANTS_BUILDROOT_OVERRIDE for Pants ourselves, but we also respect it when it's
+    // set on the way in; so users can force the build root and skip the marker file search.
+    pub fn from_override() -> Result<Option<BuildRoot>> {
+        let Some(value) = std::env::var_os("PANTS_BUILDROOT_OVERRIDE") else {
+            return Ok(None);
+        };
+        if value.is_empty() {
+            // Setting PANTS_BUILDROOT_OVERRIDE= behaves the same as not setting it.
+            return Ok(None);
+        }
+        let build_root = PathBuf::from(value);
+        if !build_root.is_dir() {
+            bail!(
+                "The PANTS_BUILDROOT_OVERRIDE env var is set to {path} but that is not a \
+                directory.",
+                path = build_root.display()
+            );
+        }
+        // N.B.: A `pants.toml` that is not a file, say a directory, gets a targeted error when the
+        // Pants config is read.
+        let config_name = config::config_name()?;
+        if std::env::var_os("PANTS_TOML").is_none() && !build_root.join(&config_name).exists() {
+            bail!(
+                "The PANTS_BUILDROOT_OVERRIDE env var is set to {path} but there is no \
+                {config_name} in that directory.",
+                path = build_root.display()
+            );
+        }
+        let build_root = build_root.canonicalize().with_context(|| {
+            format!(
+                "Failed to resolve PANTS_BUILDROOT_OVERRIDE {path}",
+                path = build_root.display()
+            )
+        })?;
+        Ok(Some(BuildRoot(build_root)))
+    }
+
+    /// Finds the build root the way Pants does: PANTS_BUILDROOT_OVERRIDE, if set, wins; otherwise
+    /// the build root is the closest directory at or above the CWD containing a marker file.
+    ///
+    /// Returns `None` if there is no override and no marker file is found.
+    pub fn find_from_env() -> Result<Option<BuildRoot>> {
+        if let Some(build_root) = Self::from_override()? {
+            return Ok(Some(build_root));
+        }
+        match Self::find(None) {
+            Ok(build_root) => Ok(Some(build_root)),
+            Err(err) => {
+                debug!("{err:#}");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Finds the closest directory at or above `start_dir`, or the CWD by default, containing a
+    /// marker file.
+    ///
+    /// When `SCIE_PANTS_RESOLVE_CWD=1` is set, the start directory is canonicalized first; so the
+    /// search walks up its real path instead of the path it was reached by through symlinks.
+    ///
+    /// The search gives up after walking `SCIE_PANTS_BUILDROOT_MAX_DEPTH` directories up from the
+    /// start directory, 50 by default.
+    ///
+    /// When `SCIE_PANTS_BUILDROOT_STRICT=1` is set, the search carries on past the build root found
+    /// and warns if any ancestor directory contains a marker file too.
     #[time("debug", "BuildRoot::{}")]
-    pub(crate) fn find(start_dir: Option<PathBuf>) -> Result<BuildRoot> {
+    pub fn find(start_dir: Option<PathBuf>) -> Result<BuildRoot> {
         let start_search = if let Some(cwd) = start_dir {
             cwd
         } else {
             std::env::current_dir()?
         };
+        Self::find_in(
+            start_search,
+            Self::resolve_cwd()?,
+            Self::max_depth()?,
+            Self::strict()?,
+        )
+    }
+
+    fn max_depth() -> Result<usize> {
+        match std::env::var(MAX_DEPTH_ENV_VAR) {
+            Ok(value) if !value.trim().is_empty() => value.trim().parse().map_err(|_| {
+                anyhow!(
+                    "{MAX_DEPTH_ENV_VAR} must be a non-negative integer number of directories, \
+                    got: `{value}`."
+                )
+            }),
+            _ => Ok(DEFAULT_MAX_DEPTH),
+        }
+    }
+
+    fn resolve_cwd() -> Result<bool> {
+        match std::env::var("SCIE_PANTS_RESOLVE_CWD")
+            .unwrap_or_default()
+            .trim()
+        {
+            "" | "0" => Ok(false),
+            "1" => Ok(true),
+            value => bail!("SCIE_PANTS_RESOLVE_CWD must be either `0` or `1`, got: `{value}`."),
+        }
+    }
+
+    fn strict() -> Result<bool> {
+        match std::env::var("SCIE_PANTS_BUILDROOT_STRICT")
+            .unwrap_or_default()
+            .trim()
+        {
+            "" | "0" => Ok(false),
+            "1" => Ok(true),
+            value => {
+                bail!("SCIE_PANTS_BUILDROOT_STRICT must be either `0` or `1`, got: `{value}`.")
+            }
+        }
+    }
+
+    fn find_in(
+        start_search: PathBuf,
+        resolve_symlinks: bool,
+        max_depth: usize,
+        strict: bool,
+    ) -> Result<BuildRoot> {
+        let start_search = if resolve_symlinks {
+            start_search.canonicalize().with_context(|| {
+                format!(
+                    "Failed to resolve the real path of {path} to search for the build root from",
+                    path = start_search.display()
+                )
+            })?
+        } else {
+            start_search
+        };
+
+        let markers = Self::markers()?;
+        let key = (start_search, markers, max_depth);
+        if let Some(build_root) = SEARCH_RESULTS
+            .lock()
+            .map_err(|err| anyhow!("Failed to lock the build root search results: {err}"))?
+            .get(&key)
+        {
+            return Ok(BuildRoot(build_root.clone()));
+        }
+        let build_root = Self::search(&key.0, &key.1, max_depth, strict)?;
+        SEARCH_RESULTS
+            .lock()
+            .map_err(|err| anyhow!("Failed to lock the build root search results: {err}"))?
+            .insert(key, build_root.clone());
+        Ok(BuildRoot(build_root))
+    }
 
-        let mut cwd = start_search.as_path();
-        loop {
-            for marker_file_name in ["pants.toml", "BUILDROOT", "BUILD_ROOT"] {
-                if cwd.join(marker_file_name).is_file() {
-                    return Ok(BuildRoot(cwd.to_path_buf()));
+    fn search(
+        start_search: &Path,
+        markers: &[String],
+        max_depth: usize,
+        strict: bool,
+    ) -> Result<PathBuf> {
+        let mut cwd = start_search;
+        for depth in 0.. {
+            for marker_file_name in markers {
+                let marker = cwd.join(marker_file_name);
+                if marker.is_file() {
+                    if strict {
+                        Self::warn_if_ambiguous(cwd, &marker, markers, max_depth - depth);
+                    }
+                    return Ok(cwd.to_path_buf());
+                } else if marker.is_dir() {
+                    warn!(
+                        "Ignoring {path} while searching for the build root since it is a \
+                        directory and build root markers must be files.",
+                        path = marker.display()
+                    );
                 }
             }
+            if depth == max_depth {
+                break;
+            }
             cwd = cwd.parent().with_context(|| {
                 format!(
-                    "Failed to find pants.toml, BUILDROOT or BUILD_ROOT starting at {start_search}",
+                    "Failed to find {markers} starting at {start_search}",
+                    markers = Self::describe(markers),
                     start_search = start_search.display()
                 )
             })?;
         }
+        bail!(
+            "Failed to find {markers} starting at {start_search}; gave up after searching \
+            {max_depth} directories up, the {MAX_DEPTH_ENV_VAR} limit.",
+            markers = Self::describe(markers),
+            start_search = start_search.display()
+        )
+    }
+
+    fn warn_if_ambiguous(build_root: &Path, marker: &Path, markers: &[String], max_depth: usize) {
+        let outer_markers = Self::outer_markers(build_root, markers, max_depth);
+        if outer_markers.is_empty() {
+            return;
+        }
+        warn!(
+            "The build root was found at {build_root} via {marker}, but there are more build root \
+            markers further up:\n{outer_markers}\n\
+            The nearest build root wins. If you meant another one, export PANTS_BUILDROOT_OVERRIDE \
+            to point to it or remove the stray markers.",
+            build_root = build_root.display(),
+            marker = marker.display(),
+            outer_markers = outer_markers
+                .iter()
+                .map(|marker| format!("  {marker}", marker = marker.display()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    /// Returns the marker files in the ancestors of `build_root`, nearest first, looking at most
+    /// `max_depth` directories up.
+    fn outer_markers(build_root: &Path, markers: &[String], max_depth: usize) -> Vec<PathBuf> {
+        build_root
+            .ancestors()
+            .skip(1)
+            .take(max_depth)
+            .flat_map(|dir| markers.iter().map(move |marker| dir.join(marker)))
+            .filter(|marker| marker.is_file())
+            .collect()
+    }
+
+    // N.B.: The search stops at the first directory containing any of the markers; so the order of
+    // the markers only matters for which one is checked first, not which directory is found.
+    fn markers() -> Result<Vec<String>> {
+        let config_name = config::config_name()?;
+        let default_markers = || {
+            std::iter::once(config_name.clone())
+                .chain(DEFAULT_MARKERS.iter().map(|marker| marker.to_string()))
+        };
+        let value = std::env::var_os("PANTS_BUILDROOT_MARKERS")
+            .unwrap_or_default()
+            .into_string()
+            .map_err(|raw| {
+                anyhow!("Failed to interpret PANTS_BUILDROOT_MARKERS {raw:?} as UTF-8.")
+            })?;
+        let (augment, value) = match value.trim().strip_prefix('+') {
+            Some(value) => (true, value),
+            None => (false, value.trim()),
+        };
+        let mut markers = value
+            .split(',')
+            .map(str::trim)
+            .filter(|marker| !marker.is_empty())
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        // Setting PANTS_BUILDROOT_MARKERS= behaves the same as not setting it.
+        if augment || markers.is_empty() {
+            markers.extend(default_markers());
+        }
+        Ok(markers)
+    }
+
+    fn describe(markers: &[String]) -> String {
+        match markers {
+            [] => String::new(),
+            [marker] => marker.clone(),
+            [init @ .., last] => format!("{init} or {last}", init = init.join(", ")),
+        }
     }
 }
 
@@ -48,3 +310,119 @@ impl AsRef<Path> for BuildRoot {
         self.0.as_path()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::BuildRoot;
+
+    // N.B.: This is the only test that reads PANTS_BUILDROOT_OVERRIDE; so it can set it without
+    // racing other tests.
+    #[test]
+    fn find_from_env() {
+        env::remove_var("PANTS_BUILDROOT_OVERRIDE");
+        let workspace_root = env::current_dir().unwrap();
+        assert_eq!(
+            workspace_root,
+            *BuildRoot::find_from_env().unwrap().unwrap()
+        );
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let build_root = tmpdir.path().canonicalize().unwrap();
+        fs::write(build_root.join("pants.toml"), "").unwrap();
+        env::set_var("PANTS_BUILDROOT_OVERRIDE", &build_root);
+        let found = BuildRoot::find_from_env();
+        env::remove_var("PANTS_BUILDROOT_OVERRIDE");
+        assert_eq!(build_root, *found.unwrap().unwrap());
+    }
+
+    #[test]
+    fn find_memoized() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let build_root = tmpdir.path().canonicalize().unwrap();
+        let start_dir = build_root.join("src").join("python");
+        fs::create_dir_all(&start_dir).unwrap();
+        let pants_toml = build_root.join("pants.toml");
+        fs::write(&pants_toml, "").unwrap();
+
+        assert_eq!(
+            build_root,
+            *BuildRoot::find(Some(start_dir.clone())).unwrap()
+        );
+
+        // With the marker gone, only a memoized search result can find the build root.
+        fs::remove_file(&pants_toml).unwrap();
+        assert_eq!(
+            build_root,
+            *BuildRoot::find(Some(start_dir.clone())).unwrap()
+        );
+        assert!(BuildRoot::find(Some(build_root.join("src"))).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn find_resolving_symlinks() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let tmpdir = tmpdir.path().canonicalize().unwrap();
+        let build_root = tmpdir.join("project");
+        let subdir = build_root.join("src");
+        fs::create_dir_all(&subdir).unwrap();
+        fs::write(build_root.join("pants.toml"), "").unwrap();
+        let link = tmpdir.join("elsewhere").join("link");
+        fs::create_dir_all(link.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(&subdir, &link).unwrap();
+
+        assert!(BuildRoot::find_in(link.clone(), false, 50, false).is_err());
+        assert_eq!(
+            build_root,
+            *BuildRoot::find_in(link, true, 50, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn find_bounded() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let build_root = tmpdir.path().canonicalize().unwrap();
+        fs::write(build_root.join("pants.toml"), "").unwrap();
+        let start_dir = build_root.join("a").join("b").join("c").join("d");
+        fs::create_dir_all(&start_dir).unwrap();
+
+        let Err(error) = BuildRoot::find_in(start_dir.clone(), false, 3, false) else {
+            panic!("Expected the search to give up before reaching the build root.");
+        };
+        assert!(
+            format!("{error}").contains(
+                "gave up after searching 3 directories up, the SCIE_PANTS_BUILDROOT_MAX_DEPTH \
+                limit"
+            ),
+            "{error}"
+        );
+        assert_eq!(
+            build_root,
+            *BuildRoot::find_in(start_dir, false, 4, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn find_ambiguous() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let outer = tmpdir.path().canonicalize().unwrap();
+        fs::write(outer.join("pants.toml"), "").unwrap();
+        let inner = outer.join("inner");
+        let start_dir = inner.join("src");
+        fs::create_dir_all(&start_dir).unwrap();
+        fs::write(inner.join("BUILD_ROOT"), "").unwrap();
+
+        assert_eq!(
+            inner,
+            *BuildRoot::find_in(start_dir, false, 50, true).unwrap()
+        );
+        let markers = BuildRoot::markers().unwrap();
+        assert_eq!(
+            vec![outer.join("pants.toml")],
+            BuildRoot::outer_markers(&inner, &markers, 50)
+        );
+        assert!(BuildRoot::outer_markers(&inner, &markers, 0).is_empty());
+    }
+}
@@ -1,83 +1,961 @@
 // Copyright 2022 Pants project contributors.
 // Licensed under the Apache License, Version 2.0 (see LICENSE).
 
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use log::warn;
 use logging_timer::time;
-use serde::Deserialize;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
 
 use crate::build_root::BuildRoot;
+use crate::remote_config;
+use crate::resolve::Env;
+
+pub const DEFAULT_CONFIG_NAME: &str = "pants.toml";
+
+const CONFIG_NAME_ENV_VAR: &str = "SCIE_PANTS_CONFIG_NAME";
+
+/// The file name of the Pants config in the build root; `SCIE_PANTS_CONFIG_NAME` if set and
+/// `pants.toml` otherwise.
+pub fn config_name() -> Result<String> {
+    parse_config_name(std::env::var_os(CONFIG_NAME_ENV_VAR).as_deref())
+}
+
+fn parse_config_name(value: Option<&OsStr>) -> Result<String> {
+    let value = value
+        .unwrap_or_default()
+        .to_owned()
+        .into_string()
+        .map_err(|raw| anyhow!("Failed to interpret {CONFIG_NAME_ENV_VAR} {raw:?} as UTF-8."))?;
+    let name = value.trim();
+    if name.is_empty() {
+        // Setting SCIE_PANTS_CONFIG_NAME= behaves the same as not setting it.
+        return Ok(DEFAULT_CONFIG_NAME.to_owned());
+    }
+    if Path::new(name)
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        != Some(name)
+    {
+        bail!(
+            "{CONFIG_NAME_ENV_VAR} must be the bare file name of the Pants config in the build \
+            root, got: `{name}`."
+        );
+    }
+    Ok(name.to_owned())
+}
 
 #[derive(Default, Deserialize)]
-pub(crate) struct Global {
-    #[serde(default)]
-    pub(crate) pants_version: Option<String>,
+pub struct Global {
+    #[serde(default, deserialize_with = "pants_version")]
+    pub pants_version: Option<VersionSpec>,
+}
+
+/// A version given either exactly or as a range.
+pub enum VersionSpec {
+    Exact(String),
+    Range(VersionRange),
+}
+
+fn pants_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<VersionSpec>, D::Error> {
+    version_spec(deserializer, "`pants_version`")
+}
+
+fn debugpy_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<VersionSpec>, D::Error> {
+    version_spec(deserializer, "`[debugpy] version`")
+}
+
+fn version_spec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    name: &str,
+) -> Result<Option<VersionSpec>, D::Error> {
+    match toml::Value::deserialize(deserializer)? {
+        toml::Value::String(version) => Ok(Some(VersionSpec::Exact(version))),
+        table @ toml::Value::Table(_) => {
+            let range: VersionRange = table.try_into().map_err(D::Error::custom)?;
+            if range.min.is_none() && range.max.is_none() {
+                return Err(D::Error::custom(format!(
+                    "A {name} table must specify a `min` version, a `max` version or both."
+                )));
+            }
+            Ok(Some(VersionSpec::Range(range)))
+        }
+        value => Err(D::Error::custom(format!(
+            "Expected {name} to be a version string or a table with `min` and / or `max` \
+            versions, found: {value}"
+        ))),
+    }
+}
+
+/// An inclusive range of versions; the newest released version in the range is used.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VersionRange {
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+impl VersionRange {
+    /// Renders the range as a PEP 440 version specifier; e.g.: `>=2.18.0,<=2.20.0`.
+    pub fn specifier(&self) -> String {
+        self.min
+            .iter()
+            .map(|min| format!(">={min}"))
+            .chain(self.max.iter().map(|max| format!("<={max}")))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
 }
 
 #[derive(Default, Deserialize)]
-pub(crate) struct DebugPy {
-    pub(crate) version: Option<String>,
+pub struct DebugPy {
+    #[serde(default, deserialize_with = "debugpy_version")]
+    pub version: Option<VersionSpec>,
+}
+
+/// The file name of the scie-pants settings in the build root.
+pub const SCIE_PANTS_CONFIG_FILE: &str = ".scie-pants.toml";
+
+/// Settings for scie-pants itself; each is overridden by its corresponding env var.
+///
+/// These are read from their own file since Pants rejects unknown sections in its config.
+#[derive(Clone, Default, Deserialize)]
+pub struct SciePants {
+    pub quiet: Option<bool>,
+    pub no_prompt: Option<bool>,
+    pub download_retries: Option<u32>,
+    pub no_system_python: Option<bool>,
+    // N.B.: This collects settings this scie-pants does not know about, say ones added by a newer
+    // release, so they can be warned about and ignored.
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+impl SciePants {
+    #[time("debug", "SciePants::{}")]
+    pub fn load(build_root: &Path) -> Result<SciePants> {
+        let path = build_root.join(SCIE_PANTS_CONFIG_FILE);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(SciePants::default()),
+            Err(err) => {
+                return Err(
+                    anyhow!(err).context(format!("Failed to read {path}", path = path.display()))
+                )
+            }
+        };
+        let mut scie_pants: SciePants = toml::from_str(&normalize(&contents))
+            .with_context(|| format!("Failed to parse {path}", path = path.display()))?;
+        for name in std::mem::take(&mut scie_pants.unknown).keys() {
+            warn!(
+                "Ignoring the unknown `{name}` setting in {path}; it may be meant for a newer \
+                scie-pants.",
+                path = path.display()
+            );
+        }
+        Ok(scie_pants)
+    }
+}
+
+// N.B.: These are the Pants `[python-bootstrap] search_path` defaults that list edits apply to.
+const DEFAULT_PYTHON_BOOTSTRAP_SEARCH_PATH: [&str; 2] = ["<PYENV>", "<PATH>"];
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum SearchPath {
+    Replace(Vec<String>),
+    Edit {
+        #[serde(default)]
+        add: Vec<String>,
+        #[serde(default)]
+        remove: Vec<String>,
+    },
+    // Any other form of the option is left for Pants itself to interpret.
+    Other(toml::Value),
+}
+
+impl SearchPath {
+    fn apply(self, search_path: Option<Vec<String>>) -> Option<Vec<String>> {
+        match self {
+            SearchPath::Replace(entries) => Some(entries),
+            SearchPath::Edit { add, remove } => {
+                let mut entries = search_path.unwrap_or_else(|| {
+                    DEFAULT_PYTHON_BOOTSTRAP_SEARCH_PATH
+                        .iter()
+                        .map(|entry| entry.to_string())
+                        .collect()
+                });
+                entries.extend(add);
+                entries.retain(|entry| !remove.contains(entry));
+                Some(entries)
+            }
+            SearchPath::Other(_) => search_path,
+        }
+    }
 }
 
 #[derive(Default, Deserialize)]
-pub(crate) struct Default {
-    pub(crate) delegate_bootstrap: Option<bool>,
+pub struct PythonBootstrap {
+    pub search_path: Option<SearchPath>,
 }
 
 #[derive(Deserialize)]
-pub(crate) struct Config {
+#[serde(untagged)]
+pub enum InterpreterConstraints {
+    List(Vec<String>),
+    Single(String),
+    // Any other form of the option is left for Pants itself to interpret.
+    Other(toml::Value),
+}
+
+#[derive(Default, Deserialize)]
+pub struct Python {
+    pub interpreter_constraints: Option<InterpreterConstraints>,
+}
+
+// N.B.: This is the script the Pants repo itself uses to run Pants from sources.
+const DEFAULT_DELEGATE_BOOTSTRAP_SCRIPT: &str = "pants";
+
+pub enum DelegateBootstrap {
+    Enabled(bool),
+    Script(String),
+}
+
+impl<'de> Deserialize<'de> for DelegateBootstrap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match toml::Value::deserialize(deserializer)? {
+            toml::Value::Boolean(enabled) => Ok(DelegateBootstrap::Enabled(enabled)),
+            toml::Value::String(script) => {
+                // N.B.: Only plain relative paths are allowed; so the script can't point outside
+                // the build root via `..` or an absolute path.
+                if script.trim().is_empty()
+                    || !Path::new(&script).components().all(|component| {
+                        matches!(component, Component::Normal(_) | Component::CurDir)
+                    })
+                {
+                    return Err(D::Error::custom(format!(
+                        "A `delegate_bootstrap` script must be a path relative to the build root \
+                        that stays within it, found: {script:?}"
+                    )));
+                }
+                Ok(DelegateBootstrap::Script(script))
+            }
+            value => Err(D::Error::custom(format!(
+                "Expected `delegate_bootstrap` to be a boolean or the name of a script in the \
+                build root to delegate to, found: {value}"
+            ))),
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct Default {
+    pub delegate_bootstrap: Option<DelegateBootstrap>,
+    #[serde(flatten)]
+    pub values: HashMap<String, toml::Value>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct Config {
     #[serde(default, rename = "GLOBAL")]
-    pub(crate) global: Global,
+    pub global: Global,
     #[serde(default)]
-    pub(crate) debugpy: DebugPy,
+    pub debugpy: DebugPy,
     #[serde(default, rename = "DEFAULT")]
-    pub(crate) default: Default,
+    pub default: Default,
+    #[serde(default)]
+    pub python: Python,
+    #[serde(default, rename = "python-bootstrap")]
+    pub python_bootstrap: PythonBootstrap,
 }
 
-pub(crate) struct PantsConfig {
+impl Config {
+    fn merge(&mut self, other: Config) {
+        if other.global.pants_version.is_some() {
+            self.global.pants_version = other.global.pants_version;
+        }
+        if other.debugpy.version.is_some() {
+            self.debugpy.version = other.debugpy.version;
+        }
+        if other.default.delegate_bootstrap.is_some() {
+            self.default.delegate_bootstrap = other.default.delegate_bootstrap;
+        }
+        if other.python.interpreter_constraints.is_some() {
+            self.python.interpreter_constraints = other.python.interpreter_constraints;
+        }
+        self.default.values.extend(other.default.values);
+        // N.B.: Merged search paths are always resolved to a `SearchPath::Replace`.
+        if let Some(search_path) = other.python_bootstrap.search_path {
+            let current = match self.python_bootstrap.search_path.take() {
+                Some(SearchPath::Replace(entries)) => Some(entries),
+                _ => None,
+            };
+            self.python_bootstrap.search_path = search_path.apply(current).map(SearchPath::Replace);
+        }
+    }
+
+    fn interpolate_values(&mut self, build_root: &Path, env: &Env) -> Result<()> {
+        if let Some(ref pants_version) = self.global.pants_version {
+            let pants_version = self.interpolate_version_spec(
+                pants_version,
+                build_root,
+                env,
+                "[GLOBAL] pants_version",
+            )?;
+            self.global.pants_version = Some(pants_version);
+        }
+        if let Some(ref debugpy_version) = self.debugpy.version {
+            let debugpy_version = self.interpolate_version_spec(
+                debugpy_version,
+                build_root,
+                env,
+                "[debugpy] version",
+            )?;
+            self.debugpy.version = Some(debugpy_version);
+        }
+        let search_path = match self.python_bootstrap.search_path.take() {
+            Some(SearchPath::Replace(entries)) => Some(SearchPath::Replace(
+                self.interpolate_entries(entries, build_root, env)?,
+            )),
+            Some(SearchPath::Edit { add, remove }) => Some(SearchPath::Edit {
+                add: self.interpolate_entries(add, build_root, env)?,
+                remove: self.interpolate_entries(remove, build_root, env)?,
+            }),
+            search_path => search_path,
+        };
+        self.python_bootstrap.search_path = search_path;
+        Ok(())
+    }
+
+    fn interpolate_version_spec(
+        &self,
+        version_spec: &VersionSpec,
+        build_root: &Path,
+        env: &Env,
+        name: &str,
+    ) -> Result<VersionSpec> {
+        let interpolate_version = |version: &String, name: &str| {
+            self.interpolate(version, build_root, env, &mut vec![])
+                .with_context(|| format!("Failed to interpolate `{name}`"))
+        };
+        Ok(match version_spec {
+            VersionSpec::Exact(version) => VersionSpec::Exact(interpolate_version(version, name)?),
+            VersionSpec::Range(range) => VersionSpec::Range(VersionRange {
+                min: range
+                    .min
+                    .as_ref()
+                    .map(|min| interpolate_version(min, &format!("{name}.min")))
+                    .transpose()?,
+                max: range
+                    .max
+                    .as_ref()
+                    .map(|max| interpolate_version(max, &format!("{name}.max")))
+                    .transpose()?,
+            }),
+        })
+    }
+
+    fn interpolate_entries(
+        &self,
+        entries: Vec<String>,
+        build_root: &Path,
+        env: &Env,
+    ) -> Result<Vec<String>> {
+        entries
+            .iter()
+            .map(|entry| {
+                self.interpolate(entry, build_root, env, &mut vec![])
+                    .context("Failed to interpolate `[python-bootstrap] search_path`")
+            })
+            .collect()
+    }
+
+    // N.B.: This mirrors the `%(name)s` interpolation Pants supports in its config files, where
+    // names are resolved against the `[DEFAULT]` section, the `env.` prefix resolves environment
+    // variables and `%%` escapes a literal `%`.
+    fn interpolate(
+        &self,
+        value: &str,
+        build_root: &Path,
+        env: &Env,
+        stack: &mut Vec<String>,
+    ) -> Result<String> {
+        let mut interpolated = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(index) = rest.find('%') {
+            interpolated.push_str(&rest[..index]);
+            rest = &rest[index..];
+            if let Some(remaining) = rest.strip_prefix("%%") {
+                interpolated.push('%');
+                rest = remaining;
+            } else if let Some(reference) = rest.strip_prefix("%(") {
+                let (name, remaining) = reference.split_once(")s").with_context(|| {
+                    format!("Malformed interpolation in `{value}`, expected `%(<name>)s`.")
+                })?;
+                interpolated.push_str(&self.resolve(name, build_root, env, stack)?);
+                rest = remaining;
+            } else {
+                interpolated.push('%');
+                rest = &rest[1..];
+            }
+        }
+        interpolated.push_str(rest);
+        Ok(interpolated)
+    }
+
+    fn resolve(
+        &self,
+        name: &str,
+        build_root: &Path,
+        env: &Env,
+        stack: &mut Vec<String>,
+    ) -> Result<String> {
+        if stack.iter().any(|entry| entry == name) {
+            bail!(
+                "The interpolation of `{name}` refers back to itself: {cycle} -> {name}",
+                cycle = stack.join(" -> ")
+            );
+        }
+        if let Some(env_var) = name.strip_prefix("env.") {
+            return env
+                .var_os(env_var)
+                .and_then(OsStr::to_str)
+                .map(str::to_owned)
+                .with_context(|| {
+                    format!(
+                        "The `%({name})s` interpolation requires {env_var} be set in the \
+                        environment."
+                    )
+                });
+        }
+        let value = match self.default.values.get(name) {
+            Some(toml::Value::String(value)) => value.to_owned(),
+            Some(
+                value @ (toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_)),
+            ) => value.to_string(),
+            Some(value) => bail!(
+                "The `[DEFAULT] {name}` value used for interpolation must be a string, found: \
+                {value}"
+            ),
+            None if name == "buildroot" => return Ok(build_root.display().to_string()),
+            None if name == "homedir" => {
+                return dirs::home_dir()
+                    .map(|home_dir| home_dir.display().to_string())
+                    .context("Failed to determine the home directory for `%(homedir)s`.")
+            }
+            None => {
+                bail!("There is no `{name}` key defined in the `[DEFAULT]` section to interpolate.")
+            }
+        };
+        stack.push(name.to_owned());
+        let interpolated = self.interpolate(&value, build_root, env, stack)?;
+        stack.pop();
+        Ok(interpolated)
+    }
+}
+
+pub struct PantsConfig {
     build_root: BuildRoot,
-    pub(crate) config: Config,
+    pub config: Config,
 }
 
 impl PantsConfig {
-    pub(crate) fn package_version(&self) -> Option<String> {
-        self.config.global.pants_version.clone()
+    pub fn package_version(&self) -> Option<String> {
+        match self.config.global.pants_version {
+            Some(VersionSpec::Exact(ref version)) => Some(version.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn package_version_range(&self) -> Option<&VersionRange> {
+        match self.config.global.pants_version {
+            Some(VersionSpec::Range(ref range)) => Some(range),
+            _ => None,
+        }
     }
 
-    pub(crate) fn build_root(&self) -> &Path {
+    pub fn build_root(&self) -> &Path {
         self.build_root.as_path()
     }
 
-    pub(crate) fn debugpy_version(&self) -> Option<String> {
-        self.config.debugpy.version.clone()
+    /// The debugpy version to install for `PANTS_DEBUG` runs; a range is rendered as a requirement
+    /// the install binding resolves to the newest matching debugpy release.
+    pub fn debugpy_version(&self) -> Option<String> {
+        match self.config.debugpy.version {
+            Some(VersionSpec::Exact(ref version)) => Some(version.clone()),
+            Some(VersionSpec::Range(ref range)) => {
+                Some(format!("debugpy{specifier}", specifier = range.specifier()))
+            }
+            None => None,
+        }
+    }
+
+    /// The scie-pants settings from the `.scie-pants.toml` in the build root, if any.
+    pub fn scie_pants(&self) -> Result<SciePants> {
+        SciePants::load(self.build_root())
+    }
+
+    // N.B.: Special entries like `<PATH>` and `<PYENV>` are left as-is for Pants to expand.
+    pub fn python_bootstrap_search_path(&self) -> Vec<String> {
+        match self.config.python_bootstrap.search_path {
+            Some(SearchPath::Replace(ref entries)) => entries.clone(),
+            _ => vec![],
+        }
+    }
+
+    /// The `[python] interpreter_constraints` configured; any of which may be satisfied.
+    ///
+    /// A string is a single constraint; list edits written as strings, like `"+['CPython==3.9.*']"`,
+    /// are left for Pants to interpret and yield no constraints.
+    pub fn interpreter_constraints(&self) -> Vec<String> {
+        match self.config.python.interpreter_constraints {
+            Some(InterpreterConstraints::List(ref constraints)) => constraints.clone(),
+            Some(InterpreterConstraints::Single(ref constraint))
+                if !constraint.trim_start().starts_with(['[', '+', '-']) =>
+            {
+                vec![constraint.clone()]
+            }
+            _ => vec![],
+        }
     }
 
-    pub(crate) fn delegate_bootstrap(&self) -> bool {
-        self.config.default.delegate_bootstrap.unwrap_or_default()
+    /// The path of the script to delegate to, relative to the build root, when
+    /// `[DEFAULT] delegate_bootstrap` is enabled.
+    pub fn delegate_bootstrap(&self) -> Option<PathBuf> {
+        match self.config.default.delegate_bootstrap {
+            Some(DelegateBootstrap::Enabled(true)) => {
+                Some(PathBuf::from(DEFAULT_DELEGATE_BOOTSTRAP_SCRIPT))
+            }
+            Some(DelegateBootstrap::Script(ref script)) => Some(PathBuf::from(script)),
+            Some(DelegateBootstrap::Enabled(false)) | None => None,
+        }
     }
 }
 
 impl PantsConfig {
     #[time("debug", "PantsConfig::{}")]
-    pub(crate) fn parse(build_root: BuildRoot) -> Result<PantsConfig> {
-        let (pants_config, provenance) = if let Some(path) = std::env::var_os("PANTS_TOML") {
-            (path.into(), " (via PANTS_TOML env var)")
+    pub fn parse(build_root: BuildRoot) -> Result<PantsConfig> {
+        Self::parse_in(build_root, &Env::current()?)
+    }
+
+    /// Parses the Pants config in the given build root, reading `SCIE_PANTS_CONFIG_NAME`,
+    /// `PANTS_TOML`, `PANTS_CONFIG_FILES` and any `%(env.X)s` interpolations from `env` instead of
+    /// from the environment of the current process.
+    pub(crate) fn parse_in(build_root: BuildRoot, env: &Env) -> Result<PantsConfig> {
+        let config_name = parse_config_name(env.var_os(CONFIG_NAME_ENV_VAR))?;
+        let mut config = Config::default();
+        for (pants_config, provenance) in Self::config_files(&build_root, &config_name, env)? {
+            let contents = std::fs::read_to_string(&pants_config).map_err(|err| {
+                let context = format!(
+                    "Failed to read Pants config from {path}{provenance}",
+                    path = pants_config.display()
+                );
+                if err.kind() == io::ErrorKind::PermissionDenied {
+                    let path = pants_config
+                        .canonicalize()
+                        .unwrap_or_else(|_| pants_config.clone());
+                    anyhow!(err).context(format!(
+                        "{context}\n\
+                        Permission was denied reading {path}. Check that the file is owned by, or \
+                        readable by, the current user; e.g.: `ls -l {path}` and, if needed, \
+                        `chmod a+r {path}`.",
+                        path = path.display()
+                    ))
+                } else if pants_config.is_dir() {
+                    anyhow!(err).context(format!(
+                        "{context}\n\
+                        The path {path} is a directory, not a file. If it was created by mistake, \
+                        say by a bad archive extraction, remove it and restore your Pants config.",
+                        path = pants_config.display()
+                    ))
+                } else {
+                    anyhow!(err).context(context)
+                }
+            })?;
+            let mut file_config = parse_config(&normalize(&contents)).with_context(|| {
+                format!(
+                    "Failed to parse Pants config from {path}{provenance}",
+                    path = pants_config.display()
+                )
+            })?;
+            file_config
+                .interpolate_values(&build_root, env)
+                .with_context(|| {
+                    format!(
+                        "Failed to interpolate Pants config from {path}{provenance}",
+                        path = pants_config.display()
+                    )
+                })?;
+            config.merge(file_config);
+        }
+        Ok(PantsConfig { build_root, config })
+    }
+
+    // N.B.: This mirrors how Pants treats PANTS_CONFIG_FILES as a list option layered on top of
+    // the default `pants.toml` (or `SCIE_PANTS_CONFIG_NAME`): a bare path is appended, `[...]`
+    // replaces the list and `+[...]` / `-[...]` add to or remove from it. Later files override
+    // values from earlier ones.
+    fn config_files(
+        build_root: &BuildRoot,
+        config_name: &str,
+        env: &Env,
+    ) -> Result<Vec<(PathBuf, Cow<'static, str>)>> {
+        let default_config_file = if let Some(value) = env.var_os("PANTS_TOML") {
+            let provenance = match remote_config::remote_url(value) {
+                Some(url) => Cow::Owned(format!(" (fetched from {url} via PANTS_TOML env var)")),
+                None => Cow::Borrowed(" (via PANTS_TOML env var)"),
+            };
+            (remote_config::local_path(value)?, provenance)
         } else {
-            (build_root.join("pants.toml"), "")
+            (build_root.join(config_name), Cow::Borrowed(""))
+        };
+        let mut config_files = vec![default_config_file];
+
+        let Some(value) = env.var_os("PANTS_CONFIG_FILES") else {
+            return Ok(config_files);
         };
-        let contents = std::fs::read_to_string(&pants_config).with_context(|| {
-            format!(
-                "Failed to read Pants config from {path}{provenance}",
-                path = pants_config.display()
+        let value = value
+            .to_owned()
+            .into_string()
+            .map_err(|raw| anyhow!("Failed to interpret PANTS_CONFIG_FILES {raw:?} as UTF-8."))?;
+        let value = value.trim();
+        let resolve = |path: &str| {
+            (
+                build_root.join(path),
+                Cow::Borrowed(" (via PANTS_CONFIG_FILES env var)"),
             )
+        };
+        if let Some(list) = value.strip_prefix("+[") {
+            config_files.extend(Self::parse_list(list)?.into_iter().map(resolve));
+        } else if let Some(list) = value.strip_prefix("-[") {
+            let removals = Self::parse_list(list)?
+                .into_iter()
+                .map(|path| build_root.join(path))
+                .collect::<Vec<_>>();
+            config_files.retain(|(path, _)| !removals.contains(path));
+        } else if let Some(list) = value.strip_prefix('[') {
+            config_files = Self::parse_list(list)?.into_iter().map(resolve).collect();
+        } else if !value.is_empty() {
+            config_files.push(resolve(value));
+        }
+        Ok(config_files)
+    }
+
+    fn parse_list(list: &str) -> Result<Vec<&str>> {
+        let items = list.trim_end().strip_suffix(']').with_context(|| {
+            format!("Failed to parse PANTS_CONFIG_FILES list, expected a closing `]`: [{list}")
         })?;
-        let config: Config = toml::from_str(&contents).with_context(|| {
-            format!(
-                "Failed to parse Pants config from {path}{provenance}",
-                path = pants_config.display()
+        Ok(items
+            .split(',')
+            .map(|item| item.trim().trim_matches(|c| c == '"' || c == '\''))
+            .filter(|item| !item.is_empty())
+            .collect())
+    }
+}
+
+// These are the only sections of the Pants config scie-pants reads.
+const CONSULTED_SECTIONS: [&str; 5] =
+    ["DEFAULT", "GLOBAL", "debugpy", "python", "python-bootstrap"];
+
+// N.B.: Pants reports problems with its own config far better than we can; so a config that only
+// fails to parse due to a section scie-pants doesn't read is not allowed to block launching Pants.
+fn parse_config(contents: &str) -> Result<Config> {
+    let err = match toml::from_str(contents) {
+        Ok(config) => return Ok(config),
+        Err(err) => err,
+    };
+    if let Ok(config) = toml::from_str(&consulted_sections(contents)) {
+        warn!(
+            "Ignoring a problem with the Pants config outside of the sections scie-pants reads; \
+            Pants will report it if it matters:\n{err}"
+        );
+        return Ok(config);
+    }
+    Err(anyhow!(err).context(format!(
+        "scie-pants only reads these sections of the Pants config: {sections}. There is a problem \
+        with one of them",
+        sections = CONSULTED_SECTIONS
+            .iter()
+            .map(|section| format!("[{section}]"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )))
+}
+
+/// Returns just the sections of the given TOML document that scie-pants reads.
+fn consulted_sections(contents: &str) -> String {
+    let mut consulted = true;
+    let mut sections = String::with_capacity(contents.len());
+    for line in contents.lines() {
+        // N.B.: The sub-tables of the sections we read, like `[python.resolves]`, are never read
+        // themselves; so they are dropped too.
+        if let Some(table) = table_header(line) {
+            consulted = CONSULTED_SECTIONS.contains(&table.as_str());
+        }
+        // N.B.: We keep the lines of the sections we drop, but blanked out; so errors in the
+        // sections we keep are reported at their original line numbers.
+        if consulted {
+            sections.push_str(line);
+        }
+        sections.push('\n');
+    }
+    sections
+}
+
+/// Returns the table name if `line` is a table header like `[name]`, `["name"]`, `[name.sub]` or
+/// `[[name]]`; with any quoting of its keys removed.
+fn table_header(line: &str) -> Option<String> {
+    let line = line.trim();
+    let line = match line.find('#') {
+        Some(index) if !line[..index].contains(['"', '\'']) => line[..index].trim_end(),
+        _ => line,
+    };
+    let header = line
+        .strip_prefix("[[")
+        .and_then(|header| header.strip_suffix("]]"))
+        .or_else(|| line.strip_prefix('[')?.strip_suffix(']'))?;
+    if header.trim().is_empty()
+        || !header
+            .chars()
+            .all(|c| c.is_alphanumeric() || " \t._-\"'".contains(c))
+    {
+        return None;
+    }
+    Some(
+        header
+            .split('.')
+            .map(|key| key.trim().trim_matches(|c| c == '"' || c == '\''))
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+// N.B.: Editors on Windows can save a config with a UTF-8 BOM and CRLF line endings. The BOM is not
+// valid TOML and a stray `\r` can end up in values; so we strip the one and normalize the other.
+fn normalize(contents: &str) -> Cow<'_, str> {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    if contents.contains("\r\n") {
+        Cow::Owned(contents.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuildRoot, Env, PantsConfig, SciePants, SCIE_PANTS_CONFIG_FILE};
+
+    #[test]
+    fn bom_and_crlf() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmpdir.path().join("pants.toml"),
+            "\u{feff}[GLOBAL]\r\npants_version = \"2.18.0\"\r\n",
+        )
+        .unwrap();
+        let pants_config = PantsConfig::parse(BuildRoot::new(tmpdir.path().to_path_buf())).unwrap();
+        assert_eq!(Some("2.18.0".to_string()), pants_config.package_version());
+    }
+
+    #[test]
+    fn scie_pants_settings() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let settings = SciePants::load(tmpdir.path()).unwrap();
+        assert_eq!(None, settings.quiet);
+
+        let config_file = tmpdir.path().join(SCIE_PANTS_CONFIG_FILE);
+        std::fs::write(
+            &config_file,
+            "quiet = true\ndownload_retries = 5\nsetting_from_the_future = \"x\"\n",
+        )
+        .unwrap();
+        let settings = SciePants::load(tmpdir.path()).unwrap();
+        assert_eq!(Some(true), settings.quiet);
+        assert_eq!(Some(5), settings.download_retries);
+        assert_eq!(None, settings.no_prompt);
+
+        std::fs::write(&config_file, "quiet = \"yes\"\n").unwrap();
+        assert!(SciePants::load(tmpdir.path()).is_err());
+    }
+
+    #[test]
+    fn python_bootstrap_search_path() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let pants_toml = tmpdir.path().join("pants.toml");
+        std::fs::write(&pants_toml, "[GLOBAL]\npants_version = \"2.18.0\"\n").unwrap();
+        let pants_config = PantsConfig::parse(BuildRoot::new(tmpdir.path().to_path_buf())).unwrap();
+        assert!(pants_config.python_bootstrap_search_path().is_empty());
+
+        std::fs::write(
+            &pants_toml,
+            "[python-bootstrap]\nsearch_path = [\"<PYENV>\", \"/opt/python/bin\", \"<PATH>\"]\n",
+        )
+        .unwrap();
+        let pants_config = PantsConfig::parse(BuildRoot::new(tmpdir.path().to_path_buf())).unwrap();
+        assert_eq!(
+            vec!["<PYENV>", "/opt/python/bin", "<PATH>"],
+            pants_config.python_bootstrap_search_path()
+        );
+    }
+
+    #[test]
+    fn alternate_config_name() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmpdir.path().join("pants.toml"),
+            "[GLOBAL]\npants_version = \"2.17.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tmpdir.path().join("pants.ci.toml"),
+            "[GLOBAL]\npants_version = \"2.18.0\"\n",
+        )
+        .unwrap();
+        let env = Env::new(tmpdir.path(), [("SCIE_PANTS_CONFIG_NAME", "pants.ci.toml")]);
+        let pants_config =
+            PantsConfig::parse_in(BuildRoot::new(tmpdir.path().to_path_buf()), &env).unwrap();
+        assert_eq!(Some("2.18.0".to_string()), pants_config.package_version());
+    }
+
+    #[test]
+    fn env_interpolation() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmpdir.path().join("pants.toml"),
+            "[GLOBAL]\npants_version = \"%(env.SCIE_PANTS_TEST_VERSION)s\"\n",
+        )
+        .unwrap();
+        let build_root = || BuildRoot::new(tmpdir.path().to_path_buf());
+
+        let env = Env::new(tmpdir.path(), [("SCIE_PANTS_TEST_VERSION", "2.18.0")]);
+        let pants_config = PantsConfig::parse_in(build_root(), &env).unwrap();
+        assert_eq!(Some("2.18.0".to_string()), pants_config.package_version());
+
+        let env = Env::new(tmpdir.path(), Vec::<(String, String)>::new());
+        assert!(PantsConfig::parse_in(build_root(), &env).is_err());
+    }
+
+    #[test]
+    fn unrelated_malformed_section() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let pants_toml = tmpdir.path().join("pants.toml");
+        std::fs::write(
+            &pants_toml,
+            r#"
+[GLOBAL]
+pants_version = "2.18.0"
+
+[python.resolves]
+python-default = "3rdparty/python/default.lock
+
+[debugpy]
+version = "1.6.0"
+"#,
+        )
+        .unwrap();
+        let pants_config = PantsConfig::parse(BuildRoot::new(tmpdir.path().to_path_buf())).unwrap();
+        assert_eq!(Some("2.18.0".to_string()), pants_config.package_version());
+        assert_eq!(Some("1.6.0".to_string()), pants_config.debugpy_version());
+
+        std::fs::write(
+            &pants_toml,
+            r#"
+[source]
+root_patterns = ["/"]
+
+[GLOBAL]
+pants_version = "2.18.0
+"#,
+        )
+        .unwrap();
+        let Err(err) = PantsConfig::parse(BuildRoot::new(tmpdir.path().to_path_buf())) else {
+            panic!("Expected a malformed [GLOBAL] section to fail to parse.");
+        };
+        let message = format!("{err:#}");
+        assert!(
+            message.contains("scie-pants only reads these sections of the Pants config"),
+            "{message}"
+        );
+        assert!(message.contains("line 6"), "{message}");
+    }
+
+    #[test]
+    fn table_header() {
+        use super::table_header;
+
+        assert_eq!(Some("GLOBAL"), table_header("[GLOBAL]").as_deref());
+        assert_eq!(
+            Some("python.resolves"),
+            table_header("  [python.resolves] # Lockfiles.").as_deref()
+        );
+        assert_eq!(
+            Some("scie-pants"),
+            table_header("[\"scie-pants\"]").as_deref()
+        );
+        assert_eq!(Some("plugin"), table_header("[[plugin]]").as_deref());
+        assert_eq!(None, table_header("  [\"a\", \"b\"],").as_deref());
+        assert_eq!(None, table_header("pants_version = \"2.18.0\"").as_deref());
+    }
+
+    #[test]
+    fn delegate_bootstrap_script() {
+        let delegate_bootstrap = |pants_toml: &str| {
+            let tmpdir = tempfile::tempdir().unwrap();
+            std::fs::write(tmpdir.path().join("pants.toml"), pants_toml).unwrap();
+            PantsConfig::parse(BuildRoot::new(tmpdir.path().to_path_buf()))
+                .map(|pants_config| pants_config.delegate_bootstrap())
+        };
+        assert_eq!(
+            Some(std::path::PathBuf::from("tools/pants-fork")),
+            delegate_bootstrap("[DEFAULT]\ndelegate_bootstrap = \"tools/pants-fork\"\n").unwrap()
+        );
+        for script in ["../pants", "tools/../../pants", "/usr/bin/pants"] {
+            let Err(err) =
+                delegate_bootstrap(&format!("[DEFAULT]\ndelegate_bootstrap = \"{script}\"\n"))
+            else {
+                panic!("Expected {script} to be rejected.");
+            };
+            assert!(
+                format!("{err:#}").contains("that stays within it"),
+                "{err:#}"
+            );
+        }
+    }
+
+    fn interpreter_constraints(pants_toml: &str) -> Vec<String> {
+        let tmpdir = tempfile::tempdir().unwrap();
+        std::fs::write(tmpdir.path().join("pants.toml"), pants_toml).unwrap();
+        PantsConfig::parse(BuildRoot::new(tmpdir.path().to_path_buf()))
+            .unwrap()
+            .interpreter_constraints()
+    }
+
+    #[test]
+    fn interpreter_constraints_forms() {
+        assert!(interpreter_constraints("[GLOBAL]\npants_version = \"1.30.5\"\n").is_empty());
+        assert_eq!(
+            vec!["CPython>=3.8,<3.10".to_string(), "CPython==3.11.*".to_string()],
+            interpreter_constraints(
+                "[python]\ninterpreter_constraints = [\"CPython>=3.8,<3.10\", \"CPython==3.11.*\"]\n"
             )
-        })?;
-        Ok(PantsConfig { build_root, config })
+        );
+        assert_eq!(
+            vec!["CPython>=3.8,<3.10".to_string()],
+            interpreter_constraints("[python]\ninterpreter_constraints = \"CPython>=3.8,<3.10\"\n")
+        );
+        assert!(interpreter_constraints(
+            "[python]\ninterpreter_constraints = \"+['CPython==3.9.*']\"\n"
+        )
+        .is_empty());
     }
 }
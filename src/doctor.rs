@@ -0,0 +1,225 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::build_root::BuildRoot;
+use crate::config::PantsConfig;
+use crate::user_config::UserConfig;
+use crate::version;
+
+/// Runs the checks of the scie-pants installation for the `doctor` boot command and returns the
+/// exit code to use.
+///
+/// The scie-jump passes the locations of the scie-pants caches via env vars set in the `doctor`
+/// command of the lift manifest.
+pub(crate) fn run() -> Result<i32> {
+    let mut doctor = Doctor::default();
+
+    let scie_base = doctor.check("SCIE base directory", Critical::Yes, || {
+        let scie_base = required_env_path("SCIE_PANTS_BASE")?;
+        if !scie_base.is_dir() {
+            bail!("{path} is not a directory", path = scie_base.display());
+        }
+        Ok((scie_base.display().to_string(), scie_base))
+    });
+
+    doctor.check("tools.pex", Critical::No, || {
+        let Some(ref scie_base) = scie_base else {
+            bail!("The SCIE base directory is not available.");
+        };
+        let hash = env::var("SCIE_PANTS_TOOLS_PEX_HASH")
+            .context("The SCIE_PANTS_TOOLS_PEX_HASH env var is not set.")?;
+        let tools_pex = scie_base.join(hash).join("tools.pex");
+        if !tools_pex.is_file() {
+            bail!(
+                "{path} has not been extracted yet; it will be on the next Pants run",
+                path = tools_pex.display()
+            );
+        }
+        Ok((tools_pex.display().to_string(), ()))
+    });
+
+    let pants_config = doctor.check("Pants build root", Critical::Yes, || {
+        let build_root = BuildRoot::find_from_env()?.context(
+            "No build root marker file, like pants.toml, was found at or above the current \
+            directory and PANTS_BUILDROOT_OVERRIDE is not set.",
+        )?;
+        let pants_config = PantsConfig::parse(build_root)?;
+        Ok((
+            pants_config.build_root().display().to_string(),
+            pants_config,
+        ))
+    });
+
+    let pants_version = doctor.check("Pants version", Critical::Yes, || {
+        let env_version = env::var("PANTS_VERSION")
+            .ok()
+            .filter(|value| !value.is_empty());
+        if env_version.is_none() {
+            if let Some(range) = pants_config
+                .as_ref()
+                .and_then(PantsConfig::package_version_range)
+            {
+                for bound in range.min.iter().chain(range.max.iter()) {
+                    version::validate(bound)?;
+                }
+                return Ok((
+                    format!(
+                        "The newest release matching `{specifier}` (via `[GLOBAL] \
+                        pants_version`)",
+                        specifier = range.specifier()
+                    ),
+                    None,
+                ));
+            }
+        }
+        let pants_version = match env_version {
+            Some(pants_version) => Some((pants_version, "the PANTS_VERSION env var")),
+            None => pants_config
+                .as_ref()
+                .and_then(PantsConfig::package_version)
+                .map(|pants_version| (pants_version, "`[GLOBAL] pants_version`"))
+                .or_else(|| {
+                    UserConfig::load().1.default_version.map(|default_version| {
+                        (default_version, "the user config `default_version`")
+                    })
+                }),
+        };
+        let Some((pants_version, source)) = pants_version else {
+            bail!("No Pants version is configured; you'll be prompted for one.");
+        };
+        version::validate(&pants_version)?;
+        Ok((
+            format!("{pants_version} (via {source})"),
+            Some(pants_version),
+        ))
+    });
+
+    doctor.check("Pants native client", Critical::Yes, || {
+        let pants_version = match pants_version {
+            Some(Some(ref pants_version)) => pants_version,
+            Some(None) => {
+                return Ok((
+                    "The Pants version range will be resolved on the next Pants run".to_string(),
+                    (),
+                ))
+            }
+            None => bail!("The Pants version is not known."),
+        };
+        let bindings = required_env_path("SCIE_PANTS_BINDINGS")?;
+        let venv = bindings.join("venvs").join(pants_version);
+        if !venv.is_dir() {
+            return Ok((
+                format!(
+                    "Pants {pants_version} is not installed yet; it will be on the next Pants run"
+                ),
+                (),
+            ));
+        }
+        let Some(native_client) = find_native_client(&venv)? else {
+            return Ok((
+                format!(
+                    "Pants {pants_version} does not use a native client; {venv} uses the Pants \
+                    server executable",
+                    venv = venv.display()
+                ),
+                (),
+            ));
+        };
+        if !is_executable(&native_client)? {
+            bail!("{path} is not executable", path = native_client.display());
+        }
+        Ok((native_client.display().to_string(), ()))
+    });
+
+    Ok(if doctor.critical_failure { 1 } else { 0 })
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Critical {
+    Yes,
+    No,
+}
+
+#[derive(Default)]
+struct Doctor {
+    critical_failure: bool,
+}
+
+impl Doctor {
+    // N.B.: Each check returns a report of what it found alongside any value later checks need.
+    fn check<T>(
+        &mut self,
+        name: &str,
+        critical: Critical,
+        check: impl FnOnce() -> Result<(String, T)>,
+    ) -> Option<T> {
+        match check() {
+            Ok((report, value)) => {
+                println!("PASS {name}: {report}");
+                Some(value)
+            }
+            Err(err) => {
+                if critical == Critical::Yes {
+                    self.critical_failure = true;
+                    println!("FAIL {name}: {err:#}");
+                } else {
+                    println!("WARN {name}: {err:#}");
+                }
+                None
+            }
+        }
+    }
+}
+
+fn required_env_path(name: &str) -> Result<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            anyhow!("The {name} env var is not set; run the doctor via SCIE_BOOT=doctor.")
+        })
+}
+
+// N.B.: This mirrors the `lib/python*/site-packages/pants/bin/native_client` glob the install tool
+// uses to find the native client.
+fn find_native_client(venv: &Path) -> Result<Option<PathBuf>> {
+    let lib = venv.join("lib");
+    let entries = std::fs::read_dir(&lib)
+        .with_context(|| format!("Failed to read {path}", path = lib.display()))?;
+    for entry in entries {
+        let entry =
+            entry.with_context(|| format!("Failed to read {path}", path = lib.display()))?;
+        if !entry.file_name().to_string_lossy().starts_with("python") {
+            continue;
+        }
+        let native_client = entry
+            .path()
+            .join("site-packages")
+            .join("pants")
+            .join("bin")
+            .join("native_client");
+        if native_client.exists() {
+            return Ok(Some(native_client));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(unix)]
+pub(crate) fn is_executable(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = std::fs::metadata(path)
+        .with_context(|| format!("Failed to stat {path}", path = path.display()))?;
+    Ok(metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(windows)]
+pub(crate) fn is_executable(path: &Path) -> Result<bool> {
+    Ok(path.is_file())
+}
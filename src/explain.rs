@@ -0,0 +1,34 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use scie_pants::VersionSource;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub(crate) struct Resolution {
+    pub(crate) pants_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) pants_version_specifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) pants_sha: Option<String>,
+    pub(crate) source: VersionSource,
+    pub(crate) build_root: Option<PathBuf>,
+    pub(crate) scie_boot: Option<String>,
+}
+
+pub(crate) fn requested() -> bool {
+    matches!(env::var_os("PANTS_BOOTSTRAP_EXPLAIN"), Some(value) if !value.is_empty())
+}
+
+pub(crate) fn report(resolution: Resolution) -> Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&resolution)
+            .context("Failed to serialize the Pants version resolution.")?
+    );
+    std::process::exit(0)
+}
@@ -0,0 +1,79 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::ffi::OsString;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+
+const EXTRA_ENV_VAR: &str = "SCIE_PANTS_EXTRA_ENV";
+
+/// The env vars set by the `KEY=VALUE` file `SCIE_PANTS_EXTRA_ENV` points to, if any.
+///
+/// Vars already set in the environment win over the file; so these are only the vars from the
+/// file that are not.
+pub(crate) fn extra_env() -> Result<Vec<(OsString, OsString)>> {
+    let Some(path) = env::var_os(EXTRA_ENV_VAR).filter(|value| !value.is_empty()) else {
+        return Ok(vec![]);
+    };
+    let path = Path::new(&path);
+    let contents = std::fs::read_to_string(path).with_context(|| {
+        format!(
+            "Failed to read the {EXTRA_ENV_VAR} file {path}",
+            path = path.display()
+        )
+    })?;
+
+    let mut extra_env = vec![];
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, value)) = parse_line(line) else {
+            warn!(
+                "Skipping malformed line {line_number} of the {EXTRA_ENV_VAR} file {path}, \
+                expected `KEY=VALUE`: {line}",
+                line_number = index + 1,
+                path = path.display()
+            );
+            continue;
+        };
+        if env::var_os(name).is_some() {
+            debug!(
+                "Ignoring {name} from the {EXTRA_ENV_VAR} file {path} since it is already set in \
+                the environment.",
+                path = path.display()
+            );
+            continue;
+        }
+        extra_env.push((name.into(), value.into()));
+    }
+    Ok(extra_env)
+}
+
+fn parse_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let (name, value) = line.split_once('=')?;
+    let name = name.trim();
+    let mut chars = name.chars();
+    if !chars
+        .next()
+        .is_some_and(|char| char.is_ascii_alphabetic() || char == '_')
+        || !chars.all(|char| char.is_ascii_alphanumeric() || char == '_')
+    {
+        return None;
+    }
+    let value = value.trim();
+    let value = ['"', '\'']
+        .into_iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(quote)
+                .and_then(|value| value.strip_suffix(quote))
+        })
+        .unwrap_or(value);
+    Some((name, value))
+}
@@ -0,0 +1,105 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+use crate::config::{self, PantsConfig};
+
+// N.B.: The configure binding looks up the latest stable Pants release when the prompt is accepted;
+// the launcher can't do that without network access; so the preview stands in this placeholder.
+const LATEST_STABLE_PLACEHOLDER: &str = "<latest stable Pants release>";
+
+// N.B.: Pants has no `--init-dry-run` option, but we only claim it when it is the sole argument;
+// so it can never be mistaken for part of a Pants command line.
+pub(crate) fn requested() -> bool {
+    let mut args = env::args_os().skip(1);
+    matches!(
+        (args.next(), args.next()),
+        (Some(arg), None) if arg == "--init-dry-run"
+    )
+}
+
+/// Prints the `pants.toml` the version prompt would write, if any, without writing it.
+pub(crate) fn dry_run(pants_installation: Option<PantsConfig>) -> Result<()> {
+    if env::var_os("PANTS_VERSION").is_some_and(|value| !value.is_empty())
+        || env::var_os("PANTS_SHA").is_some_and(|value| !value.is_empty())
+    {
+        eprintln!(
+            "The Pants version is set via the environment; so there would be no prompt and \
+            nothing would be written."
+        );
+        return Ok(());
+    }
+    let (pants_toml, contents) = match pants_installation {
+        Some(pants_config)
+            if pants_config.package_version().is_some()
+                || pants_config.package_version_range().is_some() =>
+        {
+            eprintln!(
+                "Pants is already configured for the build root at {build_root}; so there would \
+                be no prompt and nothing would be written.",
+                build_root = pants_config.build_root().display()
+            );
+            return Ok(());
+        }
+        Some(pants_config) => {
+            let pants_toml = pants_config.build_root().join(config::config_name()?);
+            let contents = std::fs::read_to_string(&pants_toml).with_context(|| {
+                format!(
+                    "Failed to read the Pants config at {path}",
+                    path = pants_toml.display()
+                )
+            })?;
+            (pants_toml, Some(contents))
+        }
+        None => (new_build_root()?.join(config::config_name()?), None),
+    };
+    let mut config = contents
+        .as_deref()
+        .unwrap_or_default()
+        .parse::<toml_edit::Document>()
+        .with_context(|| {
+            format!(
+                "Failed to parse the Pants config at {path}",
+                path = pants_toml.display()
+            )
+        })?;
+    let global = config
+        .entry("GLOBAL")
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .context("Expected `GLOBAL` to be a table in the Pants config.")?;
+    global.insert("pants_version", toml_edit::value(LATEST_STABLE_PLACEHOLDER));
+
+    eprintln!(
+        "If you accepted the prompt, scie-pants would {action} {path} with these contents:",
+        action = if contents.is_some() {
+            "back up and re-write"
+        } else {
+            "create"
+        },
+        path = pants_toml.display()
+    );
+    print!("{config}");
+    Ok(())
+}
+
+// N.B.: This mirrors the configure binding, which offers to set up the root of the git repo
+// containing the CWD, if any, or else the CWD itself as a new Pants project.
+fn new_build_root() -> Result<PathBuf> {
+    let cwd = env::current_dir().context("Failed to determine the current directory.")?;
+    let toplevel = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|toplevel| Path::new(toplevel.trim()).to_path_buf());
+    Ok(toplevel.unwrap_or(cwd))
+}
@@ -0,0 +1,22 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+//! The Pants version resolution `scie-pants` performs when launching Pants, for tools that want
+//! to pick the same Pants version without shelling out to `scie-pants`.
+
+// N.B.: These modules are shared with the `scie-pants` binary; they are not part of the API.
+#[doc(hidden)]
+pub mod build_root;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod remote_config;
+mod resolve;
+#[doc(hidden)]
+pub mod user_config;
+#[doc(hidden)]
+pub mod version;
+
+pub use resolve::{
+    resolve_pants_version, resolve_pants_version_for, Env, ResolvedVersion, VersionSource,
+};
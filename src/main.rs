@@ -4,43 +4,210 @@
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use build_root::BuildRoot;
-use log::{info, trace};
+use log::{debug, error, info, trace, warn};
 use logging_timer::{time, timer, Level};
+use scie_pants::{build_root, config, remote_config, user_config, version, Env, VersionSource};
+use serde_json::json;
 use uuid::Uuid;
 
 use crate::config::PantsConfig;
+use crate::explain::Resolution;
+use crate::watchdog::Watchdog;
 
-mod build_root;
-mod config;
+mod doctor;
+mod explain;
+mod extra_env;
+mod init;
+mod metrics;
+#[cfg(unix)]
+mod pants_bootstrap;
+mod pants_clone;
+mod proxy;
+mod timing;
+mod update_signature;
+mod watchdog;
+#[cfg(windows)]
+mod windows;
 
 const SCIE_PANTS_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// N.B.: These exit codes are documented in the README for scripts that wrap scie-pants; so they
+// must not change. Any other failure of scie-pants itself exits with EXIT_CODE_ERROR.
+pub(crate) const EXIT_CODE_ERROR: i32 = 1;
+// No Pants version is configured and none was chosen; either since scie-pants could not prompt for
+// one or since the prompt was declined.
+const EXIT_CODE_NO_PANTS_VERSION: i32 = 3;
+
 #[derive(Debug, Default)]
 struct Process {
     exe: OsString,
     args: Vec<OsString>,
     env: Vec<(OsString, OsString)>,
+    pants_bootstrap: Vec<PathBuf>,
+    prompt_for_version: bool,
+    env_allowlist: Option<Vec<OsString>>,
 }
 
 impl Process {
+    /// Launches the process, returning its exit code when it is not exec'd.
+    fn launch(mut self) -> Result<i32> {
+        if !self.prompt_for_version {
+            return self.exec();
+        }
+        // N.B.: The version prompt is run by the configure binding in the scie and scie-jump
+        // reports a failed binding with its own generic exit code. So we wait on the scie to learn
+        // whether the prompt was declined from a file the configure binding writes to.
+        let prompt_declined = tempfile::Builder::new()
+            .prefix("scie-pants-prompt-declined.")
+            .tempfile()
+            .context("Failed to create a file to record a declined Pants version prompt in.")?
+            .into_temp_path();
+        self.env.push((
+            "SCIE_PANTS_PROMPT_DECLINED_FILE".into(),
+            prompt_declined.as_os_str().to_os_string(),
+        ));
+        let exit_code = self.spawn()?;
+        let declined = std::fs::metadata(&prompt_declined)
+            .map(|metadata| metadata.len() > 0)
+            .unwrap_or_default();
+        Ok(if exit_code != 0 && declined {
+            EXIT_CODE_NO_PANTS_VERSION
+        } else {
+            exit_code
+        })
+    }
+
+    /// Returns whether the inherited env var `name` should be passed through to the process.
+    fn env_allowed(&self, name: &OsStr) -> bool {
+        let Some(ref allowlist) = self.env_allowlist else {
+            return true;
+        };
+        allowlist.iter().any(|allowed| {
+            if cfg!(windows) {
+                allowed.eq_ignore_ascii_case(name)
+            } else {
+                allowed == name
+            }
+        })
+    }
+
+    /// Returns the inherited env the process should start from if it is not the full parent env.
+    fn inherited_env(&self) -> Option<Vec<(OsString, OsString)>> {
+        self.env_allowlist.as_ref()?;
+        Some(
+            env::vars_os()
+                .filter(|(name, _)| self.env_allowed(name))
+                .collect(),
+        )
+    }
+
+    /// Prints the env the process would be launched with, one `KEY=VALUE` per line, instead of
+    /// launching it.
+    fn dump_env(&self) -> Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        for (name, value) in &self.env {
+            writeln!(stdout, "{}={}", display_env(name), display_env(value))?;
+        }
+        if !self.pants_bootstrap.is_empty() {
+            writeln!(
+                stdout,
+                "# Exported by {}:",
+                display_paths(&self.pants_bootstrap)
+            )?;
+            for (name, value) in self.pants_bootstrap_env(&self.pants_bootstrap)? {
+                writeln!(stdout, "{}={}", display_env(&name), display_env(&value))?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn pants_bootstrap_env(
+        &self,
+        pants_bootstrap: &[PathBuf],
+    ) -> Result<Vec<(OsString, OsString)>> {
+        pants_bootstrap::source(pants_bootstrap, &self.env, true)
+    }
+
+    #[cfg(windows)]
+    fn pants_bootstrap_env(
+        &self,
+        pants_bootstrap: &[PathBuf],
+    ) -> Result<Vec<(OsString, OsString)>> {
+        Err(anyhow!(
+            "Sourcing {paths} is not supported on Windows.",
+            paths = display_paths(pants_bootstrap)
+        ))
+    }
+
     #[cfg(windows)]
     fn exec(self) -> Result<i32> {
+        self.spawn()
+    }
+
+    #[cfg(windows)]
+    fn spawn(self) -> Result<i32> {
         use std::process::Command;
 
-        let exit_status = Command::new(&self.exe)
+        // N.B.: We install our console control handler before spawning Pants; so a Ctrl-C or
+        // Ctrl-Break can never kill us before we're waiting on Pants to handle it and exit.
+        windows::forward_console_ctrl_events()?;
+        let mut command = Command::new(&self.exe);
+        if let Some(inherited_env) = self.inherited_env() {
+            command.env_clear().envs(inherited_env);
+        }
+        let mut child = command
             .args(&self.args)
             .args(env::args().skip(1))
             .envs(self.env.clone())
-            .spawn()?
+            .spawn()
+            .with_context(|| format!("Failed to spawn process: {self:#?}"))?;
+        // N.B.: Pants is already running; so failing here would orphan it. We just lose the
+        // guarantee Pants is killed along with us.
+        if let Err(err) = windows::kill_on_exit(&child) {
+            warn!("Pants will not be terminated if scie-pants is killed: {err:#}");
+        }
+        let exit_status = child
             .wait()
             .with_context(|| format!("Failed to execute process: {self:#?}"))?;
-        Ok(exit_status
-            .code()
-            .unwrap_or_else(|| if exit_status.success() { 0 } else { 1 }))
+        Ok(exit_code(exit_status))
+    }
+
+    #[cfg(unix)]
+    fn spawn(self) -> Result<i32> {
+        use std::process::Command;
+
+        use nix::sys::signal::{signal, SigHandler, Signal};
+
+        let mut command = Command::new(&self.exe);
+        if let Some(inherited_env) = self.inherited_env() {
+            command.env_clear().envs(inherited_env);
+        }
+        let mut child = command
+            .args(&self.args)
+            .args(env::args_os().skip(1))
+            .envs(self.env.clone())
+            .spawn()
+            .with_context(|| format!("Failed to spawn process: {self:#?}"))?;
+        // N.B.: Like `system(3)`, we leave handling a Ctrl-C or Ctrl-\ at the terminal to the child,
+        // which receives it too; we only ignore these after spawning it so that it does not inherit
+        // our ignoring them.
+        for sig in [Signal::SIGINT, Signal::SIGQUIT] {
+            unsafe { signal(sig, SigHandler::SigIgn) }
+                .with_context(|| format!("Failed to ignore {sig} while waiting on Pants."))?;
+        }
+        let exit_status = child
+            .wait()
+            .with_context(|| format!("Failed to execute process: {self:#?}"))?;
+        Ok(exit_code(exit_status))
     }
 
     #[cfg(unix)]
@@ -50,6 +217,13 @@ impl Process {
 
         use nix::unistd::execv;
 
+        for (name, _) in env::vars_os() {
+            if !self.env_allowed(&name) {
+                env::remove_var(name);
+            }
+        }
+
+        let exe = PathBuf::from(&self.exe);
         let c_exe = CString::new(self.exe.into_vec())
             .context("Failed to convert executable to a C string.")?;
 
@@ -71,7 +245,67 @@ impl Process {
 
         execv(&c_exe, &c_args)
             .map(|_| 0)
-            .context("Failed to exec process.")
+            .with_context(|| format!("Failed to exec {exe}.", exe = exe.display()))
+    }
+}
+
+// N.B.: Names are separated by commas and the vars scie-pants sets for Pants are always passed
+// through; so an empty allowlist still launches Pants, just with no inherited env at all.
+fn env_allowlist() -> Option<Vec<OsString>> {
+    let allowlist = env::var_os("SCIE_PANTS_ENV_ALLOWLIST")?;
+    let allowlist = allowlist.to_string_lossy();
+    Some(
+        allowlist
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(OsString::from)
+            .collect(),
+    )
+}
+
+// N.B.: A process killed by a signal has no exit code; so we encode the signal as `128 + signum`
+// just as shells do, e.g.: 130 for SIGINT or 143 for SIGTERM. Windows processes always have an
+// exit code.
+fn exit_code(exit_status: ExitStatus) -> i32 {
+    if let Some(code) = exit_status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        if let Some(signal) = exit_status.signal() {
+            return 128 + signal;
+        }
+    }
+    if exit_status.success() {
+        0
+    } else {
+        1
+    }
+}
+
+// N.B.: Names and values are printed as-is when they're UTF-8 free of control characters and with
+// non-printable bytes escaped otherwise; e.g.: `\xff` or `\n`; so each var takes exactly one line.
+#[cfg(unix)]
+fn display_env(value: &OsStr) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    match value.to_str() {
+        Some(value) if !value.contains(char::is_control) => value.to_owned(),
+        _ => value.as_bytes().escape_ascii().to_string(),
+    }
+}
+
+#[cfg(windows)]
+fn display_env(value: &OsStr) -> String {
+    match value.to_str() {
+        Some(value) if !value.contains(char::is_control) => value.to_owned(),
+        _ => {
+            let debug = format!("{value:?}");
+            debug[1..debug.len() - 1].to_owned()
+        }
     }
 }
 
@@ -87,27 +321,354 @@ fn env_version(env_var_name: &str) -> Result<Option<String>> {
     }
 }
 
+// N.B.: Prompting needs a human at a terminal; otherwise, say in CI, the prompt would fail or hang
+// waiting on a STDIN nobody writes to. Prompting can be forced with `SCIE_PANTS_NO_PROMPT=0` for
+// answers piped to STDIN.
+fn no_prompt_reason(configured: Option<bool>) -> Result<Option<&'static str>> {
+    use std::io::IsTerminal;
+
+    match env_version("SCIE_PANTS_NO_PROMPT")?
+        .as_deref()
+        .map(str::trim)
+    {
+        Some("0") => Ok(None),
+        Some("1") => Ok(Some("SCIE_PANTS_NO_PROMPT=1 is set")),
+        Some(value) => bail!("SCIE_PANTS_NO_PROMPT must be either `0` or `1`, got: `{value}`."),
+        None => match configured {
+            Some(false) => Ok(None),
+            Some(true) => Ok(Some("`no_prompt` is enabled in .scie-pants.toml")),
+            None if std::io::stdin().is_terminal() => Ok(None),
+            None => Ok(Some("STDIN is not a terminal")),
+        },
+    }
+}
+
+fn env_flag(name: &str) -> Result<bool> {
+    env_flag_or(name, None)
+}
+
+// N.B.: The env var, when set, takes precedence over the value configured in the
+// `.scie-pants.toml` of the build root.
+fn env_flag_or(name: &str, configured: Option<bool>) -> Result<bool> {
+    match env_version(name)?.as_deref().map(str::trim) {
+        Some("0") => Ok(false),
+        Some("1") => Ok(true),
+        Some(value) => bail!("{name} must be either `0` or `1`, got: `{value}`."),
+        None => Ok(configured.unwrap_or_default()),
+    }
+}
+
+fn quiet(configured: Option<bool>) -> Result<bool> {
+    env_flag_or("SCIE_PANTS_QUIET", configured)
+}
+
+// N.B.: The configure and install bindings read SCIE_PANTS_OFFLINE too and refuse to download
+// anything in offline mode.
+pub(crate) fn offline() -> Result<bool> {
+    env_flag("SCIE_PANTS_OFFLINE")
+}
+
+const DEFAULT_DOWNLOAD_RETRIES: u32 = 3;
+const DEFAULT_PANTS_DEBUG_PORT: u16 = 5678;
+
+// N.B.: The downloads themselves are done by the tools in the configure and install bindings; we
+// just validate the number of retries up front and always export it for them.
+fn download_retries(configured: Option<u32>) -> Result<u32> {
+    match env_version("SCIE_PANTS_DOWNLOAD_RETRIES")? {
+        Some(retries) => retries.trim().parse().map_err(|_| {
+            anyhow!(
+                "SCIE_PANTS_DOWNLOAD_RETRIES must be a non-negative integer number of times to \
+                retry failed downloads, got: `{retries}`."
+            )
+        }),
+        None => Ok(configured.unwrap_or(DEFAULT_DOWNLOAD_RETRIES)),
+    }
+}
+
+// N.B.: PANTS_DEBUG historically just needed to be non-empty to turn on debugging; so we accept
+// the common spellings of true and false in addition to a port for the debugpy server to listen on.
+fn pants_debug_port() -> Result<Option<u16>> {
+    match env_version("PANTS_DEBUG")? {
+        Some(value) => parse_pants_debug(&value),
+        None => Ok(None),
+    }
+}
+
+fn parse_pants_debug(value: &str) -> Result<Option<u16>> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" | "0" | "false" | "no" | "off" => Ok(None),
+        "1" | "true" | "yes" | "on" => Ok(Some(DEFAULT_PANTS_DEBUG_PORT)),
+        port => match port.parse::<u16>() {
+            Ok(port) if port > 0 => Ok(Some(port)),
+            _ => bail!(
+                "PANTS_DEBUG must be either a boolean, like `1` or `true`, or the port number for \
+                the debugpy server to listen on, like `5678`, got: `{value}`."
+            ),
+        },
+    }
+}
+
+// N.B.: The scie-jump defaults SCIE_BASE to the `nce` directory of the user cache directory. On
+// Linux, we keep the caches of the Pants installs we launch under the XDG_CACHE_HOME the user chose
+// instead; we can't do the same for the scie we're running in since it has already been extracted.
+#[cfg(target_os = "linux")]
+fn default_scie_base() -> Option<PathBuf> {
+    if env::var_os("SCIE_BASE").is_some_and(|value| !value.is_empty()) {
+        return None;
+    }
+    // Per the XDG Base Directory Specification, relative paths are invalid and should be ignored.
+    let xdg_cache_home = PathBuf::from(env::var_os("XDG_CACHE_HOME")?);
+    if !xdg_cache_home.is_absolute() {
+        return None;
+    }
+    Some(xdg_cache_home.join("scie-pants"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn default_scie_base() -> Option<PathBuf> {
+    None
+}
+
+// N.B.: Installing Pants onto a network filesystem works, but it is very slow; so we point this out
+// once per SCIE_BASE, recording that we did with a marker file in it.
+const NETWORK_FS_WARNING_MARKER: &str = ".scie-pants-network-fs-warned";
+
+// The SCIE_BASE the scie-jump will use given the one we're exporting, if any.
+fn effective_scie_base(scie_base: Option<&Path>) -> Option<PathBuf> {
+    match scie_base {
+        Some(scie_base) => Some(scie_base.to_path_buf()),
+        None => match env::var_os("SCIE_BASE").filter(|value| !value.is_empty()) {
+            Some(scie_base) => Some(PathBuf::from(scie_base)),
+            None => dirs::cache_dir().map(|cache_dir| cache_dir.join("nce")),
+        },
+    }
+}
+
+fn warn_if_scie_base_is_networked(scie_base: Option<&Path>, quiet: bool) -> Result<()> {
+    if quiet {
+        return Ok(());
+    }
+    let Some(scie_base) = effective_scie_base(scie_base) else {
+        return Ok(());
+    };
+    let marker = scie_base.join(NETWORK_FS_WARNING_MARKER);
+    if marker.exists() {
+        return Ok(());
+    }
+    // The SCIE_BASE may not have been created yet; in which case it will be created on the
+    // filesystem of its nearest existing ancestor.
+    let Some(existing) = scie_base.ancestors().find(|path| path.exists()) else {
+        return Ok(());
+    };
+    let Some(fs_type) = network_fs_type(existing) else {
+        return Ok(());
+    };
+    warn!(
+        "The SCIE_BASE of {scie_base} is on a {fs_type} network filesystem. Installing and \
+        running Pants from there can be very slow. Consider exporting SCIE_BASE as a path on a \
+        local filesystem instead. Export SCIE_PANTS_QUIET=1 to silence this warning.",
+        scie_base = scie_base.display()
+    );
+    if std::fs::create_dir_all(&scie_base)
+        .and_then(|()| std::fs::write(&marker, fs_type))
+        .is_err()
+    {
+        debug!(
+            "Failed to record that the network filesystem warning was issued at {marker}.",
+            marker = marker.display()
+        );
+    }
+    Ok(())
+}
+
+// N.B.: The x86_64 scie-pants works under Rosetta 2 on Apple Silicon, but it's slow and it installs
+// x86_64 Pants wheels; so we point this out once per SCIE_BASE, just like the network filesystem
+// warning.
+const ROSETTA_WARNING_MARKER: &str = ".scie-pants-rosetta-warned";
+
+fn warn_if_translated(scie_base: Option<&Path>, quiet: bool) {
+    if quiet {
+        return;
+    }
+    let Some(scie_base) = effective_scie_base(scie_base) else {
+        return;
+    };
+    let marker = scie_base.join(ROSETTA_WARNING_MARKER);
+    if marker.exists() || !is_translated() {
+        return;
+    }
+    warn!(
+        "This x86_64 scie-pants is running under Rosetta 2 emulation on an Apple Silicon Mac. \
+        That makes Pants slow to start and has it install x86_64 wheels. Install the native \
+        scie-pants-macos-aarch64 binary instead. Export SCIE_PANTS_QUIET=1 to silence this \
+        warning."
+    );
+    if std::fs::create_dir_all(&scie_base)
+        .and_then(|()| std::fs::write(&marker, ""))
+        .is_err()
+    {
+        debug!(
+            "Failed to record that the Rosetta warning was issued at {marker}.",
+            marker = marker.display()
+        );
+    }
+}
+
+/// Returns true if this process is an x86_64 binary being translated by Rosetta 2.
+#[cfg(target_os = "macos")]
+fn is_translated() -> bool {
+    // See: https://developer.apple.com/documentation/apple-silicon/about-the-rosetta-translation-environment
+    let mut translated: libc::c_int = 0;
+    let mut size = std::mem::size_of::<libc::c_int>();
+    let result = unsafe {
+        libc::sysctlbyname(
+            b"sysctl.proc_translated\0".as_ptr().cast(),
+            (&mut translated as *mut libc::c_int).cast(),
+            &mut size,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    // N.B.: The sysctl does not exist on Intel Macs older than Big Sur; so a failure means native.
+    result == 0 && translated == 1
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_translated() -> bool {
+    false
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn network_fs_type(path: &Path) -> Option<&'static str> {
+    // See `man 2 statfs` and linux/magic.h; not all of these magic numbers are exposed by nix.
+    const NETWORK_FS_MAGIC: [(u32, &str); 11] = [
+        (0x6969, "nfs"),
+        (0x517B, "smb"),
+        (0xFE53_4D42, "smb2"),
+        (0xFF53_4D42, "cifs"),
+        (0x5346_414F, "afs"),
+        (0x00C3_6400, "ceph"),
+        (0x7375_7245, "coda"),
+        (0x564C, "ncp"),
+        (0x0102_1997, "9p"),
+        (0x0BD0_0BD0, "lustre"),
+        (0x4750_4653, "gpfs"),
+    ];
+    let statfs = nix::sys::statfs::statfs(path).ok()?;
+    // N.B.: The width and signedness of the magic number varies by platform, but the magic numbers
+    // themselves are all 32 bits.
+    let magic = statfs.filesystem_type().0 as u32;
+    NETWORK_FS_MAGIC
+        .iter()
+        .find_map(|(network_magic, fs_type)| (magic == *network_magic).then_some(*fs_type))
+}
+
+#[cfg(target_os = "macos")]
+fn network_fs_type(path: &Path) -> Option<&'static str> {
+    const NETWORK_FS_TYPES: [&str; 5] = ["nfs", "smbfs", "afpfs", "webdav", "cifs"];
+    let statfs = nix::sys::statfs::statfs(path).ok()?;
+    let fs_type_name = statfs.filesystem_type_name();
+    NETWORK_FS_TYPES
+        .into_iter()
+        .find(|fs_type| *fs_type == fs_type_name)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+fn network_fs_type(_path: &Path) -> Option<&'static str> {
+    None
+}
+
 fn find_pants_installation() -> Result<Option<PantsConfig>> {
-    if let Ok(build_root) = BuildRoot::find(None) {
-        let pants_config = PantsConfig::parse(build_root)?;
-        return Ok(Some(pants_config));
+    timing::time("build root discovery", BuildRoot::find_from_env)?
+        .map(|build_root| timing::time("config parse", || PantsConfig::parse(build_root)))
+        .transpose()
+}
+
+// N.B.: Scripts commonly size their output with `tput cols` and the like, but `tput` is missing from
+// some minimal environments and fails when TERM is not set, which aborts sourcing under `set -e`.
+// In either case, we stand in a `tput` function that answers `cols` and `lines` from COLUMNS and
+// LINES, or the classic 80x24 terminal size, and outputs nothing for any other capability.
+const TPUT_SHIM: &str = r#"if [[ -z "${TERM:-}" ]] || ! command -v tput >/dev/null; then tput() { case "${1:-}" in cols) echo "${COLUMNS:-80}";; lines) echo "${LINES:-24}";; esac; }; fi"#;
+
+// N.B.: The `.pants.bootstrap` script is sourced with the `bash` found on the PATH unless
+// SCIE_PANTS_BASH is set to the path or name of a specific bash to use instead.
+#[cfg(unix)]
+fn bash() -> (OsString, Vec<OsString>) {
+    match env::var_os("SCIE_PANTS_BASH") {
+        Some(bash) if !bash.is_empty() => {
+            if Path::new(&bash).components().count() > 1 {
+                (bash, vec![])
+            } else {
+                ("/usr/bin/env".into(), vec![bash])
+            }
+        }
+        _ => ("/usr/bin/env".into(), vec!["bash".into()]),
+    }
+}
+
+// N.B.: On Windows, the process is spawned and a bare executable name is searched for on the PATH;
+// so there is no need for `/usr/bin/env` (which Git Bash provides, but only to its own programs).
+#[cfg(windows)]
+fn bash() -> (OsString, Vec<OsString>) {
+    match env::var_os("SCIE_PANTS_BASH") {
+        Some(bash) if !bash.is_empty() => (bash, vec![]),
+        _ => ("bash".into(), vec![]),
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns the `.pants.bootstrap` script and the `*.sh` fragments in `.pants.bootstrap.d/` found
+/// in the build root, in the order they should be sourced.
+fn pants_bootstrap_scripts(build_root: &Path) -> Result<Vec<PathBuf>> {
+    let mut scripts = vec![];
+    let pants_bootstrap = build_root.join(".pants.bootstrap");
+    if pants_bootstrap.is_file() {
+        scripts.push(pants_bootstrap);
     }
-    Ok(None)
+    let fragments_dir = build_root.join(".pants.bootstrap.d");
+    if fragments_dir.is_dir() {
+        let mut fragments = std::fs::read_dir(&fragments_dir)
+            .with_context(|| format!("Failed to list {path}", path = fragments_dir.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Failed to list {path}", path = fragments_dir.display()))?;
+        fragments.retain(|path| {
+            path.extension().map(|ext| ext == "sh").unwrap_or_default() && path.is_file()
+        });
+        fragments.sort();
+        scripts.extend(fragments);
+    }
+    Ok(scripts)
 }
 
 #[derive(Eq, PartialEq)]
 enum ScieBoot {
     BootstrapTools,
+    CacheKey,
+    Doctor,
     Pants,
     PantsDebug,
+    VerifyUpdate,
+    Version,
 }
 
 impl ScieBoot {
     fn env_value(&self) -> OsString {
         match self {
             ScieBoot::BootstrapTools => "bootstrap-tools",
+            ScieBoot::CacheKey => "cache-key",
+            ScieBoot::Doctor => "doctor",
             ScieBoot::Pants => "pants",
             ScieBoot::PantsDebug => "pants-debug",
+            ScieBoot::VerifyUpdate => "verify-update",
+            ScieBoot::Version => "version",
         }
         .into()
     }
@@ -118,40 +679,89 @@ impl ScieBoot {
             .context("Shell-quoted value could not be interpreted as UTF-8.")
     }
 
+    // N.B.: The shell_quote crate assumes unix and fails to compile on Windows; so we quote for
+    // Git Bash ourselves. Git Bash accepts Windows paths written with forward slashes, which avoids
+    // backslashes being treated as escapes; so we normalize those and then single-quote the value,
+    // ending and re-opening the quotes around any embedded single quote just as bash expects.
     #[cfg(windows)]
-    fn quote<T: Into<OsString> + Debug>(_value: T) -> Result<String> {
-        // The shell_quote crate assumes unix and fails to compile on Windows.
-        todo!("TODO(John Sirois): Figure out Git bash? shell quoting for Windows WTF-16 strings.")
+    fn quote<T: Into<OsString> + Debug>(value: T) -> Result<String> {
+        let value = value.into().into_string().map_err(|value| {
+            anyhow!(
+                "Failed to quote {value:?} for Git Bash since it is not valid Unicode; i.e.: it \
+                contains unpaired surrogates."
+            )
+        })?;
+        Ok(format!(
+            "'{quoted}'",
+            quoted = value.replace('\\', "/").replace('\'', r"'\''")
+        ))
+    }
+
+    /// Returns bash commands that source each of the given scripts in turn, naming the script that
+    /// failed if one does.
+    fn source_commands(scripts: &[PathBuf]) -> Result<String> {
+        let mut commands = vec![
+            "set -eou pipefail".to_owned(),
+            TPUT_SHIM.to_owned(),
+            r#"trap 'echo "Failed to source ${__scie_pants_bootstrap}." >&2' EXIT"#.to_owned(),
+        ];
+        for script in scripts {
+            let script = Self::quote(script)?;
+            commands.push(format!("__scie_pants_bootstrap={script}"));
+            commands.push(format!("source {script}"));
+        }
+        commands.push("trap - EXIT".to_owned());
+        commands.push("unset __scie_pants_bootstrap".to_owned());
+        Ok(commands.join("; "))
     }
 
     fn into_process(
         self,
         scie: String,
         build_root: Option<PathBuf>,
-        env: Vec<(OsString, OsString)>,
+        #[allow(unused_mut)] mut env: Vec<(OsString, OsString)>,
     ) -> Result<Process> {
-        Ok(match build_root.map(|br| br.join(".pants.bootstrap")) {
-            Some(pants_bootstrap) if self != Self::BootstrapTools && pants_bootstrap.is_file() => {
-                Process {
-                    exe: "/usr/bin/env".into(),
-                    args: vec![
-                        "bash".into(),
-                        "-c".into(),
-                        format!(
-                            r#"set -eou pipefail; source {bootstrap}; exec {scie} "$0" "$@""#,
-                            bootstrap = Self::quote(pants_bootstrap)?,
-                            scie = Self::quote(scie)?
-                        )
-                        .into(),
-                    ],
-                    env,
-                }
+        let pants_bootstrap = match build_root {
+            Some(ref build_root) if self != Self::BootstrapTools => {
+                pants_bootstrap_scripts(build_root)?
             }
-            _ => Process {
+            _ => vec![],
+        };
+        if pants_bootstrap.is_empty() {
+            return Ok(Process {
                 exe: scie.into(),
                 env,
                 ..Default::default()
-            },
+            });
+        }
+
+        #[cfg(unix)]
+        if let Some(static_env) = pants_bootstrap::static_env(&pants_bootstrap, &env)? {
+            env.extend(static_env);
+            return Ok(Process {
+                exe: scie.into(),
+                env,
+                ..Default::default()
+            });
+        }
+        #[cfg(windows)]
+        metrics::record_pants_bootstrap_cache("uncacheable");
+        let (exe, mut args) = bash();
+        args.extend([
+            "-c".into(),
+            format!(
+                r#"{source}; exec {scie} "$0" "$@""#,
+                source = Self::source_commands(&pants_bootstrap)?,
+                scie = Self::quote(scie)?
+            )
+            .into(),
+        ]);
+        Ok(Process {
+            exe,
+            args,
+            env,
+            pants_bootstrap,
+            ..Default::default()
         })
     }
 }
@@ -159,54 +769,188 @@ impl ScieBoot {
 #[time("debug", "scie-pants::{}")]
 fn get_pants_process() -> Result<Process> {
     let pants_installation = find_pants_installation()?;
-    let (build_root, configured_pants_version, debugpy_version, delegate_bootstrap) =
-        if let Some(ref pants_config) = pants_installation {
-            (
-                Some(pants_config.build_root().to_path_buf()),
-                pants_config.package_version(),
-                pants_config.debugpy_version(),
-                pants_config.delegate_bootstrap(),
-            )
-        } else {
-            (None, None, None, false)
-        };
-
-    let env_pants_version = env_version("PANTS_VERSION")?;
-    let pants_version = if let Some(env_version) = env_pants_version {
-        Some(env_version)
+    let scie_pants_config = pants_installation
+        .as_ref()
+        .map(PantsConfig::scie_pants)
+        .transpose()?
+        .unwrap_or_default();
+    let quiet = quiet(scie_pants_config.quiet)?;
+    let download_retries = download_retries(scie_pants_config.download_retries)?;
+    let proxy_env = proxy::proxy_env()?;
+    let config_name = config::config_name()?;
+    let (
+        build_root,
+        configured_pants_version,
+        debugpy_version,
+        delegate_bootstrap,
+        interpreter_constraints,
+    ) = if let Some(ref pants_config) = pants_installation {
+        (
+            Some(pants_config.build_root().to_path_buf()),
+            pants_config.package_version(),
+            pants_config.debugpy_version(),
+            pants_config.delegate_bootstrap(),
+            pants_config.interpreter_constraints(),
+        )
     } else {
-        configured_pants_version.clone()
+        (None, None, None, None, vec![])
     };
 
-    if delegate_bootstrap && pants_version.is_none() {
-        let exe = build_root
-            .expect("Failed to locate build root")
-            .join("pants")
-            .into_os_string();
+    let resolved_version =
+        scie_pants::resolve_pants_version_for(pants_installation.as_ref(), &Env::current()?)?;
+    let (pants_version, pants_version_specifier, env_pants_sha, version_source) =
+        match resolved_version {
+            Some(resolved_version) => (
+                resolved_version.pants_version,
+                resolved_version.pants_version_specifier,
+                resolved_version.pants_sha,
+                resolved_version.source,
+            ),
+            None => (None, None, None, VersionSource::None),
+        };
+    if let (Some(env_version), Some(configured_version)) = (
+        pants_version
+            .as_ref()
+            .filter(|_| matches!(version_source, VersionSource::Env | VersionSource::DotEnv)),
+        configured_pants_version.as_ref(),
+    ) {
+        if configured_version != env_version && !quiet {
+            warn!(
+                "Using Pants {env_version} from the PANTS_VERSION env var instead of Pants \
+                {configured_version} from `[GLOBAL] pants_version` in {pants_toml}. Unset \
+                PANTS_VERSION to use the configured version or export SCIE_PANTS_QUIET=1 to \
+                silence this warning.",
+                pants_toml = build_root
+                    .as_ref()
+                    .map(|build_root| build_root.join(&config_name))
+                    .unwrap_or_else(|| config_name.clone().into())
+                    .display()
+            );
+        }
+    }
+    if let Some((pants_version, floor)) = pants_version
+        .as_deref()
+        .filter(|_| !quiet)
+        .and_then(|version| Some((version, version::unsupported(version)?)))
+    {
+        warn!(
+            "Pants {pants_version} is end of life; Pants versions older than {minimum} are no \
+            longer supported. See {docs} for how to upgrade. Export SCIE_PANTS_QUIET=1 to silence \
+            this warning.",
+            minimum = floor.minimum,
+            docs = floor.migration_docs
+        );
+    }
+
+    if let (Some(ref script), None, None) = (
+        &delegate_bootstrap,
+        &pants_version,
+        &pants_version_specifier,
+    ) {
+        let build_root = build_root.expect("Failed to locate build root");
+        let exe = build_root.join(script);
+        if !exe.is_file() {
+            bail!(
+                "The `[DEFAULT] delegate_bootstrap` script {exe} does not exist.",
+                exe = exe.display()
+            );
+        }
+        if !doctor::is_executable(&exe)? {
+            bail!(
+                "The `[DEFAULT] delegate_bootstrap` script {exe} is not executable.",
+                exe = exe.display()
+            );
+        }
+        let exe = exe.into_os_string();
+        debug!(
+            "Launch mode: delegating to {exe:?} (via `[DEFAULT] delegate_bootstrap` in the Pants \
+            config with no Pants version set)"
+        );
+        resolved(Resolution {
+            pants_version: None,
+            pants_version_specifier: None,
+            pants_sha: None,
+            source: VersionSource::Delegate,
+            build_root: Some(build_root),
+            scie_boot: None,
+        })?;
         return Ok(Process {
             exe,
             ..Default::default()
         });
     }
 
+    let prompt_for_version =
+        pants_version.is_none() && pants_version_specifier.is_none() && env_pants_sha.is_none();
+    if prompt_for_version {
+        debug!("Launch mode: prompting for a Pants version to use (none is configured)");
+    }
     info!("Found Pants build root at {build_root:?}");
     info!("The required Pants version is {pants_version:?}");
 
-    let scie =
-        env::var("SCIE").context("Failed to retrieve SCIE location from the environment.")?;
-
-    let pants_debug = matches!(env::var_os("PANTS_DEBUG"), Some(value) if !value.is_empty());
+    let pants_debug_port = pants_debug_port()?;
+    let pants_debug = pants_debug_port.is_some();
+    // N.B.: The `cache-key` boot command is a shortcut for the `bootstrap-cache-key` bootstrap tool.
+    let cache_key = env::var_os("SCIE_BOOT") == Some(ScieBoot::CacheKey.env_value());
     let scie_boot = match env::var_os("PANTS_BOOTSTRAP_TOOLS") {
+        _ if cache_key => ScieBoot::BootstrapTools,
         Some(_) => ScieBoot::BootstrapTools,
         None if pants_debug => ScieBoot::PantsDebug,
         None => ScieBoot::Pants,
     };
 
-    let pants_bin_name = env::var_os("PANTS_BIN_NAME")
+    resolved(Resolution {
+        pants_version: pants_version.clone(),
+        pants_version_specifier: pants_version_specifier.clone(),
+        pants_sha: env_pants_sha.clone(),
+        source: version_source,
+        build_root: build_root.clone(),
+        scie_boot: Some(scie_boot.env_value().to_string_lossy().into_owned()),
+    })?;
+    if prompt_for_version {
+        if offline()? {
+            bail!(
+                "No Pants version is configured and SCIE_PANTS_OFFLINE=1 forbids looking up the \
+                latest Pants release to use. Configure `[GLOBAL] pants_version` or export \
+                PANTS_VERSION."
+            );
+        }
+        if let Some(reason) = no_prompt_reason(scie_pants_config.no_prompt)? {
+            let configure = match build_root {
+                Some(ref build_root) => format!(
+                    "set `[GLOBAL] pants_version` in {pants_toml}",
+                    pants_toml = build_root.join(&config_name).display()
+                ),
+                None => format!("create a `{config_name}` with `[GLOBAL] pants_version` set"),
+            };
+            error!(
+                "No Pants version is configured and scie-pants will not prompt for one since \
+                {reason}. To fix this, {configure} or export PANTS_VERSION."
+            );
+            std::process::exit(EXIT_CODE_NO_PANTS_VERSION);
+        }
+    }
+
+    let scie = scie_path(env::var_os("SCIE"))?;
+
+    let explicit_pants_bin_name = env::var_os("PANTS_BIN_NAME");
+    if let Some(ref pants_bin_name) = explicit_pants_bin_name {
+        if !quiet && !bin_name_resolvable(pants_bin_name) {
+            warn!(
+                "PANTS_BIN_NAME is set to {pants_bin_name:?}, which is not a file and is not found \
+                on the PATH; so the commands Pants suggests in its help and error messages may not \
+                work. If that name is intentional, export SCIE_PANTS_QUIET=1 to silence this \
+                warning."
+            );
+        }
+    }
+    let pants_bin_name = explicit_pants_bin_name
         .or_else(|| env::var_os("SCIE_ARGV0"))
         .unwrap_or_else(|| scie.clone().into());
 
-    let mut env = vec![
+    // N.B.: The SCIE_PANTS_EXTRA_ENV vars come first; so the vars we set below for Pants win.
+    let mut env = extra_env::extra_env()?;
+    env.extend([
         ("SCIE_BOOT".into(), scie_boot.env_value()),
         ("PANTS_BIN_NAME".into(), pants_bin_name),
         (
@@ -214,10 +958,51 @@ fn get_pants_process() -> Result<Process> {
             if pants_debug { "1" } else { "" }.into(),
         ),
         ("SCIE_PANTS_VERSION".into(), SCIE_PANTS_VERSION.into()),
-    ];
+        (
+            "SCIE_PANTS_DOWNLOAD_RETRIES".into(),
+            download_retries.to_string().into(),
+        ),
+    ]);
+    env.extend(proxy_env);
+    let scie_base = default_scie_base();
+    warn_if_scie_base_is_networked(scie_base.as_deref(), quiet)?;
+    warn_if_translated(scie_base.as_deref(), quiet);
+    if let Some(scie_base) = scie_base {
+        env.push(("SCIE_BASE".into(), scie_base.into_os_string()));
+    }
+    if let Some(port) = pants_debug_port {
+        env.push(("PANTS_DEBUG_PORT".into(), port.to_string().into()));
+    }
     if let Some(debugpy_version) = debugpy_version {
         env.push(("PANTS_DEBUGPY_VERSION".into(), debugpy_version.into()));
     }
+    if !interpreter_constraints.is_empty() {
+        env.push((
+            "PANTS_BOOTSTRAP_INTERPRETER_CONSTRAINTS".into(),
+            serde_json::to_string(&interpreter_constraints)
+                .context("Failed to serialize the `[python] interpreter_constraints`.")?
+                .into(),
+        ));
+    }
+    // N.B.: An empty `[python-bootstrap] search_path` keeps Pants from probing the system for
+    // Python interpreters to run user code with; Pants itself always runs on the CPython the scie
+    // fetches.
+    if env_flag_or(
+        "SCIE_PANTS_NO_SYSTEM_PYTHON",
+        scie_pants_config.no_system_python,
+    )? {
+        env.push(("PANTS_PYTHON_BOOTSTRAP_SEARCH_PATH".into(), "[]".into()));
+    }
+    // N.B.: The tools run by the scie bindings read PANTS_TOML too, but only understand local paths.
+    if let Some(url) = env::var_os("PANTS_TOML")
+        .as_deref()
+        .and_then(remote_config::remote_url)
+    {
+        env.push((
+            "PANTS_TOML".into(),
+            remote_config::cache_path(url)?.into_os_string(),
+        ));
+    }
     if let Some(ref build_root) = build_root {
         env.push((
             "PANTS_BUILDROOT_OVERRIDE".into(),
@@ -226,18 +1011,33 @@ fn get_pants_process() -> Result<Process> {
         // This should not be conditional. Ideally we'd always set this env var, which is used
         // by the configure binding, and scie-jump would be smart enough to skip the configure
         // binding when the install binding is a cache hit.
-        if configured_pants_version.is_none() {
+        if configured_pants_version.is_none() && pants_version_specifier.is_none() {
             env.push((
                 "PANTS_TOML".into(),
-                build_root.join("pants.toml").into_os_string(),
+                build_root.join(&config_name).into_os_string(),
+            ));
+        }
+        // N.B.: Pants itself only reads `pants.toml` by default; so we point it at the alternate
+        // config unless the user is already choosing the config files Pants reads.
+        if config_name != config::DEFAULT_CONFIG_NAME
+            && env::var_os("PANTS_TOML").is_none()
+            && env::var_os("PANTS_CONFIG_FILES").is_none()
+        {
+            env.push((
+                "PANTS_CONFIG_FILES".into(),
+                format!("[{path:?}]", path = build_root.join(&config_name)).into(),
             ));
         }
     }
     if let Some(version) = pants_version {
-        if delegate_bootstrap {
+        if delegate_bootstrap.is_some() {
             env.push(("_PANTS_VERSION_OVERRIDE".into(), version.clone().into()));
         }
         env.push(("PANTS_VERSION".into(), version.into()));
+    } else if let Some(sha) = env_pants_sha {
+        env.push(("PANTS_SHA".into(), sha.into()));
+    } else if let Some(specifier) = pants_version_specifier {
+        env.push(("SCIE_PANTS_VERSION_SPECIFIER".into(), specifier.into()));
     } else {
         // Ensure the install binding always re-runs when no Pants version is found so that the
         // the user can be prompted with configuration options.
@@ -247,21 +1047,71 @@ fn get_pants_process() -> Result<Process> {
         ))
     }
 
-    scie_boot.into_process(scie, build_root, env)
+    let prepend_args = if matches!(scie_boot, ScieBoot::Pants | ScieBoot::PantsDebug) {
+        prepend_args()?
+    } else {
+        vec![]
+    };
+    let mut process = timing::time("bootstrap source", || {
+        scie_boot.into_process(scie, build_root, env)
+    })?;
+    if cache_key {
+        process.args.push("bootstrap-cache-key".into());
+    }
+    // N.B.: The user's own args are appended after these when Pants is launched; so, since Pants
+    // lets the last occurrence of a flag win, users can still override any of these flags.
+    process.args.extend(prepend_args);
+    process.prompt_for_version = prompt_for_version;
+    Ok(process)
+}
+
+// N.B.: The scie-jump at the head of the scie-pants scie sets SCIE to the path of the scie when it
+// runs the scie-pants binary; so SCIE is only missing when the bare binary is run directly.
+fn scie_path(scie: Option<OsString>) -> Result<String> {
+    let Some(scie) = scie.filter(|scie| !scie.is_empty()) else {
+        bail!(
+            "The SCIE env var is not set; so this appears to be the bare scie-pants binary and not \
+            the scie-pants scie.\n\
+            scie-pants needs the scie it is packaged in to install and run Pants, and the scie-jump \
+            launcher at the head of that scie sets SCIE to the path of the scie when it runs \
+            scie-pants. Run the packaged scie-pants scie instead; e.g.: one downloaded from \
+            https://github.com/pantsbuild/scie-pants/releases or built with \
+            `cargo run -p package -- scie`."
+        );
+    };
+    scie.into_string()
+        .map_err(|scie| anyhow!("Failed to interpret the SCIE env var {scie:?} as UTF-8."))
 }
 
-fn get_pants_from_sources_process(pants_repo_location: PathBuf) -> Result<Process> {
+fn get_pants_from_sources_process(
+    pants_repo_location: PathBuf,
+    version_source: VersionSource,
+) -> Result<Process> {
     let exe = pants_repo_location.join("pants").into_os_string();
 
-    let args = vec!["--no-verify-config".into()];
+    // N.B.: The user's own args are appended after these when Pants is launched; so they can still
+    // override any of these flags.
+    let mut args = vec!["--no-verify-config".into()];
+    if let Some(extra_args) = env_version("PANTS_SOURCE_EXTRA_ARGS")? {
+        args.extend(
+            shell_split(&extra_args)
+                .context("Failed to parse the PANTS_SOURCE_EXTRA_ARGS env var.")?
+                .into_iter()
+                .map(OsString::from),
+        );
+    }
 
-    let version = std::fs::read_to_string(
-        pants_repo_location
-            .join("src")
-            .join("python")
-            .join("pants")
-            .join("VERSION"),
-    )?;
+    let version = pants_source_version(&pants_repo_location)?;
+
+    let build_root = timing::time("build root discovery", BuildRoot::find_from_env)?;
+    resolved(Resolution {
+        pants_version: Some(version.trim().to_string()),
+        pants_version_specifier: None,
+        pants_sha: None,
+        source: version_source,
+        build_root: build_root.as_ref().map(|br| br.to_path_buf()),
+        scie_boot: None,
+    })?;
 
     // The ENABLE_PANTSD env var is a custom env var defined by the legacy `./pants_from_sources`
     // script. We maintain support here in perpetuity because it's cheap and we don't break folks'
@@ -270,7 +1120,10 @@ fn get_pants_from_sources_process(pants_repo_location: PathBuf) -> Result<Proces
         .or_else(|| env::var_os("PANTS_PANTSD"))
         .unwrap_or_else(|| "false".into());
 
-    let build_root = BuildRoot::find(None)?;
+    let build_root = build_root.context(
+        "Failed to find the build root of the repo to run Pants from sources in. Run from within \
+        a Pants-using repo or export PANTS_BUILDROOT_OVERRIDE.",
+    )?;
     let env = vec![
         ("PANTS_VERSION".into(), version.trim().into()),
         ("PANTS_PANTSD".into(), enable_pantsd),
@@ -282,24 +1135,360 @@ fn get_pants_from_sources_process(pants_repo_location: PathBuf) -> Result<Proces
         ("SCIE_PANTS_VERSION".into(), SCIE_PANTS_VERSION.into()),
     ];
 
-    Ok(Process { exe, args, env })
+    Ok(Process {
+        exe,
+        args,
+        env,
+        ..Default::default()
+    })
 }
 
-fn invoked_as_basename() -> Option<String> {
-    let scie = env::var("SCIE_ARGV0").ok()?;
-    let exe_path = PathBuf::from(scie);
+// N.B.: Pants has always kept its version in src/python/pants/VERSION, but forks may restructure.
+const PANTS_SOURCE_VERSION_FILES: &[&[&str]] = &[
+    &["src", "python", "pants", "VERSION"],
+    &["src", "pants", "VERSION"],
+    &["pants", "VERSION"],
+    &["VERSION"],
+];
+
+/// Finds the version of the Pants sources at `pants_repo_location`.
+///
+/// This is read from the first VERSION file found in the usual spots, falling back to the
+/// `release_<version>` tag `git describe` finds for the sources.
+fn pants_source_version(pants_repo_location: &Path) -> Result<String> {
+    let candidates = PANTS_SOURCE_VERSION_FILES
+        .iter()
+        .map(|components| {
+            components
+                .iter()
+                .fold(pants_repo_location.to_path_buf(), |path, component| {
+                    path.join(component)
+                })
+        })
+        .collect::<Vec<_>>();
+    for candidate in &candidates {
+        if candidate.is_file() {
+            let version = std::fs::read_to_string(candidate).with_context(|| {
+                format!(
+                    "Failed to read the Pants version from {path}",
+                    path = candidate.display()
+                )
+            })?;
+            debug!(
+                "Read the Pants sources version from {path}",
+                path = candidate.display()
+            );
+            return Ok(version.trim().to_string());
+        }
+    }
+    if let Some(version) = std::process::Command::new("git")
+        .args(["describe", "--tags", "--abbrev=0", "--match", "release_*"])
+        .current_dir(pants_repo_location)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|tag| {
+            tag.trim()
+                .strip_prefix("release_")
+                .map(|version| version.to_string())
+        })
+    {
+        debug!("Determined the Pants sources version from the git tag release_{version}");
+        return Ok(version);
+    }
+    bail!(
+        "Failed to determine the version of the Pants sources at {path}. None of these VERSION \
+        files exist:\n{candidates}\nand no `release_*` git tag was found for the sources either.",
+        path = pants_repo_location.display(),
+        candidates = candidates
+            .iter()
+            .map(|candidate| format!("  {path}", path = candidate.display()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+fn prepend_args() -> Result<Vec<OsString>> {
+    let Some(prepend_args) = env_version("PANTS_PREPEND_ARGS")? else {
+        return Ok(vec![]);
+    };
+    Ok(shell_split(&prepend_args)
+        .context("Failed to parse the PANTS_PREPEND_ARGS env var.")?
+        .into_iter()
+        .map(OsString::from)
+        .collect())
+}
+
+/// Splits `value` into words like a POSIX shell would, minus any expansions.
+fn shell_split(value: &str) -> Result<Vec<String>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            char if char.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(char) => word.push(char),
+                        None => bail!("Unterminated single quote in: {value}"),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(char @ ('"' | '\\' | '$' | '`')) => word.push(char),
+                            Some(char) => {
+                                word.push('\\');
+                                word.push(char);
+                            }
+                            None => bail!("Unterminated double quote in: {value}"),
+                        },
+                        Some(char) => word.push(char),
+                        None => bail!("Unterminated double quote in: {value}"),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(char) => word.get_or_insert_with(String::new).push(char),
+                None => bail!("Trailing backslash in: {value}"),
+            },
+            char => word.get_or_insert_with(String::new).push(char),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+// N.B.: The event log is best-effort; failing to record an event is warned about but does not fail
+// the Pants run.
+fn emit_event(event: &str, data: serde_json::Value) {
+    let Some(event_log) = env::var_os("SCIE_PANTS_EVENT_LOG").filter(|path| !path.is_empty())
+    else {
+        return;
+    };
+    let event_log = PathBuf::from(event_log);
+    if let Err(err) = append_event(&event_log, event, data) {
+        warn!(
+            "Failed to record the {event} event to {path}: {err:#}",
+            path = event_log.display()
+        );
+    }
+}
+
+fn append_event(event_log: &Path, event: &str, data: serde_json::Value) -> Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("The system clock is set before the UNIX epoch.")?;
+    let mut record = json!({
+        "event": event,
+        "timestamp": timestamp.as_secs_f64(),
+        "pid": std::process::id(),
+    });
+    if let (Some(record), serde_json::Value::Object(data)) = (record.as_object_mut(), data) {
+        record.extend(data);
+    }
+    let mut line = serde_json::to_vec(&record).context("Failed to serialize the event.")?;
+    line.push(b'\n');
+    // N.B.: We open in append mode and write each event with a single write; so concurrent
+    // invocations sharing an event log interleave whole lines instead of truncating each other.
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(event_log)
+        .with_context(|| format!("Failed to open {path}", path = event_log.display()))?;
+    file.write_all(&line)
+        .and_then(|()| file.flush())
+        .with_context(|| format!("Failed to write to {path}", path = event_log.display()))
+}
+
+fn resolved(resolution: Resolution) -> Result<()> {
+    if let Some(ref build_root) = resolution.build_root {
+        emit_event("build-root", json!({"build_root": build_root}));
+    }
+    let mut version =
+        json!({"pants_version": resolution.pants_version, "source": resolution.source});
+    if let Some(ref specifier) = resolution.pants_version_specifier {
+        version["pants_version_specifier"] = json!(specifier);
+    }
+    if let Some(ref sha) = resolution.pants_sha {
+        version["pants_sha"] = json!(sha);
+    }
+    metrics::record_version(&version);
+    emit_event("version", version);
+    if let Some(ref scie_boot) = resolution.scie_boot {
+        emit_event("boot", json!({"scie_boot": scie_boot}));
+    }
+    if explain::requested() {
+        explain::report(resolution)?;
+    }
+    Ok(())
+}
 
+// N.B.: PANTS_VERSION is ignored here; so even a malformed (e.g.: non-UTF-8) value must not fail
+// the run.
+fn warn_if_pants_version_ignored() {
+    if let Some(pants_version) = env::var_os("PANTS_VERSION").filter(|value| !value.is_empty()) {
+        warn!(
+            "Both PANTS_SOURCE and PANTS_VERSION={pants_version} are set. PANTS_SOURCE takes \
+            precedence; so PANTS_VERSION is being ignored for this run.",
+            pants_version = pants_version.to_string_lossy()
+        );
+    }
+}
+
+// N.B.: Pants has no `--scie-pants-version` option, but we only claim it when it is the sole
+// argument; so it can never be mistaken for part of a Pants command line.
+fn version_flag_requested() -> bool {
+    let mut args = env::args_os().skip(1);
+    matches!(
+        (args.next(), args.next()),
+        (Some(arg), None) if arg == "--scie-pants-version"
+    )
+}
+
+fn basename(exe_path: &Path) -> Option<&str> {
     #[cfg(windows)]
     let basename = exe_path.file_stem().and_then(OsStr::to_str);
 
     #[cfg(unix)]
     let basename = exe_path.file_name().and_then(OsStr::to_str);
 
-    basename.map(str::to_owned)
+    basename
+}
+
+fn invoked_as_basename() -> Option<String> {
+    let scie = env::var("SCIE_ARGV0").ok()?;
+    basename(Path::new(&scie)).map(str::to_owned)
+}
+
+// N.B.: SCIE_ARGV0 is the path the scie was invoked via; so when that's a symlink to a symlink named
+// `pants_from_sources`, say, the intermediate name is lost. With SCIE_PANTS_RESOLVE_ARGV0=1 we walk
+// the symlink chain and honor any link along it named `pants_from_sources` as well.
+fn invoked_as_pants_from_sources() -> Result<bool> {
+    if invoked_as_basename().as_deref() == Some("pants_from_sources") {
+        return Ok(true);
+    }
+    if !env_flag("SCIE_PANTS_RESOLVE_ARGV0")? {
+        return Ok(false);
+    }
+    let Some(argv0) = env::var_os("SCIE_ARGV0") else {
+        return Ok(false);
+    };
+    let chain = argv0_symlink_chain(Path::new(&argv0));
+    debug!(
+        "Resolved SCIE_ARGV0 symlink chain: {chain}",
+        chain = chain
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    );
+    Ok(chain
+        .iter()
+        .any(|path| basename(path) == Some("pants_from_sources")))
+}
+
+/// Returns true if the given bin name is a path to a file or names a file on the PATH.
+fn bin_name_resolvable(bin_name: &OsStr) -> bool {
+    let bin_name = Path::new(bin_name);
+    // N.B.: On Windows, a bin name like `pants` can resolve to `pants.exe`.
+    let candidates = |path: PathBuf| {
+        if cfg!(windows) && path.extension().is_none() {
+            vec![path.with_extension(env::consts::EXE_EXTENSION), path]
+        } else {
+            vec![path]
+        }
+    };
+    if bin_name.components().count() > 1 {
+        return candidates(bin_name.to_path_buf())
+            .iter()
+            .any(|candidate| candidate.is_file());
+    }
+    env::var_os("PATH").is_some_and(|path| {
+        env::split_paths(&path)
+            .flat_map(|entry| candidates(entry.join(bin_name)))
+            .any(|candidate| candidate.is_file())
+    })
+}
+
+// The most links followed before giving up on a chain; this matches the Linux MAXSYMLINKS.
+const MAX_SYMLINKS: usize = 40;
+
+/// Returns the path of `argv0` followed by the path of each symlink target along its chain.
+fn argv0_symlink_chain(argv0: &Path) -> Vec<PathBuf> {
+    // A bare argv0, like `pants`, was found on the PATH.
+    let mut path = if argv0.components().count() == 1 {
+        env::var_os("PATH")
+            .and_then(|path| {
+                env::split_paths(&path)
+                    .map(|entry| entry.join(argv0))
+                    .find(|candidate| candidate.is_file())
+            })
+            .unwrap_or_else(|| argv0.to_path_buf())
+    } else {
+        argv0.to_path_buf()
+    };
+    let mut chain = vec![path.clone()];
+    while chain.len() <= MAX_SYMLINKS {
+        let Ok(target) = std::fs::read_link(&path) else {
+            break;
+        };
+        path = match path.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target,
+        };
+        chain.push(path.clone());
+    }
+    chain
+}
+
+// N.B.: SCIE_PANTS_LOG and SCIE_PANTS_LOG_COLOR are friendlier spellings of the env_logger
+// RUST_LOG and RUST_LOG_STYLE env vars; so the RUST_LOG filter, when set, takes precedence.
+fn init_logging() -> Result<()> {
+    let filter = match env_version("SCIE_PANTS_LOG")? {
+        Some(level) => level
+            .trim()
+            .parse::<log::LevelFilter>()
+            .map_err(|_| {
+                anyhow!(
+                    "SCIE_PANTS_LOG must be one of `off`, `error`, `warn`, `info`, `debug` or \
+                    `trace`, got: `{level}`."
+                )
+            })?
+            .as_str()
+            .to_ascii_lowercase(),
+        // N.B.: By default, dependencies only log errors just as they did under a bare
+        // `env_logger::init()`; only the warnings scie-pants issues itself are added.
+        None => "error,scie_pants=warn".to_owned(),
+    };
+    let mut builder =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(filter));
+    if let Some(color) = env_version("SCIE_PANTS_LOG_COLOR")? {
+        let write_style = match color.trim() {
+            "always" => env_logger::WriteStyle::Always,
+            "never" => env_logger::WriteStyle::Never,
+            "auto" => env_logger::WriteStyle::Auto,
+            _ => bail!(
+                "SCIE_PANTS_LOG_COLOR must be one of `always`, `never` or `auto`, got: `{color}`."
+            ),
+        };
+        builder.write_style(write_style);
+    }
+    builder.init();
+    Ok(())
 }
 
 fn main() -> Result<()> {
-    env_logger::init();
+    let start = Instant::now();
+    init_logging()?;
     let _timer = timer!(Level::Debug; "MAIN");
 
     // N.B.: The bogus version of `report` is used to signal scie-pants should report version
@@ -312,15 +1501,309 @@ fn main() -> Result<()> {
         }
     }
 
-    let pants_process = if let Ok(value) = env::var("PANTS_SOURCE") {
-        get_pants_from_sources_process(PathBuf::from(value))
-    } else if let Some("pants_from_sources") = invoked_as_basename().as_deref() {
-        get_pants_from_sources_process(PathBuf::from("..").join("pants"))
+    if env::var_os("SCIE_BOOT") == Some(ScieBoot::Version.env_value()) || version_flag_requested() {
+        println!("{}", SCIE_PANTS_VERSION);
+        std::process::exit(0);
+    }
+
+    if init::requested() {
+        init::dry_run(find_pants_installation()?)?;
+        std::process::exit(0);
+    }
+
+    // N.B.: The `doctor` boot command runs scie-pants itself with SCIE_BOOT still set; so we check
+    // for it before launching anything.
+    if env::var_os("SCIE_BOOT") == Some(ScieBoot::Doctor.env_value()) {
+        std::process::exit(doctor::run()?);
+    }
+
+    // N.B.: The `update` boot command runs the current scie with SCIE_BOOT=verify-update to check
+    // the signature of the new scie-pants binary it downloaded before replacing itself with it.
+    if env::var_os("SCIE_BOOT") == Some(ScieBoot::VerifyUpdate.env_value()) {
+        std::process::exit(update_signature::run()?);
+    }
+
+    let watchdog = Watchdog::start()?;
+    let mut pants_process = if let Ok(value) = env::var("PANTS_SOURCE") {
+        warn_if_pants_version_ignored();
+        debug!("Launch mode: Pants from sources at {value} (via the PANTS_SOURCE env var)");
+        get_pants_from_sources_process(PathBuf::from(value), VersionSource::PantsSource)
+    } else if invoked_as_pants_from_sources()? {
+        debug!(
+            "Launch mode: Pants from sources at ../pants (via invocation as `pants_from_sources`)"
+        );
+        get_pants_from_sources_process(
+            PathBuf::from("..").join("pants"),
+            VersionSource::PantsFromSources,
+        )
+    } else if let Some(sha) = pants_clone::git_sha_from_env()? {
+        debug!("Launch mode: Pants from sources at {sha} (via PANTS_VERSION=git+{sha})");
+        let version_source = VersionSource::for_env_var("PANTS_VERSION")?;
+        // N.B.: Explaining must not bootstrap anything; so we report before cloning Pants.
+        if explain::requested() {
+            let build_root = BuildRoot::find_from_env()?;
+            resolved(Resolution {
+                pants_version: None,
+                pants_version_specifier: None,
+                pants_sha: Some(sha.clone()),
+                source: version_source,
+                build_root: build_root.as_ref().map(|br| br.to_path_buf()),
+                scie_boot: None,
+            })?;
+        }
+        get_pants_from_sources_process(pants_clone::checkout(&sha)?, version_source)
     } else {
         get_pants_process()
     }?;
+    if let Some(ref watchdog) = watchdog {
+        pants_process.env.push(watchdog.deadline_env());
+    }
+
+    pants_process.env_allowlist = env_allowlist();
+
+    if matches!(env::var_os("SCIE_PANTS_DUMP_ENV"), Some(value) if !value.is_empty()) {
+        pants_process.dump_env()?;
+        std::process::exit(0);
+    }
 
     trace!("Launching: {pants_process:#?}");
-    let exit_code = pants_process.exec()?;
+    emit_event(
+        "exec",
+        json!({
+            "exe": pants_process.exe.to_string_lossy(),
+            "args": pants_process
+                .args
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .chain(env::args_os().skip(1).map(|arg| arg.to_string_lossy().into_owned()))
+                .collect::<Vec<_>>(),
+        }),
+    );
+    if let Some(watchdog) = watchdog {
+        watchdog.cancel();
+    }
+    // N.B.: On unix, Pants is exec'd; so this is our last chance to report.
+    if env_flag("SCIE_PANTS_TIMING")? {
+        timing::report(start.elapsed());
+    }
+    metrics::write(start.elapsed());
+    let exit_code = pants_process.launch()?;
     std::process::exit(exit_code)
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    #[test]
+    fn exit_code_for_signal() {
+        use std::process::Command;
+
+        use super::exit_code;
+
+        let exit_status = Command::new("sh")
+            .args(["-c", "kill -TERM $$"])
+            .status()
+            .unwrap();
+        assert_eq!(128 + 15, exit_code(exit_status));
+
+        let exit_status = Command::new("sh").args(["-c", "exit 42"]).status().unwrap();
+        assert_eq!(42, exit_code(exit_status));
+    }
+
+    #[test]
+    fn bin_name_resolvable() {
+        use std::ffi::OsStr;
+
+        use super::bin_name_resolvable;
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let bin_name = tmpdir.path().join("pants");
+        assert!(!bin_name_resolvable(bin_name.as_os_str()));
+        std::fs::write(&bin_name, "").unwrap();
+        assert!(bin_name_resolvable(bin_name.as_os_str()));
+        assert!(!bin_name_resolvable(OsStr::new(
+            "scie-pants-bogus-bin-name-not-on-the-path"
+        )));
+    }
+
+    #[test]
+    fn parse_pants_debug() {
+        use super::{parse_pants_debug, DEFAULT_PANTS_DEBUG_PORT};
+
+        for disabled in ["", "0", "false", "No", "off"] {
+            assert_eq!(None, parse_pants_debug(disabled).unwrap(), "{disabled}");
+        }
+        for enabled in ["1", "true", "YES", "on"] {
+            assert_eq!(
+                Some(DEFAULT_PANTS_DEBUG_PORT),
+                parse_pants_debug(enabled).unwrap(),
+                "{enabled}"
+            );
+        }
+        assert_eq!(Some(5679), parse_pants_debug(" 5679 ").unwrap());
+        for bad in ["debug", "-1", "65536", "5678.0"] {
+            assert!(parse_pants_debug(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pants_source_version() {
+        use super::pants_source_version;
+
+        let pants_repo = tempfile::tempdir().unwrap();
+        let error = pants_source_version(pants_repo.path()).unwrap_err();
+        let canonical = pants_repo
+            .path()
+            .join("src")
+            .join("python")
+            .join("pants")
+            .join("VERSION");
+        assert!(
+            format!("{error}").contains(&format!("  {path}", path = canonical.display())),
+            "{error}"
+        );
+
+        let alternate = pants_repo.path().join("src").join("pants");
+        std::fs::create_dir_all(&alternate).unwrap();
+        std::fs::write(alternate.join("VERSION"), "2.21.0.dev6+Custom-Local\n").unwrap();
+        assert_eq!(
+            "2.21.0.dev6+Custom-Local",
+            pants_source_version(pants_repo.path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn scie_path() {
+        use super::scie_path;
+
+        for scie in [None, Some("".into())] {
+            let error = format!("{:#}", scie_path(scie).unwrap_err());
+            assert!(
+                error.contains("The SCIE env var is not set")
+                    && error.contains("Run the packaged scie-pants scie instead"),
+                "{error}"
+            );
+        }
+        assert_eq!(
+            "/usr/bin/scie-pants",
+            scie_path(Some("/usr/bin/scie-pants".into())).unwrap()
+        );
+    }
+
+    #[test]
+    fn shell_split() {
+        use super::shell_split;
+
+        assert!(shell_split("").unwrap().is_empty());
+        assert!(shell_split("  ").unwrap().is_empty());
+        assert_eq!(
+            vec!["--no-pantsd", "--tag=a b", "it's", "", "x\\y"],
+            shell_split(r#" --no-pantsd  --tag='a b' "it's" '' x\\y"#).unwrap()
+        );
+        assert!(shell_split("'unterminated").is_err());
+        assert!(shell_split("\"unterminated").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn argv0_symlink_chain() {
+        use std::os::unix::fs::symlink;
+
+        use super::argv0_symlink_chain;
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let scie = tmpdir.path().join("scie-pants");
+        std::fs::write(&scie, "").unwrap();
+        assert_eq!(vec![scie.clone()], argv0_symlink_chain(&scie));
+
+        let bin_dir = tmpdir.path().join("bin");
+        std::fs::create_dir(&bin_dir).unwrap();
+        let pants_from_sources = tmpdir.path().join("pants_from_sources");
+        symlink("scie-pants", &pants_from_sources).unwrap();
+        let pants = bin_dir.join("pants");
+        symlink(&pants_from_sources, &pants).unwrap();
+        assert_eq!(
+            vec![
+                pants.clone(),
+                pants_from_sources.clone(),
+                tmpdir.path().join("scie-pants")
+            ],
+            argv0_symlink_chain(&pants)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn source_without_tput() {
+        use std::ffi::OsString;
+        use std::os::unix::fs::symlink;
+
+        use crate::pants_bootstrap;
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        // N.B.: The PATH holds just bash; so there is no `tput` and, since the env is not
+        // inherited, no TERM either.
+        let bin_dir = tmpdir.path().join("bin");
+        std::fs::create_dir(&bin_dir).unwrap();
+        let bash = std::env::split_paths(&std::env::var_os("PATH").unwrap())
+            .map(|dir| dir.join("bash"))
+            .find(|bash| bash.is_file())
+            .unwrap();
+        symlink(bash, bin_dir.join("bash")).unwrap();
+        let script = tmpdir.path().join(".pants.bootstrap");
+        std::fs::write(
+            &script,
+            "WIDTH=\"$(tput cols)\"\nexport WIDTH\nexport BOLD=\"$(tput bold)\"\n",
+        )
+        .unwrap();
+
+        let source = |env: Vec<(&str, &str)>| {
+            let env = env
+                .into_iter()
+                .map(|(name, value)| (OsString::from(name), OsString::from(value)))
+                .chain([(OsString::from("PATH"), bin_dir.clone().into_os_string())])
+                .collect::<Vec<_>>();
+            pants_bootstrap::source(&[script.clone()], &env, false).unwrap()
+        };
+        assert_eq!(
+            vec![("BOLD".into(), "".into()), ("WIDTH".into(), "80".into())],
+            source(vec![])
+        );
+        assert_eq!(
+            vec![("BOLD".into(), "".into()), ("WIDTH".into(), "132".into())],
+            source(vec![("COLUMNS", "132")])
+        );
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn is_translated() {
+        // N.B.: Whether the tests run under Rosetta depends on the machine running them; so we
+        // just check that the detection works at all.
+        let _ = super::is_translated();
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn quote_for_git_bash() {
+        use std::ffi::OsString;
+        use std::os::windows::ffi::OsStringExt;
+
+        use super::ScieBoot;
+
+        assert_eq!(
+            "'C:/Program Files/scie-pants/scie-pants.exe'",
+            ScieBoot::quote(r"C:\Program Files\scie-pants\scie-pants.exe").unwrap()
+        );
+        assert_eq!(
+            r"'C:/Users/o'\''brien/my repo/.pants.bootstrap'",
+            ScieBoot::quote(r"C:\Users\o'brien\my repo\.pants.bootstrap").unwrap()
+        );
+        assert_eq!(
+            "'D:/répo/$HOME/`pwd`/.pants.bootstrap'",
+            ScieBoot::quote(r"D:\répo\$HOME\`pwd`\.pants.bootstrap").unwrap()
+        );
+        assert!(ScieBoot::quote(OsString::from_wide(&[0x0061, 0xD800])).is_err());
+    }
+}
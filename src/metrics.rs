@@ -0,0 +1,131 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use log::warn;
+use serde_json::{json, Value};
+
+use crate::{timing, SCIE_PANTS_VERSION};
+
+const METRICS_FILE_ENV_VAR: &str = "SCIE_PANTS_METRICS_FILE";
+
+// N.B.: Like the timing phases, these are always recorded since that's cheap, but they're only
+// written out when SCIE_PANTS_METRICS_FILE is set.
+static VERSION: Mutex<Option<Value>> = Mutex::new(None);
+static PANTS_BOOTSTRAP_CACHE: Mutex<Option<&'static str>> = Mutex::new(None);
+
+/// Records the Pants version resolved and where it came from.
+pub(crate) fn record_version(version: &Value) {
+    if let Ok(mut recorded) = VERSION.lock() {
+        *recorded = Some(version.clone());
+    }
+}
+
+/// Records how the env exported by `.pants.bootstrap` scripts was obtained; one of `hit`, `miss`
+/// or `uncacheable`.
+pub(crate) fn record_pants_bootstrap_cache(status: &'static str) {
+    if let Ok(mut recorded) = PANTS_BOOTSTRAP_CACHE.lock() {
+        *recorded = Some(status);
+    }
+}
+
+/// Writes the metrics recorded to the file named by `SCIE_PANTS_METRICS_FILE`, if set.
+///
+/// Failing to write the metrics is warned about but is not fatal.
+pub(crate) fn write(total: Duration) {
+    let Some(path) = env::var_os(METRICS_FILE_ENV_VAR).filter(|value| !value.is_empty()) else {
+        return;
+    };
+    let path = PathBuf::from(path);
+    if let Err(err) = write_to(&path, &metrics(total)) {
+        warn!(
+            "Failed to write the {METRICS_FILE_ENV_VAR} {path}: {err:#}",
+            path = path.display()
+        );
+    }
+}
+
+fn metrics(total: Duration) -> Value {
+    let mut metrics = json!({
+        "scie_pants_version": SCIE_PANTS_VERSION,
+        "pants_version": null,
+        "source": null,
+    });
+    if let (Some(metrics), Ok(Some(Value::Object(version)))) = (
+        metrics.as_object_mut(),
+        VERSION.lock().map(|version| version.clone()),
+    ) {
+        metrics.extend(version);
+    }
+    metrics["phases"] = timing::phases()
+        .into_iter()
+        .map(|(phase, elapsed)| json!({"phase": phase, "millis": millis(elapsed)}))
+        .collect();
+    metrics["total_millis"] = json!(millis(total));
+    metrics["pants_bootstrap_cache"] =
+        json!(PANTS_BOOTSTRAP_CACHE.lock().ok().and_then(|status| *status));
+    metrics
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// N.B.: We write to a temporary file alongside the metrics file and rename it into place; so
+// collectors never read a partially written file.
+fn write_to(path: &Path, metrics: &Value) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut tmp = tempfile::NamedTempFile::new_in(dir).with_context(|| {
+        format!(
+            "Failed to create a temporary file in {dir}",
+            dir = dir.display()
+        )
+    })?;
+    serde_json::to_writer_pretty(tmp.as_file_mut(), metrics)
+        .context("Failed to serialize the metrics.")?;
+    tmp.persist(path)
+        .with_context(|| format!("Failed to write {path}", path = path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use super::{metrics, record_pants_bootstrap_cache, record_version, write_to};
+
+    #[test]
+    fn write_metrics() {
+        record_version(&json!({"pants_version": "2.18.0", "source": "config"}));
+        record_pants_bootstrap_cache("hit");
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let path = tmpdir.path().join("metrics.json");
+        write_to(&path, &metrics(Duration::from_millis(2))).unwrap();
+
+        let written: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json!("2.18.0"), written["pants_version"]);
+        assert_eq!(json!("config"), written["source"]);
+        assert_eq!(json!("hit"), written["pants_bootstrap_cache"]);
+        assert_eq!(json!(2.0), written["total_millis"]);
+        assert!(written["phases"].is_array());
+        assert_eq!(
+            vec![path.file_name().unwrap().to_owned()],
+            std::fs::read_dir(tmpdir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect::<Vec<_>>()
+        );
+    }
+}
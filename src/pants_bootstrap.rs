@@ -0,0 +1,270 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{debug, warn};
+use logging_timer::time;
+use serde::{Deserialize, Serialize};
+
+use crate::{bash, display_paths, metrics, ScieBoot};
+
+/// A `.pants.bootstrap` containing this line promises that the vars it exports only depend on
+/// its own contents; so they can be computed once and cached until the file changes. When there
+/// are `.pants.bootstrap.d/` fragments, they must all contain the line too.
+const STATIC_ENV_MARKER: &str = "# scie-pants: static-env";
+
+// These are maintained by bash itself.
+const BASH_VARS: [&str; 4] = ["_", "OLDPWD", "PWD", "SHLVL"];
+
+/// Sources the `.pants.bootstrap` scripts just as we would when launching Pants and returns the
+/// vars that they add or change.
+///
+/// When `inherit_env` is false, the script is sourced with just the given `env` plus the `PATH`
+/// and `HOME` of the current process; so the result does not depend on the rest of the current
+/// environment.
+pub(crate) fn source(
+    pants_bootstrap: &[PathBuf],
+    env: &[(OsString, OsString)],
+    inherit_env: bool,
+) -> Result<Vec<(OsString, OsString)>> {
+    let baseline = if inherit_env {
+        env::vars_os().collect::<Vec<_>>()
+    } else {
+        ["PATH", "HOME"]
+            .into_iter()
+            .filter_map(|name| env::var_os(name).map(|value| (name.into(), value)))
+            .collect()
+    };
+    let env_before = baseline
+        .iter()
+        .chain(env)
+        .cloned()
+        .collect::<BTreeMap<_, _>>();
+
+    let (bash, bash_args) = bash();
+    let mut command = Command::new(&bash);
+    if !inherit_env {
+        command.env_clear();
+    }
+    let output = command
+        .args(bash_args)
+        .args([
+            "-c".into(),
+            format!(
+                "{source}; exec /usr/bin/env -0",
+                source = ScieBoot::source_commands(pants_bootstrap)?
+            ),
+        ])
+        .envs(env_before.iter())
+        .stderr(Stdio::inherit())
+        .output()
+        .with_context(|| {
+            format!(
+                "Failed to source {paths} using {bash}",
+                paths = display_paths(pants_bootstrap),
+                bash = Path::new(&bash).display()
+            )
+        })?;
+    if !output.status.success() {
+        bail!(
+            "Failed to source {paths}: {status}",
+            paths = display_paths(pants_bootstrap),
+            status = output.status
+        );
+    }
+
+    let mut env_after = BTreeMap::new();
+    for entry in output.stdout.split(|byte| *byte == b'\0') {
+        if let Some(index) = entry.iter().position(|byte| *byte == b'=') {
+            env_after.insert(
+                OsString::from_vec(entry[..index].to_vec()),
+                OsString::from_vec(entry[index + 1..].to_vec()),
+            );
+        }
+    }
+    Ok(env_after
+        .into_iter()
+        .filter(|(name, value)| {
+            !BASH_VARS.iter().any(|bash_var| name == bash_var)
+                && env_before.get(name) != Some(value)
+        })
+        .collect())
+}
+
+/// Returns the vars exported by `.pants.bootstrap` scripts all marked with [`STATIC_ENV_MARKER`],
+/// sourcing them only if there is no cached result for their current modification times and sizes.
+///
+/// Returns `None` when a script is not marked or the SCIE base directory is not known; in which
+/// case the scripts must be sourced on every run.
+#[time("debug", "pants_bootstrap::{}")]
+pub(crate) fn static_env(
+    pants_bootstrap: &[PathBuf],
+    env: &[(OsString, OsString)],
+) -> Result<Option<Vec<(OsString, OsString)>>> {
+    for script in pants_bootstrap {
+        let contents = std::fs::read_to_string(script)
+            .with_context(|| format!("Failed to read {path}", path = script.display()))?;
+        if !contents
+            .lines()
+            .any(|line| line.trim() == STATIC_ENV_MARKER)
+        {
+            metrics::record_pants_bootstrap_cache("uncacheable");
+            return Ok(None);
+        }
+    }
+    let Some(scie_base) = env::var_os("SCIE_PANTS_BASE").filter(|value| !value.is_empty()) else {
+        metrics::record_pants_bootstrap_cache("uncacheable");
+        return Ok(None);
+    };
+
+    let key = pants_bootstrap
+        .iter()
+        .map(|script| CacheKey::for_file(script))
+        .collect::<Result<Vec<_>>>()?;
+    let cache_file = PathBuf::from(scie_base)
+        .join("scie-pants")
+        .join("pants-bootstrap")
+        .join(format!("{hash:016x}.json", hash = path_hash(&key)));
+    if let Some(cached_env) = load(&cache_file, &key) {
+        debug!(
+            "Using the env cached in {cache} for {paths}",
+            cache = cache_file.display(),
+            paths = display_paths(pants_bootstrap)
+        );
+        metrics::record_pants_bootstrap_cache("hit");
+        return Ok(Some(cached_env));
+    }
+    metrics::record_pants_bootstrap_cache("miss");
+
+    let static_env = source(pants_bootstrap, env, false)?;
+    if let Err(err) = store(&cache_file, key, &static_env) {
+        warn!(
+            "Failed to cache the env exported by {paths}: {err:#}",
+            paths = display_paths(pants_bootstrap)
+        );
+    }
+    Ok(Some(static_env))
+}
+
+#[derive(Deserialize, Eq, PartialEq, Serialize)]
+struct CacheKey {
+    path: PathBuf,
+    modified_secs: u64,
+    modified_nanos: u32,
+    size: u64,
+}
+
+impl CacheKey {
+    fn for_file(path: &Path) -> Result<Self> {
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {path}", path = path.display()))?;
+        let metadata = path
+            .metadata()
+            .with_context(|| format!("Failed to stat {path}", path = path.display()))?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| {
+                anyhow!(
+                    "{path} has a modification time before the UNIX epoch: {err}",
+                    path = path.display()
+                )
+            })?;
+        Ok(Self {
+            path,
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+            size: metadata.len(),
+        })
+    }
+}
+
+fn path_hash(key: &[CacheKey]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for entry in key {
+        entry.path.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+// N.B.: Env var names and values are stored hex-encoded; so values that aren't UTF-8 round-trip.
+#[derive(Deserialize, Serialize)]
+struct CacheEntry {
+    key: Vec<CacheKey>,
+    env: Vec<(String, String)>,
+}
+
+fn load(cache_file: &Path, key: &[CacheKey]) -> Option<Vec<(OsString, OsString)>> {
+    let contents = std::fs::read(cache_file).ok()?;
+    let entry: CacheEntry = serde_json::from_slice(&contents).ok()?;
+    if entry.key != key {
+        return None;
+    }
+    entry
+        .env
+        .into_iter()
+        .map(|(name, value)| Some((decode(&name)?, decode(&value)?)))
+        .collect()
+}
+
+fn store(cache_file: &Path, key: Vec<CacheKey>, env: &[(OsString, OsString)]) -> Result<()> {
+    let entry = CacheEntry {
+        key,
+        env: env
+            .iter()
+            .map(|(name, value)| (encode(name), encode(value)))
+            .collect(),
+    };
+    let cache_dir = cache_file
+        .parent()
+        .with_context(|| format!("{path} has no parent", path = cache_file.display()))?;
+    std::fs::create_dir_all(cache_dir)
+        .with_context(|| format!("Failed to create {path}", path = cache_dir.display()))?;
+    // N.B.: We write to a temporary file and rename it into place; so concurrent runs never see a
+    // partially written cache file.
+    let mut tmp = tempfile::NamedTempFile::new_in(cache_dir).with_context(|| {
+        format!(
+            "Failed to create a file in {path}",
+            path = cache_dir.display()
+        )
+    })?;
+    tmp.write_all(&serde_json::to_vec(&entry)?)?;
+    tmp.persist(cache_file)
+        .with_context(|| format!("Failed to write {path}", path = cache_file.display()))?;
+    Ok(())
+}
+
+fn encode(value: &OsString) -> String {
+    use std::fmt::Write;
+
+    value
+        .as_bytes()
+        .iter()
+        .fold(String::new(), |mut encoded, byte| {
+            let _ = write!(encoded, "{byte:02x}");
+            encoded
+        })
+}
+
+fn decode(value: &str) -> Option<OsString> {
+    if value.len() % 2 != 0 {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect::<Option<Vec<_>>>()
+        .map(OsString::from_vec)
+}
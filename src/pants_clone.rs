@@ -0,0 +1,93 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::info;
+use logging_timer::time;
+use scie_pants::Env;
+
+const PANTS_REPO_URL: &str = "https://github.com/pantsbuild/pants";
+/// Returns the commit sha named by a `PANTS_VERSION=git+<sha>`, if any.
+pub(crate) fn git_sha_from_env() -> Result<Option<String>> {
+    Env::current()?.pants_git_sha()
+}
+
+/// Returns a checkout of the Pants repo at the given commit sha, cloning it on first use.
+#[time("debug", "pants_clone::{}")]
+pub(crate) fn checkout(sha: &str) -> Result<PathBuf> {
+    let clones_dir = clones_dir()?;
+    let clone_dir = clones_dir.join(sha);
+    if clone_dir.join("pants").is_file() {
+        return Ok(clone_dir);
+    }
+
+    if crate::offline()? {
+        bail!(
+            "Pants at {sha} is not cloned to {path} yet and SCIE_PANTS_OFFLINE=1 forbids fetching \
+            it from {PANTS_REPO_URL}.",
+            path = clone_dir.display()
+        );
+    }
+    std::fs::create_dir_all(&clones_dir)
+        .with_context(|| format!("Failed to create {path}", path = clones_dir.display()))?;
+    // N.B.: We clone into a temporary directory and rename it into place; so an interrupted clone
+    // is never mistaken for a complete one.
+    let tmp_dir = tempfile::tempdir_in(&clones_dir).with_context(|| {
+        format!(
+            "Failed to create a directory in {path}",
+            path = clones_dir.display()
+        )
+    })?;
+    info!("Fetching Pants at {sha} from {PANTS_REPO_URL}...");
+    git(tmp_dir.path(), &["init", "--quiet"])?;
+    git(
+        tmp_dir.path(),
+        &["fetch", "--depth", "1", PANTS_REPO_URL, sha],
+    )?;
+    git(tmp_dir.path(), &["reset", "--quiet", "--hard", sha])?;
+
+    if let Err(err) = std::fs::rename(tmp_dir.path(), &clone_dir) {
+        // Another run may have won the race to clone the same sha; in which case we use its clone.
+        if !clone_dir.join("pants").is_file() {
+            return Err(anyhow!(err)).with_context(|| {
+                format!(
+                    "Failed to move the Pants clone into place at {path}",
+                    path = clone_dir.display()
+                )
+            });
+        }
+    }
+    Ok(clone_dir)
+}
+
+// N.B.: Clones live alongside the other scie-pants caches under the SCIE base directory when
+// run via the scie and in the user cache directory otherwise.
+fn clones_dir() -> Result<PathBuf> {
+    let cache_dir = match env::var_os("SCIE_PANTS_BASE").filter(|value| !value.is_empty()) {
+        Some(scie_base) => PathBuf::from(scie_base),
+        None => dirs::cache_dir()
+            .context("Failed to look up the user cache dir for caching Pants clones.")?,
+    };
+    Ok(cache_dir.join("scie-pants").join("pants-clones"))
+}
+
+fn git(cwd: &Path, args: &[&str]) -> Result<()> {
+    // N.B.: Git's progress goes to STDERR; we keep STDOUT clean for Pants.
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .stdout(Stdio::null())
+        .status()
+        .with_context(|| format!("Failed to run `git {args}`", args = args.join(" ")))?;
+    if !status.success() {
+        bail!(
+            "Failed to clone the Pants repo: `git {args}` exited with {status}",
+            args = args.join(" ")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::ffi::OsString;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use log::debug;
+
+// N.B.: Tools disagree on which case of these env vars they read; e.g.: curl, which the ptex
+// binary uses for downloads, only reads `http_proxy` in lower case. So we pick a value for each
+// pair, preferring the lower case one like curl and Python's urllib do, and export it in both cases.
+const PROXY_ENV_VARS: [(&str, &str); 3] = [
+    ("https_proxy", "HTTPS_PROXY"),
+    ("http_proxy", "HTTP_PROXY"),
+    ("no_proxy", "NO_PROXY"),
+];
+
+const CA_BUNDLE_ENV_VAR: &str = "SCIE_PANTS_CA_BUNDLE";
+
+/// The env vars that configure the proxies and certificate authorities used by the downloads the
+/// bootstrap performs.
+pub(crate) fn proxy_env() -> Result<Vec<(OsString, OsString)>> {
+    let mut proxy_env = vec![];
+    for (lower, upper) in PROXY_ENV_VARS {
+        let Some(value) = env::var_os(lower)
+            .filter(|value| !value.is_empty())
+            .or_else(|| env::var_os(upper).filter(|value| !value.is_empty()))
+        else {
+            continue;
+        };
+        debug!("Using {upper}={value:?} for downloads.");
+        proxy_env.push((lower.into(), value.clone()));
+        proxy_env.push((upper.into(), value));
+    }
+
+    if let Some(ca_bundle) = env::var_os(CA_BUNDLE_ENV_VAR).filter(|value| !value.is_empty()) {
+        let ca_bundle = Path::new(&ca_bundle);
+        if !ca_bundle.is_file() {
+            bail!(
+                "{CA_BUNDLE_ENV_VAR} must be the path of a PEM file of CA certificates, but \
+                {path} is not a file.",
+                path = ca_bundle.display()
+            );
+        }
+        let ca_bundle = ca_bundle
+            .canonicalize()
+            .with_context(|| {
+                format!(
+                    "Failed to resolve the {CA_BUNDLE_ENV_VAR} path {path}",
+                    path = ca_bundle.display()
+                )
+            })?
+            .into_os_string();
+        debug!("Using {CA_BUNDLE_ENV_VAR}={ca_bundle:?} for downloads.");
+        // N.B.: OpenSSL, and thus both the ptex binary and Python's ssl module, read SSL_CERT_FILE;
+        // Pip uses its own CA bundle unless told otherwise via PIP_CERT.
+        proxy_env.push(("SSL_CERT_FILE".into(), ca_bundle.clone()));
+        proxy_env.push(("PIP_CERT".into(), ca_bundle));
+    }
+    Ok(proxy_env)
+}
@@ -0,0 +1,204 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{debug, warn};
+use logging_timer::time;
+use sha2::{Digest, Sha256};
+
+const MAX_AGE_ENV_VAR: &str = "SCIE_PANTS_REMOTE_CONFIG_MAX_AGE";
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Returns the remote URL a `PANTS_TOML` value names, if it names one.
+///
+/// Only `http://` and `https://` URLs are remote; `file://` URLs and plain paths are local.
+pub fn remote_url(value: &OsStr) -> Option<&str> {
+    value
+        .to_str()
+        .filter(|value| value.starts_with("https://") || value.starts_with("http://"))
+}
+
+/// Returns the local path of the Pants config a `PANTS_TOML` value names.
+///
+/// Remote configs are fetched and cached under the SCIE base keyed by their URL. A cached config is
+/// re-used until it is older than `SCIE_PANTS_REMOTE_CONFIG_MAX_AGE` seconds, an hour by default.
+pub fn local_path(value: &OsStr) -> Result<PathBuf> {
+    if let Some(url) = remote_url(value) {
+        return fetch(url);
+    }
+    match value
+        .to_str()
+        .and_then(|value| value.strip_prefix("file://"))
+    {
+        Some(path) => Ok(file_url_path(path)),
+        None => Ok(PathBuf::from(value)),
+    }
+}
+
+// N.B.: A Windows file URL looks like `file:///C:/path`; so we drop the leading `/` before the
+// drive letter.
+fn file_url_path(path: &str) -> PathBuf {
+    if cfg!(windows) && path.starts_with('/') && path.get(2..3) == Some(":") {
+        PathBuf::from(&path[1..])
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+/// The path a remote config fetched from `url` is cached at.
+pub fn cache_path(url: &str) -> Result<PathBuf> {
+    let scie_base = std::env::var_os("SCIE_PANTS_BASE")
+        .or_else(|| std::env::var_os("SCIE_BASE"))
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::cache_dir().map(|cache_dir| cache_dir.join("nce")))
+        .context("Failed to determine the SCIE base to cache the remote PANTS_TOML under.")?;
+    let key = format!("{digest:x}", digest = Sha256::digest(url.as_bytes()));
+    Ok(scie_base
+        .join("scie-pants")
+        .join("remote-configs")
+        .join(key)
+        .join("pants.toml"))
+}
+
+fn max_age() -> Result<Duration> {
+    match std::env::var(MAX_AGE_ENV_VAR) {
+        Ok(value) if !value.trim().is_empty() => {
+            value.trim().parse().map(Duration::from_secs).map_err(|_| {
+                anyhow!(
+                    "{MAX_AGE_ENV_VAR} must be a non-negative integer number of seconds, got: \
+                    `{value}`."
+                )
+            })
+        }
+        _ => Ok(DEFAULT_MAX_AGE),
+    }
+}
+
+fn age(path: &Path) -> Option<Duration> {
+    let modified = path
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()?;
+    Some(
+        SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default(),
+    )
+}
+
+#[time("debug", "remote_config::{}")]
+fn fetch(url: &str) -> Result<PathBuf> {
+    let path = cache_path(url)?;
+    let max_age = max_age()?;
+    let age = age(&path);
+    let offline = std::env::var("SCIE_PANTS_OFFLINE").is_ok_and(|value| value.trim() == "1");
+    match age {
+        Some(age) if offline || age < max_age => {
+            debug!(
+                "Using the PANTS_TOML fetched from {url} {age}s ago.",
+                age = age.as_secs()
+            );
+            return Ok(path);
+        }
+        None if offline => bail!(
+            "PANTS_TOML is set to {url} but SCIE_PANTS_OFFLINE=1 is set and that config has not \
+            been fetched yet."
+        ),
+        _ => {}
+    }
+    match download(url, &path) {
+        Ok(()) => Ok(path),
+        Err(err) if age.is_some() => {
+            warn!(
+                "Using the PANTS_TOML previously fetched from {url} since re-fetching it failed: \
+                {err:#}"
+            );
+            Ok(path)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+// N.B.: We have no HTTP client of our own; so we use the ptex binary embedded in the scie via its
+// hidden `fetch` command.
+fn download(url: &str, dest: &Path) -> Result<()> {
+    let scie = std::env::var_os("SCIE").with_context(|| {
+        format!(
+            "PANTS_TOML is set to {url} but remote Pants configs can only be fetched when running \
+            via the scie-pants scie."
+        )
+    })?;
+    let dest_dir = dest
+        .parent()
+        .expect("The remote config cache path always has a parent directory.");
+    std::fs::create_dir_all(dest_dir).with_context(|| {
+        format!(
+            "Failed to create the remote PANTS_TOML cache directory {path}",
+            path = dest_dir.display()
+        )
+    })?;
+    let mut fetched = tempfile::NamedTempFile::new_in(dest_dir).with_context(|| {
+        format!(
+            "Failed to create a temporary file in {path} to fetch PANTS_TOML into",
+            path = dest_dir.display()
+        )
+    })?;
+    let output = Command::new(scie)
+        .env("SCIE_BOOT", "fetch")
+        .arg(url)
+        .stdout(
+            fetched
+                .as_file_mut()
+                .try_clone()
+                .context("Failed to open the temporary PANTS_TOML file for writing.")?,
+        )
+        .stderr(Stdio::piped())
+        .output()
+        .with_context(|| format!("Failed to spawn a fetch of the PANTS_TOML at {url}"))?;
+    if !output.status.success() {
+        bail!(
+            "Failed to fetch the PANTS_TOML at {url}:\n{stderr}",
+            stderr = String::from_utf8_lossy(&output.stderr).trim_end()
+        );
+    }
+    fetched.persist(dest).with_context(|| {
+        format!(
+            "Failed to cache the PANTS_TOML fetched from {url} at {path}",
+            path = dest.display()
+        )
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+    use std::path::PathBuf;
+
+    use super::{local_path, remote_url};
+
+    #[test]
+    fn urls() {
+        assert_eq!(
+            Some("https://example.com/pants.toml"),
+            remote_url(OsStr::new("https://example.com/pants.toml"))
+        );
+        assert_eq!(None, remote_url(OsStr::new("file:///tmp/pants.toml")));
+        assert_eq!(None, remote_url(OsStr::new("pants.ci.toml")));
+
+        assert_eq!(
+            PathBuf::from("/tmp/pants.toml"),
+            local_path(OsStr::new("file:///tmp/pants.toml")).unwrap()
+        );
+        assert_eq!(
+            PathBuf::from("pants.ci.toml"),
+            local_path(OsStr::new("pants.ci.toml")).unwrap()
+        );
+    }
+}
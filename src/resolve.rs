@@ -0,0 +1,486 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::debug;
+use serde::Serialize;
+
+use crate::build_root::BuildRoot;
+use crate::config::PantsConfig;
+use crate::user_config::UserConfig;
+use crate::version;
+
+const GIT_VERSION_PREFIX: &str = "git+";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionSource {
+    /// The `PANTS_VERSION` or `PANTS_SHA` env var.
+    Env,
+    /// A `PANTS_VERSION` or `PANTS_SHA` exported by the `.env` file loaded by the scie.
+    #[serde(rename = "dotenv")]
+    DotEnv,
+    /// The `[GLOBAL] pants_version` in the Pants config.
+    Config,
+    /// The `default_version` in the scie-pants user config.
+    UserConfig,
+    /// The `./pants` script the Pants config delegates to.
+    Delegate,
+    /// The `VERSION` file of a Pants clone found via the `PANTS_SOURCE` env var.
+    PantsSource,
+    /// The `VERSION` file of the `../pants` clone used when invoked as `pants_from_sources`.
+    PantsFromSources,
+    /// No version is configured; the user will be prompted.
+    None,
+}
+
+impl VersionSource {
+    pub fn for_env_var(name: &str) -> Result<Self> {
+        Self::for_env_var_in(&std::env::current_dir()?, name)
+    }
+
+    fn for_env_var_in(cwd: &Path, name: &str) -> Result<Self> {
+        Ok(if dot_env_defines(cwd, name)? {
+            VersionSource::DotEnv
+        } else {
+            VersionSource::Env
+        })
+    }
+}
+
+// N.B.: The scie-jump loads the first `.env` file found in the CWD or its ancestors and it has
+// already been applied to our environment by the time we run; so we just check whether that file
+// is where the env var came from.
+fn dot_env_defines(cwd: &Path, name: &str) -> Result<bool> {
+    let Some(dot_env) = cwd
+        .ancestors()
+        .map(|dir| dir.join(".env"))
+        .find(|path| path.is_file())
+    else {
+        return Ok(false);
+    };
+    let contents = std::fs::read_to_string(&dot_env)
+        .with_context(|| format!("Failed to read {path}", path = dot_env.display()))?;
+    Ok(contents.lines().any(|line| {
+        let line = line.trim_start();
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+        matches!(line.split_once('='), Some((key, _)) if key.trim_end() == name)
+    }))
+}
+
+/// The environment Pants versions are resolved in: the env vars, as loaded by the scie from any
+/// `.env` file, and the directory Pants is run from.
+pub struct Env {
+    vars: HashMap<OsString, OsString>,
+    cwd: PathBuf,
+}
+
+impl Env {
+    /// The environment of the current process.
+    pub fn current() -> Result<Env> {
+        Ok(Env {
+            vars: std::env::vars_os().collect(),
+            cwd: std::env::current_dir().context("Failed to determine the current directory.")?,
+        })
+    }
+
+    /// An environment with just the given env vars, run from the given directory.
+    pub fn new<K, V>(cwd: impl Into<PathBuf>, vars: impl IntoIterator<Item = (K, V)>) -> Env
+    where
+        K: Into<OsString>,
+        V: Into<OsString>,
+    {
+        Env {
+            vars: vars
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+            cwd: cwd.into(),
+        }
+    }
+
+    pub(crate) fn var_os(&self, name: &str) -> Option<&OsStr> {
+        self.vars.get(OsStr::new(name)).map(OsString::as_os_str)
+    }
+
+    /// The value of the given env var; setting an env var to the empty string is the same as not
+    /// setting it.
+    pub fn var(&self, name: &str) -> Result<Option<String>> {
+        match self.var_os(name) {
+            Some(value) if !value.is_empty() => value
+                .to_str()
+                .map(|value| Some(value.to_owned()))
+                .ok_or_else(|| anyhow!("Failed to interpret {name} {value:?} as UTF-8 string.")),
+            _ => Ok(None),
+        }
+    }
+
+    /// The full, lower case, Pants commit sha selected by the `PANTS_SHA` env var, if any.
+    ///
+    /// Just like the legacy `./pants` script, `PANTS_SHA` selects the Pants wheels Pants CI builds
+    /// for each commit of the Pants repo.
+    pub fn pants_sha(&self) -> Result<Option<String>> {
+        let Some(sha) = self.var("PANTS_SHA")? else {
+            return Ok(None);
+        };
+        if let Some(pants_version) = self.var("PANTS_VERSION")? {
+            bail!(
+                "PANTS_VERSION={pants_version} and PANTS_SHA={sha} are mutually exclusive; unset \
+                one of them. PANTS_VERSION selects a Pants release and PANTS_SHA selects Pants as \
+                built at a commit of the Pants repo."
+            );
+        }
+        if !version::is_full_sha(&sha) {
+            bail!("PANTS_SHA={sha} must be a full 40 character git commit sha of the Pants repo.");
+        }
+        Ok(Some(sha.to_ascii_lowercase()))
+    }
+
+    /// The full, lower case, Pants commit sha selected by a `PANTS_VERSION=git+<sha>`, if any.
+    ///
+    /// Pants is then run from sources in a clone of the Pants repo at that commit.
+    pub fn pants_git_sha(&self) -> Result<Option<String>> {
+        let Some(pants_version) = self.var("PANTS_VERSION")? else {
+            return Ok(None);
+        };
+        let Some(sha) = pants_version.strip_prefix(GIT_VERSION_PREFIX) else {
+            return Ok(None);
+        };
+        // N.B.: Running Pants from sources excludes installing the Pants built at a PANTS_SHA.
+        self.pants_sha()?;
+        // N.B.: GitHub only serves shallow fetches of arbitrary commits by their full sha.
+        if !version::is_full_sha(sha) {
+            bail!(
+                "PANTS_VERSION={pants_version} must name a full 40 character git commit sha of the \
+                Pants repo to run Pants from sources at."
+            );
+        }
+        Ok(Some(sha.to_ascii_lowercase()))
+    }
+
+    fn user_config(&self) -> (Option<PathBuf>, UserConfig) {
+        UserConfig::load_from(UserConfig::path_for(
+            self.var_os("XDG_CONFIG_HOME").map(OsStr::to_os_string),
+        ))
+    }
+}
+
+/// The Pants version to run and where that choice came from. Exactly one of `pants_version`,
+/// `pants_version_specifier`, `pants_sha` and `pants_git_sha` is set.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResolvedVersion {
+    /// The Pants release to run; e.g.: `2.18.0`.
+    pub pants_version: Option<String>,
+    /// A PEP 440 version specifier the newest matching Pants release is run for; e.g.:
+    /// `>=2.18.0,<=2.20.0`.
+    pub pants_version_specifier: Option<String>,
+    /// The commit of the Pants repo whose CI built Pants is run.
+    pub pants_sha: Option<String>,
+    /// The commit of the Pants repo Pants is run from sources at; from `PANTS_VERSION=git+<sha>`.
+    pub pants_git_sha: Option<String>,
+    pub source: VersionSource,
+}
+
+/// Resolves the Pants version to run in the given build root just as `scie-pants` does.
+///
+/// In order, the first of these wins: the `PANTS_VERSION` or `PANTS_SHA` env vars, where
+/// `PANTS_VERSION` may also be a `git+<sha>` to run Pants from sources at a commit, the
+/// `[GLOBAL] pants_version` in the Pants config and the `default_version` in the scie-pants user
+/// config. The user config is skipped when the Pants config delegates to an in-repo launcher
+/// script via `[DEFAULT] delegate_bootstrap`. When no version is found, `None` is returned and
+/// `scie-pants` would delegate or prompt for one.
+///
+/// N.B.: The Pants config files are located just as Pants would, which includes honoring
+/// `SCIE_PANTS_CONFIG_NAME`, `PANTS_TOML` and `PANTS_CONFIG_FILES` as well as `%(env.X)s`
+/// interpolations; all of these are read from `env`.
+pub fn resolve_pants_version(build_root: &Path, env: &Env) -> Result<Option<ResolvedVersion>> {
+    let pants_config = PantsConfig::parse_in(BuildRoot::new(build_root.to_path_buf()), env)?;
+    resolve_pants_version_for(Some(&pants_config), env)
+}
+
+/// Like [`resolve_pants_version`] but for an already parsed Pants config, if any.
+#[doc(hidden)]
+pub fn resolve_pants_version_for(
+    pants_config: Option<&PantsConfig>,
+    env: &Env,
+) -> Result<Option<ResolvedVersion>> {
+    let resolved = |pants_version, pants_version_specifier, pants_sha, source| {
+        Ok(Some(ResolvedVersion {
+            pants_version,
+            pants_version_specifier,
+            pants_sha,
+            pants_git_sha: None,
+            source,
+        }))
+    };
+
+    // N.B.: This also checks PANTS_SHA and PANTS_VERSION are not both set.
+    let env_pants_sha = env.pants_sha()?;
+    if let Some(sha) = env.pants_git_sha()? {
+        debug!("Launch mode: Pants from sources at {sha} (via PANTS_VERSION=git+{sha})");
+        return Ok(Some(ResolvedVersion {
+            pants_version: None,
+            pants_version_specifier: None,
+            pants_sha: None,
+            pants_git_sha: Some(sha),
+            source: VersionSource::for_env_var_in(&env.cwd, "PANTS_VERSION")?,
+        }));
+    }
+    if let Some(env_version) = env.var("PANTS_VERSION")? {
+        version::validate(&env_version)?;
+        debug!("Launch mode: Pants release {env_version} (via the PANTS_VERSION env var)");
+        return resolved(
+            Some(env_version),
+            None,
+            None,
+            VersionSource::for_env_var_in(&env.cwd, "PANTS_VERSION")?,
+        );
+    }
+    if let Some(sha) = env_pants_sha {
+        debug!("Launch mode: Pants built at commit {sha} (via the PANTS_SHA env var)");
+        return resolved(
+            None,
+            None,
+            Some(sha),
+            VersionSource::for_env_var_in(&env.cwd, "PANTS_SHA")?,
+        );
+    }
+    if let Some(configured_version) = pants_config.and_then(PantsConfig::package_version) {
+        version::validate(&configured_version)?;
+        debug!(
+            "Launch mode: Pants release {configured_version} (via `[GLOBAL] pants_version` in the \
+            Pants config)"
+        );
+        return resolved(Some(configured_version), None, None, VersionSource::Config);
+    }
+    // N.B.: A version range is resolved to the newest matching Pants release by the configure
+    // binding; so we just pass it along as a PEP 440 version specifier.
+    if let Some(range) = pants_config.and_then(PantsConfig::package_version_range) {
+        for bound in range.min.iter().chain(range.max.iter()) {
+            version::validate(bound)?;
+        }
+        let specifier = range.specifier();
+        debug!(
+            "Launch mode: the newest Pants release matching `{specifier}` (via `[GLOBAL] \
+            pants_version` in the Pants config)"
+        );
+        return resolved(None, Some(specifier), None, VersionSource::Config);
+    }
+    if pants_config
+        .and_then(PantsConfig::delegate_bootstrap)
+        .is_some()
+    {
+        return Ok(None);
+    }
+    if let (
+        Some(path),
+        UserConfig {
+            default_version: Some(default_version),
+        },
+    ) = env.user_config()
+    {
+        debug!(
+            "Launch mode: Pants release {default_version} (via `default_version` in the user \
+            config at {path})",
+            path = path.display()
+        );
+        return resolved(Some(default_version), None, None, VersionSource::UserConfig);
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{resolve_pants_version, Env, ResolvedVersion, VersionSource};
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    // N.B.: XDG_CONFIG_HOME is always set to keep the user config of whoever runs the tests out of
+    // the picture.
+    fn resolve(
+        build_root: &Path,
+        vars: &[(&str, &str)],
+    ) -> anyhow::Result<Option<ResolvedVersion>> {
+        let config_home = build_root.join("config");
+        let env = Env::new(
+            build_root,
+            vars.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .chain([(
+                    "XDG_CONFIG_HOME".to_string(),
+                    config_home.display().to_string(),
+                )]),
+        );
+        resolve_pants_version(build_root, &env)
+    }
+
+    fn release(version: &str, source: VersionSource) -> Option<ResolvedVersion> {
+        Some(ResolvedVersion {
+            pants_version: Some(version.to_string()),
+            pants_version_specifier: None,
+            pants_sha: None,
+            pants_git_sha: None,
+            source,
+        })
+    }
+
+    #[test]
+    fn env_var() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let build_root = tmpdir.path();
+        write(
+            &build_root.join("pants.toml"),
+            "[GLOBAL]\npants_version = \"2.18.0\"\n",
+        );
+        assert_eq!(
+            release("2.19.0", VersionSource::Env),
+            resolve(build_root, &[("PANTS_VERSION", "2.19.0")]).unwrap()
+        );
+        assert_eq!(
+            release("2.18.0", VersionSource::Config),
+            resolve(build_root, &[("PANTS_VERSION", "")]).unwrap()
+        );
+        assert!(resolve(build_root, &[("PANTS_VERSION", "2.19")]).is_err());
+
+        write(&build_root.join(".env"), "export PANTS_VERSION=2.19.0\n");
+        assert_eq!(
+            release("2.19.0", VersionSource::DotEnv),
+            resolve(build_root, &[("PANTS_VERSION", "2.19.0")]).unwrap()
+        );
+    }
+
+    #[test]
+    fn pants_sha() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let build_root = tmpdir.path();
+        write(
+            &build_root.join("pants.toml"),
+            "[GLOBAL]\npants_version = \"2.18.0\"\n",
+        );
+        assert_eq!(
+            Some(ResolvedVersion {
+                pants_version: None,
+                pants_version_specifier: None,
+                pants_sha: Some(SHA.to_string()),
+                pants_git_sha: None,
+                source: VersionSource::Env,
+            }),
+            resolve(build_root, &[("PANTS_SHA", &SHA.to_uppercase())]).unwrap()
+        );
+        assert!(resolve(
+            build_root,
+            &[("PANTS_SHA", SHA), ("PANTS_VERSION", "2.19.0")]
+        )
+        .is_err());
+        assert!(resolve(build_root, &[("PANTS_SHA", "0123456")]).is_err());
+    }
+
+    #[test]
+    fn pants_git_sha() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let build_root = tmpdir.path();
+        write(
+            &build_root.join("pants.toml"),
+            "[GLOBAL]\npants_version = \"2.18.0\"\n",
+        );
+        assert_eq!(
+            Some(ResolvedVersion {
+                pants_version: None,
+                pants_version_specifier: None,
+                pants_sha: None,
+                pants_git_sha: Some(SHA.to_string()),
+                source: VersionSource::Env,
+            }),
+            resolve(
+                build_root,
+                &[("PANTS_VERSION", &format!("git+{}", SHA.to_uppercase()))]
+            )
+            .unwrap()
+        );
+        assert!(resolve(build_root, &[("PANTS_VERSION", "git+0123456")]).is_err());
+        assert!(resolve(
+            build_root,
+            &[("PANTS_VERSION", &format!("git+{SHA}")), ("PANTS_SHA", SHA)]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn config() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let build_root = tmpdir.path();
+        write(
+            &build_root
+                .join("config")
+                .join("pants")
+                .join("scie-pants.toml"),
+            "default_version = \"2.17.0\"\n",
+        );
+
+        write(
+            &build_root.join("pants.toml"),
+            "[GLOBAL]\npants_version = \"2.18.0\"\n",
+        );
+        assert_eq!(
+            release("2.18.0", VersionSource::Config),
+            resolve(build_root, &[]).unwrap()
+        );
+
+        write(
+            &build_root.join("pants.toml"),
+            "[GLOBAL]\npants_version = { min = \"2.18.0\", max = \"2.20.0\" }\n",
+        );
+        assert_eq!(
+            Some(ResolvedVersion {
+                pants_version: None,
+                pants_version_specifier: Some(">=2.18.0,<=2.20.0".to_string()),
+                pants_sha: None,
+                pants_git_sha: None,
+                source: VersionSource::Config,
+            }),
+            resolve(build_root, &[]).unwrap()
+        );
+        assert_eq!(
+            release("2.19.0", VersionSource::Env),
+            resolve(build_root, &[("PANTS_VERSION", "2.19.0")]).unwrap()
+        );
+    }
+
+    #[test]
+    fn user_config() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let build_root = tmpdir.path();
+        write(&build_root.join("pants.toml"), "");
+        assert_eq!(None, resolve(build_root, &[]).unwrap());
+
+        write(
+            &build_root
+                .join("config")
+                .join("pants")
+                .join("scie-pants.toml"),
+            "default_version = \"2.17.0\"\n",
+        );
+        assert_eq!(
+            release("2.17.0", VersionSource::UserConfig),
+            resolve(build_root, &[]).unwrap()
+        );
+
+        // Delegating to an in-repo launcher script takes precedence over the user config.
+        write(
+            &build_root.join("pants.toml"),
+            "[DEFAULT]\ndelegate_bootstrap = true\n",
+        );
+        assert_eq!(None, resolve(build_root, &[]).unwrap());
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// N.B.: The phases are always recorded since that's cheap, but they're only reported when
+// SCIE_PANTS_TIMING=1 or written out when SCIE_PANTS_METRICS_FILE is set.
+static PHASES: Mutex<Vec<(&'static str, Duration)>> = Mutex::new(Vec::new());
+
+/// Runs the given phase of launching Pants, recording how long it took.
+pub(crate) fn time<T>(phase: &'static str, run: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = run();
+    let elapsed = start.elapsed();
+    if let Ok(mut phases) = PHASES.lock() {
+        phases.push((phase, elapsed));
+    }
+    result
+}
+
+/// The phases recorded so far, in the order they ran.
+pub(crate) fn phases() -> Vec<(&'static str, Duration)> {
+    PHASES
+        .lock()
+        .map(|phases| phases.clone())
+        .unwrap_or_default()
+}
+
+/// Prints a table of the phases recorded, in the order they ran, followed by the total time.
+pub(crate) fn report(total: Duration) {
+    let rows = phases()
+        .into_iter()
+        .chain(std::iter::once(("total", total)))
+        .collect::<Vec<_>>();
+    let width = rows.iter().map(|(phase, _)| phase.len()).max().unwrap_or(0);
+    eprintln!("scie-pants timing:");
+    for (phase, elapsed) in rows {
+        eprintln!(
+            "  {phase:<width$}  {millis:>9.3}ms",
+            millis = elapsed.as_secs_f64() * 1000.0
+        );
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::env;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::warn;
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+// N.B.: Release builds set this to the hex-encoded ed25519 public key whose private key signs the
+// release binaries; local builds generally don't and so can't verify updates.
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("SCIE_PANTS_UPDATE_PUBLIC_KEY");
+
+/// Verifies a scie-pants binary downloaded by the `update` boot command against its detached
+/// signature for the `verify-update` boot command and returns the exit code to use.
+///
+/// The binary and signature paths are passed as the sole two arguments.
+pub(crate) fn run() -> Result<i32> {
+    let args = env::args_os().skip(1).collect::<Vec<_>>();
+    let [binary, signature] = args.as_slice() else {
+        bail!("Usage: SCIE_BOOT=verify-update scie-pants <binary> <signature>");
+    };
+    let Some(public_key) = UPDATE_PUBLIC_KEY.filter(|key| !key.trim().is_empty()) else {
+        warn!(
+            "This scie-pants was built without an update signing key; so the signature of {path} \
+            cannot be verified.",
+            path = Path::new(binary).display()
+        );
+        return Ok(0);
+    };
+    verify(
+        &decode_hex(public_key).context("The embedded update public key is not valid hex.")?,
+        Path::new(binary),
+        Path::new(signature),
+    )?;
+    Ok(0)
+}
+
+fn verify(public_key: &[u8], binary: &Path, signature: &Path) -> Result<()> {
+    let contents = std::fs::read(binary)
+        .with_context(|| format!("Failed to read {path}", path = binary.display()))?;
+    let signature_hex = std::fs::read_to_string(signature)
+        .with_context(|| format!("Failed to read {path}", path = signature.display()))?;
+    let signature_bytes = decode_hex(&signature_hex).with_context(|| {
+        format!(
+            "The signature {path} is not valid hex.",
+            path = signature.display()
+        )
+    })?;
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(&contents, &signature_bytes)
+        .map_err(|_| {
+            anyhow!(
+                "The signature {signature} does not match {binary}; refusing to install it.",
+                signature = signature.display(),
+                binary = binary.display()
+            )
+        })
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.trim();
+    if hex.len() % 2 != 0 {
+        bail!(
+            "Expected an even number of hex digits, found {len}.",
+            len = hex.len()
+        );
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| {
+            hex.get(index..index + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .with_context(|| format!("Invalid hex byte at offset {index}."))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    use super::verify;
+
+    fn encode_hex(bytes: &[u8]) -> String {
+        bytes.iter().fold(String::new(), |mut hex, byte| {
+            write!(hex, "{byte:02x}").unwrap();
+            hex
+        })
+    }
+
+    #[test]
+    fn verify_update() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = key_pair.public_key().as_ref();
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let binary = tmpdir.path().join("scie-pants");
+        std::fs::write(&binary, b"the real scie-pants").unwrap();
+        let signature = tmpdir.path().join("scie-pants.sig");
+        std::fs::write(
+            &signature,
+            encode_hex(key_pair.sign(b"the real scie-pants").as_ref()),
+        )
+        .unwrap();
+        verify(public_key, &binary, &signature).unwrap();
+
+        std::fs::write(&binary, b"the real scie-pants, tampered").unwrap();
+        let error = verify(public_key, &binary, &signature).unwrap_err();
+        assert!(
+            format!("{error}").contains("refusing to install it"),
+            "{error}"
+        );
+
+        let other_pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let other_key_pair = Ed25519KeyPair::from_pkcs8(other_pkcs8.as_ref()).unwrap();
+        std::fs::write(&binary, b"the real scie-pants").unwrap();
+        assert!(verify(other_key_pair.public_key().as_ref(), &binary, &signature).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::warn;
+use logging_timer::time;
+use serde::Deserialize;
+
+use crate::version;
+
+/// Per-user scie-pants settings read from `$XDG_CONFIG_HOME/pants/scie-pants.toml`, falling back
+/// to `~/.config/pants/scie-pants.toml`.
+#[derive(Default, Deserialize)]
+pub struct UserConfig {
+    /// The Pants version to use in a project that does not configure one.
+    pub default_version: Option<String>,
+}
+
+impl UserConfig {
+    pub fn path() -> Option<PathBuf> {
+        Self::path_for(std::env::var_os("XDG_CONFIG_HOME"))
+    }
+
+    pub fn path_for(xdg_config_home: Option<OsString>) -> Option<PathBuf> {
+        xdg_config_home
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| dirs::home_dir().map(|home_dir| home_dir.join(".config")))
+            .map(|config_dir| config_dir.join("pants").join("scie-pants.toml"))
+    }
+
+    pub fn load() -> (Option<PathBuf>, UserConfig) {
+        Self::load_from(Self::path())
+    }
+
+    // N.B.: The user config is a convenience; so problems reading it are warned about and
+    // otherwise ignored instead of failing the Pants run.
+    #[time("debug", "UserConfig::{}")]
+    pub fn load_from(path: Option<PathBuf>) -> (Option<PathBuf>, UserConfig) {
+        let Some(path) = path.filter(|path| path.exists()) else {
+            return (None, UserConfig::default());
+        };
+        match Self::parse(&path) {
+            Ok(user_config) => (Some(path), user_config),
+            Err(err) => {
+                warn!("Ignoring the scie-pants user config: {err:#}");
+                (Some(path), UserConfig::default())
+            }
+        }
+    }
+
+    fn parse(path: &Path) -> Result<UserConfig> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {path}", path = path.display()))?;
+        let user_config: UserConfig = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {path}", path = path.display()))?;
+        if let Some(ref default_version) = user_config.default_version {
+            version::validate(default_version).with_context(|| {
+                format!("Invalid `default_version` in {path}", path = path.display())
+            })?;
+        }
+        Ok(user_config)
+    }
+}
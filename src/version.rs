@@ -0,0 +1,207 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum VersionError {
+    /// The version is well-formed but only has a major and minor release component; e.g.: `2.18`.
+    MissingPatch { got: String },
+    /// The version is not a valid PEP 440 version; e.g.: `2..18.0`.
+    Malformed { got: String },
+}
+
+impl Display for VersionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionError::MissingPatch { got } => write!(
+                f,
+                "Pants version must be a full version, including patch level, got: `{got}`.\n\
+                Please add `.<patch_version>` to the end of the version. For example: `2.18` -> \
+                `2.18.0`."
+            ),
+            VersionError::Malformed { got } => write!(
+                f,
+                "Pants version must be a full version of the form \
+                `<major>.<minor>.<patch_version>`, optionally followed by a pre-release or dev \
+                suffix, got: `{got}`.\n\
+                For example: `2.18.0`, `2.17.0a1` or `2.25.0.dev1`."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VersionError {}
+
+/// Checks that the given Pants version is a full version, including patch level.
+///
+/// This mirrors the check the tools perform with a PEP 440 version parser, but runs before we
+/// launch anything; so a bad version fails fast with a friendly message.
+pub fn validate(version: &str) -> Result<(), VersionError> {
+    let malformed = || VersionError::Malformed {
+        got: version.to_owned(),
+    };
+
+    let normalized = version.trim().to_ascii_lowercase();
+    let mut rest = normalized.strip_prefix('v').unwrap_or(&normalized);
+    // An epoch; e.g.: `1!2.18.0`.
+    if let Some((epoch, remaining)) = rest.split_once('!') {
+        if !is_number(epoch) {
+            return Err(malformed());
+        }
+        rest = remaining;
+    }
+
+    let release_end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    let (release, suffix) = rest.split_at(release_end);
+    // N.B.: A dot before a `dev` or `post` suffix belongs to the suffix; e.g.: `2.25.0.dev1`.
+    let (release, suffix) = match release.strip_suffix('.') {
+        Some(release) if !suffix.is_empty() => (release, &rest[release.len()..]),
+        _ => (release, suffix),
+    };
+    if !release.split('.').all(is_number) {
+        return Err(malformed());
+    }
+    if !is_valid_suffix(suffix) {
+        return Err(malformed());
+    }
+    if release.split('.').count() < 3 {
+        return Err(VersionError::MissingPatch {
+            got: version.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Returns true if the value is a full 40 character hex git commit sha.
+pub fn is_full_sha(value: &str) -> bool {
+    value.len() == 40 && value.chars().all(|char| char.is_ascii_hexdigit())
+}
+
+/// A Pants release below which Pants versions are no longer supported.
+pub struct SupportFloor {
+    /// The oldest supported Pants release; e.g.: `2.0.0`.
+    pub minimum: &'static str,
+    /// The docs describing how to migrate to the minimum release or newer.
+    pub migration_docs: &'static str,
+}
+
+/// The Pants releases below which Pants versions are end of life, oldest first.
+pub const SUPPORT_FLOORS: &[SupportFloor] = &[SupportFloor {
+    minimum: "2.0.0",
+    migration_docs: "https://www.pantsbuild.org/stable/docs/releases/upgrade-tips",
+}];
+
+/// Returns the highest support floor the given Pants version is older than, if any.
+///
+/// Only the release segment is compared; so pre-releases of a floor release are not considered
+/// older than it; e.g.: `2.0.0rc1` is not older than `2.0.0`.
+pub fn unsupported(version: &str) -> Option<&'static SupportFloor> {
+    let version = release(version)?;
+    SUPPORT_FLOORS
+        .iter()
+        .rev()
+        .find(|floor| release(floor.minimum).is_some_and(|minimum| version < minimum))
+}
+
+fn release(version: &str) -> Option<Vec<u64>> {
+    let normalized = version.trim().to_ascii_lowercase();
+    let rest = normalized.strip_prefix('v').unwrap_or(&normalized);
+    let rest = rest
+        .split_once('!')
+        .map_or(rest, |(_, remaining)| remaining);
+    let release_end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    rest[..release_end]
+        .trim_end_matches('.')
+        .split('.')
+        .map(|component| component.parse().ok())
+        .collect()
+}
+
+fn is_number(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+}
+
+// Validates the PEP 440 pre-release, post-release, dev-release and local version segments, in
+// that order, that may follow the release segment.
+fn is_valid_suffix(suffix: &str) -> bool {
+    let (public, local) = match suffix.split_once('+') {
+        Some((public, local)) => (public, Some(local)),
+        None => (suffix, None),
+    };
+    if let Some(local) = local {
+        if !local
+            .split(['.', '-', '_'])
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+        {
+            return false;
+        }
+    }
+
+    let mut rest = public;
+    for labels in [
+        &["alpha", "beta", "preview", "pre", "rc", "a", "b", "c"][..],
+        &["post", "rev", "r"][..],
+        &["dev"][..],
+    ] {
+        rest = strip_segment(rest, labels);
+    }
+    rest.is_empty()
+}
+
+fn strip_segment<'a>(value: &'a str, labels: &[&str]) -> &'a str {
+    let separated = value.strip_prefix(['.', '-', '_']).unwrap_or(value);
+    let Some(rest) = labels
+        .iter()
+        .find_map(|label| separated.strip_prefix(label))
+    else {
+        return value;
+    };
+    let rest = match rest.strip_prefix(['.', '-', '_']) {
+        Some(numbered) if numbered.starts_with(|c: char| c.is_ascii_digit()) => numbered,
+        _ => rest,
+    };
+    rest.trim_start_matches(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unsupported, validate, VersionError};
+
+    #[test]
+    fn validate_forms() {
+        for version in ["2.18.0", "2.17.0a1", "2.25.0.dev1"] {
+            assert_eq!(Ok(()), validate(version), "{version}");
+        }
+        assert_eq!(
+            Err(VersionError::MissingPatch {
+                got: "2.19".to_owned()
+            }),
+            validate("2.19")
+        );
+        for version in ["2..18.0", "latest"] {
+            assert_eq!(
+                Err(VersionError::Malformed {
+                    got: version.to_owned()
+                }),
+                validate(version)
+            );
+        }
+    }
+
+    #[test]
+    fn support_floors() {
+        assert_eq!(
+            Some("2.0.0"),
+            unsupported("1.30.5rc1").map(|floor| floor.minimum)
+        );
+        assert!(unsupported("2.0.0rc1").is_none());
+        assert!(unsupported("2.0.0").is_none());
+        assert!(unsupported("2.18.0").is_none());
+        assert!(unsupported("not-a-version").is_none());
+    }
+}
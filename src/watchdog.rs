@@ -0,0 +1,77 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::ffi::OsString;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, thread};
+
+use anyhow::{anyhow, Result};
+use log::error;
+
+use crate::{env_version, EXIT_CODE_ERROR};
+
+const TIMEOUT_ENV_VAR: &str = "SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS";
+
+// N.B.: The bootstrap continues in the configure and install bindings after we exec the scie; so
+// we hand them the absolute deadline for the whole bootstrap instead of the timeout.
+const DEADLINE_ENV_VAR: &str = "SCIE_PANTS_BOOTSTRAP_DEADLINE";
+
+/// Enforces `SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS` for the work scie-pants does before launching
+/// Pants.
+pub(crate) struct Watchdog {
+    deadline: SystemTime,
+    cancel: Sender<()>,
+}
+
+impl Watchdog {
+    /// Starts a watchdog if `SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS` is set; the process exits with an
+    /// error if the watchdog is not cancelled before the timeout elapses.
+    pub(crate) fn start() -> Result<Option<Self>> {
+        let Some(timeout_secs) = timeout_secs()? else {
+            // N.B.: A deadline inherited from a parent bootstrap, say by a Pants run that itself
+            // runs Pants, must not apply to us.
+            env::remove_var(DEADLINE_ENV_VAR);
+            return Ok(None);
+        };
+        let timeout = Duration::from_secs(timeout_secs);
+        let deadline = SystemTime::now() + timeout;
+        let (cancel, cancelled) = mpsc::channel::<()>();
+        thread::spawn(move || {
+            if let Err(RecvTimeoutError::Timeout) = cancelled.recv_timeout(timeout) {
+                error!(
+                    "Timed out bootstrapping Pants: it took longer than \
+                    {TIMEOUT_ENV_VAR}={timeout_secs} seconds."
+                );
+                std::process::exit(EXIT_CODE_ERROR);
+            }
+        });
+        Ok(Some(Self { deadline, cancel }))
+    }
+
+    /// The env var the configure and install bindings use to enforce what is left of the timeout.
+    pub(crate) fn deadline_env(&self) -> (OsString, OsString) {
+        let deadline = self
+            .deadline
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        (DEADLINE_ENV_VAR.into(), format!("{deadline:.3}").into())
+    }
+
+    pub(crate) fn cancel(self) {
+        let _ = self.cancel.send(());
+    }
+}
+
+fn timeout_secs() -> Result<Option<u64>> {
+    let Some(timeout) = env_version(TIMEOUT_ENV_VAR)? else {
+        return Ok(None);
+    };
+    match timeout.trim().parse() {
+        Ok(secs) if secs > 0 => Ok(Some(secs)),
+        _ => Err(anyhow!(
+            "{TIMEOUT_ENV_VAR} must be a positive integer number of seconds, got: `{timeout}`."
+        )),
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2024 Pants project contributors.
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+use std::os::windows::io::AsRawHandle;
+use std::process::Child;
+
+use anyhow::{bail, Result};
+use windows_sys::Win32::Foundation::{CloseHandle, BOOL, FALSE, HANDLE, TRUE};
+use windows_sys::Win32::System::Console::{SetConsoleCtrlHandler, CTRL_BREAK_EVENT, CTRL_C_EVENT};
+use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+    SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+};
+
+// N.B.: The Pants process is spawned attached to our console and in our process group; so the
+// console already delivers Ctrl-C and Ctrl-Break to it, as well as to us. We just need to survive
+// those events ourselves so that we can wait for Pants to exit and report its exit code.
+unsafe extern "system" fn forward_to_child(ctrl_type: u32) -> BOOL {
+    match ctrl_type {
+        CTRL_C_EVENT | CTRL_BREAK_EVENT => TRUE,
+        // Let the default handler deal with console close, logoff and shutdown events.
+        _ => FALSE,
+    }
+}
+
+/// Arranges for Ctrl-C and Ctrl-Break to be handled by the Pants process alone while we wait on
+/// it.
+pub(crate) fn forward_console_ctrl_events() -> Result<()> {
+    if unsafe { SetConsoleCtrlHandler(Some(forward_to_child), TRUE) } == FALSE {
+        bail!(
+            "Failed to install a console control handler: {err}",
+            err = std::io::Error::last_os_error()
+        );
+    }
+    Ok(())
+}
+
+/// Ensures the child is terminated if we are, say by `taskkill /F`, instead of being orphaned.
+///
+/// The child is assigned to a job object that kills all of its processes when its last handle is
+/// closed; we never close our handle, so that happens when we exit. If this fails, the job object,
+/// if any, is closed and the child is left running.
+pub(crate) fn kill_on_exit(child: &Child) -> Result<()> {
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job == 0 {
+            bail!(
+                "Failed to create a job object: {err}",
+                err = std::io::Error::last_os_error()
+            );
+        }
+        if let Err(err) = assign_to_kill_on_close_job(job, child) {
+            CloseHandle(job);
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn assign_to_kill_on_close_job(job: HANDLE, child: &Child) -> Result<()> {
+    unsafe {
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        if SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const std::ffi::c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        ) == FALSE
+        {
+            bail!(
+                "Failed to configure a job object: {err}",
+                err = std::io::Error::last_os_error()
+            );
+        }
+        if AssignProcessToJobObject(job, child.as_raw_handle() as HANDLE) == FALSE {
+            bail!(
+                "Failed to assign the Pants process to a job object: {err}",
+                err = std::io::Error::last_os_error()
+            );
+        }
+    }
+    Ok(())
+}
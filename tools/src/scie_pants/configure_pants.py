@@ -3,6 +3,7 @@
 
 from __future__ import annotations
 
+import json
 import os
 import shutil
 import subprocess
@@ -16,7 +17,10 @@ from packaging.version import Version
 from scie_pants.log import fatal, info, init_logging, warn
 from scie_pants.pants_version import (
     determine_latest_stable_version,
+    determine_sha_version,
     determine_tag_version,
+    determine_version_in_range,
+    select_python,
 )
 from scie_pants.ptex import Ptex
 
@@ -55,10 +59,44 @@ def prompt_for_pants_config() -> Path | None:
     return None
 
 
+def interpreter_constraints() -> list[str]:
+    """Returns the `[python] interpreter_constraints` forwarded by scie-pants, if any."""
+    constraints = os.environ.get("PANTS_BOOTSTRAP_INTERPRETER_CONSTRAINTS")
+    if not constraints:
+        return []
+    try:
+        entries = json.loads(constraints)
+    except json.JSONDecodeError as e:
+        fatal(f"Failed to parse PANTS_BOOTSTRAP_INTERPRETER_CONSTRAINTS={constraints!r}: {e}")
+    if not isinstance(entries, list) or not all(isinstance(entry, str) for entry in entries):
+        fatal(
+            f"Expected PANTS_BOOTSTRAP_INTERPRETER_CONSTRAINTS to be a JSON list of strings, "
+            f"given: {constraints!r}"
+        )
+    return entries
+
+
+def prompt_declined() -> NoReturn:
+    # N.B.: The scie-pants launcher exits with a dedicated exit code when it finds this file
+    # written to since the scie-jump does not pass our exit code through.
+    declined_file = os.environ.get("SCIE_PANTS_PROMPT_DECLINED_FILE")
+    if declined_file:
+        with open(declined_file, "w") as fp:
+            fp.write("declined")
+    sys.exit(1)
+
+
 def main() -> NoReturn:
     parser = ArgumentParser()
     get_ptex = Ptex.add_options(parser)
     parser.add_argument("--pants-version", help="The Pants version to install")
+    parser.add_argument(
+        "--pants-version-specifier",
+        help="A PEP 440 version specifier the newest matching Pants release is installed for",
+    )
+    parser.add_argument(
+        "--pants-sha", help="The commit of the Pants repo to install the Pants CI built for"
+    )
     parser.add_argument("--pants-config", help="The path of the pants.toml file")
     parser.add_argument(
         "--github-api-bearer-token", help="The GITHUB_TOKEN to use if running in CI context."
@@ -85,7 +123,9 @@ def main() -> NoReturn:
     finalizers = []
     newly_created_build_root = None
     pants_config = Path(options.pants_config) if options.pants_config else None
-    if options.pants_version:
+    if options.pants_sha:
+        resolve_info = determine_sha_version(ptex=ptex, sha=options.pants_sha)
+    elif options.pants_version:
         resolve_info = determine_tag_version(
             ptex=ptex,
             pants_version=options.pants_version,
@@ -93,14 +133,22 @@ def main() -> NoReturn:
             github_api_bearer_token=options.github_api_bearer_token,
             bootstrap_urls_path=options.pants_bootstrap_urls,
         )
+    elif options.pants_version_specifier:
+        resolve_info = determine_version_in_range(
+            ptex=ptex,
+            pants_version_specifier=options.pants_version_specifier,
+            find_links_dir=find_links_dir,
+            github_api_bearer_token=options.github_api_bearer_token,
+            bootstrap_urls_path=options.pants_bootstrap_urls,
+        )
     else:
         if pants_config:
             if not prompt_for_pants_version(options.pants_config):
-                sys.exit(1)
+                prompt_declined()
         else:
             maybe_pants_config = prompt_for_pants_config()
             if not maybe_pants_config:
-                sys.exit(1)
+                prompt_declined()
             pants_config = maybe_pants_config
             newly_created_build_root = pants_config.parent
 
@@ -116,11 +164,15 @@ def main() -> NoReturn:
     for finalizer in finalizers:
         finalizer()
 
+    resolve_info = select_python(resolve_info, interpreter_constraints())
+
     with open(env_file, "a") as fp:
         print(f"PANTS_VERSION={resolve_info.version}", file=fp)
         print(f"PYTHON={resolve_info.python}", file=fp)
         if resolve_info.pex_url:
             print(f"PANTS_PEX_URL={resolve_info.pex_url}", file=fp)
+        if resolve_info.pex_sha256:
+            print(f"PANTS_PEX_SHA256={resolve_info.pex_sha256}", file=fp)
         if resolve_info.find_links:
             print(f"FIND_LINKS={resolve_info.find_links}", file=fp)
         if newly_created_build_root:
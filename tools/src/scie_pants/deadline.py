@@ -0,0 +1,44 @@
+# Copyright 2024 Pants project contributors.
+# Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+from __future__ import annotations
+
+import os
+import subprocess
+import time
+from subprocess import CompletedProcess
+from typing import Any, NoReturn
+
+from scie_pants.log import fatal
+
+
+def remaining_time(description: str) -> float | None:
+    """Returns the seconds left before the bootstrap deadline, if there is one.
+
+    The scie-pants launcher exports `SCIE_PANTS_BOOTSTRAP_DEADLINE` as a UNIX timestamp when
+    `SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS` is set. If the deadline has already passed, the bootstrap is
+    aborted.
+    """
+    deadline = os.environ.get("SCIE_PANTS_BOOTSTRAP_DEADLINE")
+    if not deadline:
+        return None
+    remaining = float(deadline) - time.time()
+    if remaining <= 0:
+        timed_out(description)
+    return remaining
+
+
+def timed_out(description: str) -> NoReturn:
+    timeout = os.environ.get("SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS")
+    fatal(
+        f"Timed out {description}: bootstrapping Pants took longer than "
+        f"SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS={timeout} seconds."
+    )
+
+
+def run(description: str, args: list[str], **kwargs: Any) -> CompletedProcess:
+    """Runs `subprocess.run` but aborts the bootstrap if the process outlives the deadline."""
+    try:
+        return subprocess.run(args=args, timeout=remaining_time(description), **kwargs)
+    except subprocess.TimeoutExpired:
+        timed_out(description)
@@ -3,7 +3,7 @@
 
 from __future__ import annotations
 
-import json
+import hashlib
 import logging
 import os
 import stat
@@ -17,6 +17,7 @@ from typing import Iterable, NoReturn
 
 from packaging.version import Version
 
+from scie_pants import deadline
 from scie_pants.log import debug, fatal, info, init_logging
 from scie_pants.ptex import Ptex
 
@@ -24,8 +25,12 @@ log = logging.getLogger(__name__)
 
 
 def venv_pip_install(venv_dir: Path, *args: str, find_links: str | None = None) -> None:
-    subprocess.run(
-        args=[
+    if os.environ.get("SCIE_PANTS_OFFLINE", "").strip() == "1":
+        source = f"PyPI or {find_links}" if find_links else "PyPI"
+        fatal(f"SCIE_PANTS_OFFLINE=1 is set; so refusing to install {' '.join(args)} from {source}")
+    deadline.run(
+        "installing Pants",
+        [
             str(venv_dir / "bin" / "python"),
             "-sE",
             "-m",
@@ -47,8 +52,9 @@ def venv_pip_install(venv_dir: Path, *args: str, find_links: str | None = None)
 def install_pants_from_req(
     venv_dir: Path, prompt: str, pants_requirements: Iterable[str], find_links: str | None
 ) -> None:
-    subprocess.run(
-        args=[
+    deadline.run(
+        "installing Pants",
+        [
             sys.executable,
             "-m",
             "venv",
@@ -78,6 +84,7 @@ def install_pants_from_pex(
     pex_url: str,
     ptex: Ptex,
     extra_requirements: Iterable[str],
+    pex_sha256: str | None = None,
 ) -> None:
     """Installs Pants into the venv using the platform-specific pre-built PEX."""
     pex_name = os.path.basename(pex_url)
@@ -90,9 +97,13 @@ def install_pants_from_pex(
                 "Check to see if the URL is reachable.\n\n"
                 f"Exception:\n{e}"
             )
+        if pex_sha256:
+            pants_pex.file.flush()
+            verify_sha256(Path(pants_pex.name), pex_url, pex_sha256)
         try:
-            pants_venv_result = subprocess.run(
-                args=[
+            pants_venv_result = deadline.run(
+                "installing Pants",
+                [
                     sys.executable,
                     pants_pex.name,
                     "venv",
@@ -126,6 +137,21 @@ def install_pants_from_pex(
         venv_pip_install(venv_dir, "--progress-bar", "off", *extra_requirements)
 
 
+def verify_sha256(path: Path, url: str, expected_sha256: str) -> None:
+    digest = hashlib.sha256()
+    with path.open("rb") as fp:
+        for chunk in iter(lambda: fp.read(65536), b""):
+            digest.update(chunk)
+    actual_sha256 = digest.hexdigest()
+    if actual_sha256 != expected_sha256:
+        fatal(
+            f"The Pants PEX fetched from {url} does not match the digest listed for it in the "
+            "PANTS_BOOTSTRAP_URLS file.\n"
+            f"Expected sha256: {expected_sha256}\n"
+            f"Actual sha256:   {actual_sha256}"
+        )
+
+
 def chmod_plus_x(path: str) -> None:
     os.chmod(path, os.stat(path).st_mode | stat.S_IXUSR | stat.S_IXGRP | stat.S_IXOTH)
 
@@ -137,6 +163,9 @@ def main() -> NoReturn:
         "--pants-version", type=Version, required=True, help="The Pants version to install."
     )
     parser.add_argument("--pants-pex-url", type=str, help="The pants pex release asset url.")
+    parser.add_argument(
+        "--pants-pex-sha256", type=str, help="The expected sha256 of the pants pex, if known."
+    )
     parser.add_argument(
         "--find-links",
         type=str,
@@ -185,6 +214,7 @@ def main() -> NoReturn:
             pex_url=options.pants_pex_url,
             ptex=ptex,
             extra_requirements=extra_requirements,
+            pex_sha256=options.pants_pex_sha256,
         )
     else:
         install_pants_from_req(
@@ -3,10 +3,13 @@
 
 from __future__ import annotations
 
+import dataclasses
 import importlib.resources
 import json
 import os
 import re
+import socket
+import urllib.error
 import urllib.parse
 import urllib.request
 from dataclasses import dataclass
@@ -17,10 +20,10 @@ from xml.etree import ElementTree
 
 import tomlkit
 from packaging.specifiers import SpecifierSet
-from packaging.version import Version
+from packaging.version import InvalidVersion, Version
 
 from scie_pants.log import debug, fatal, info, warn
-from scie_pants.ptex import Ptex
+from scie_pants.ptex import Ptex, check_online, with_retries
 
 TIMEOUT = int(os.getenv("PANTS_BOOTSTRAP_URL_REQUEST_TIMEOUT_SECONDS", "10"))
 PANTS_PEX_GITHUB_RELEASE_VERSION = Version("2.0.0.dev0")
@@ -31,6 +34,13 @@ PANTS_PYTHON_VERSIONS = [
     {"pants": "2.5.0.dev0", "python": "cp39"},
     {"pants": "2.0.0.dev0", "python": "cp38"},
 ]
+# N.B.: Pants 1.x runs on CPython 3.6 through 3.8; of the interpreters scie-pants provides, that
+# leaves just 3.8. Add to this list if a Pants 1.x release ever supports a newer Python.
+PANTS_1_PYTHONS = ["cpython38"]
+PANTS_PEX_NAME_RE = re.compile(
+    r"^pants\.(?P<version>.+)-(?P<python>cp\d+)-(?P<platform>[^-]+)\.pex$"
+)
+SHA256_RE = re.compile(r"^[0-9a-fA-F]{64}$")
 PYTHON_IDS = {
     # N.B.: These values must match the lift TOML interpreter ids.
     # Important: all pythons used in pants_python_versions.json must be represented in this list.
@@ -47,6 +57,7 @@ class ResolveInfo:
     python: str
     find_links: str | None = None
     pex_url: str | None = None
+    pex_sha256: str | None = None
 
 
 def determine_find_links(
@@ -99,8 +110,10 @@ def determine_tag_version(
         )
 
     if version >= PANTS_PEX_GITHUB_RELEASE_VERSION:
-        pex_url, python = determine_pex_url_and_python_id(ptex, version, bootstrap_urls_path)
-        return ResolveInfo(version=version, python=python, pex_url=pex_url)
+        pex_url, python, pex_sha256 = determine_pex_url_and_python_id(
+            ptex, version, bootstrap_urls_path
+        )
+        return ResolveInfo(version=version, python=python, pex_url=pex_url, pex_sha256=pex_sha256)
 
     tag = f"release_{pants_version}"
 
@@ -150,6 +163,36 @@ def determine_tag_version(
     )
 
 
+def determine_sha_version(ptex: Ptex, sha: str) -> ResolveInfo:
+    # N.B.: Pants CI publishes the Pants wheels it builds for each commit under the commit sha just
+    # like the legacy `./pants` script expects for its PANTS_SHA support.
+    version_url = (
+        f"https://raw.githubusercontent.com/pantsbuild/pants/{sha}/src/python/pants/VERSION"
+    )
+    try:
+        base_version = ptex.fetch_text(version_url).strip()
+    except CalledProcessError as e:
+        fatal(
+            f"Failed to determine the Pants version at PANTS_SHA={sha} from {version_url}. Check "
+            f"that the sha is a commit of the Pants repo.\n{e}"
+        )
+    version = Version(f"{base_version}+git{sha[:8]}")
+
+    python_id = get_python_id_for_pants_version(version)
+    python = PYTHON_IDS.get(python_id) if python_id else None
+    if not python:
+        fatal(f"Pants {version} at PANTS_SHA={sha} is not supported by scie-pants.")
+
+    return ResolveInfo(
+        version=version,
+        python=python,
+        find_links=(
+            f"https://binaries.pantsbuild.org/wheels/pantsbuild.pants/{sha}/"
+            f"{urllib.parse.quote(str(version))}/index.html"
+        ),
+    )
+
+
 def determine_latest_stable_version(
     ptex: Ptex,
     pants_config: Path,
@@ -199,18 +242,90 @@ def determine_latest_stable_version(
     )
 
 
+def determine_version_in_range(
+    ptex: Ptex,
+    pants_version_specifier: str,
+    find_links_dir: Path,
+    github_api_bearer_token: str | None,
+    bootstrap_urls_path: str | None,
+) -> ResolveInfo:
+    specifier_set = SpecifierSet(pants_version_specifier)
+    versions = list_released_versions(ptex, github_api_bearer_token, bootstrap_urls_path)
+    matching = list(specifier_set.filter(versions))
+    if not matching:
+        considered = ", ".join(str(version) for version in sorted(versions, reverse=True))
+        fatal(
+            f"No Pants release matches the `[GLOBAL] pants_version` range `{specifier_set}`.\n"
+            f"The releases considered were: {considered or '<none>'}"
+        )
+    pants_version = max(matching)
+    info(f"Resolved the Pants version range `{specifier_set}` to Pants {pants_version}")
+    return determine_tag_version(
+        ptex, str(pants_version), find_links_dir, github_api_bearer_token, bootstrap_urls_path
+    )
+
+
+def list_released_versions(
+    ptex: Ptex,
+    github_api_bearer_token: str | None,
+    bootstrap_urls_path: str | None,
+) -> list[Version]:
+    ptex_urls = get_bootstrap_urls(bootstrap_urls_path)
+    if ptex_urls is not None:
+        # N.B.: When the Pants PEX URLs are re-directed, the releases available are the ones with
+        # a Pants PEX for the current platform.
+        uname = os.uname()
+        platform = f"{uname.sysname.lower()}_{uname.machine.lower()}"
+        pex_names = (PANTS_PEX_NAME_RE.match(key) for key in ptex_urls)
+        return parse_versions(
+            match.group("version")
+            for match in pex_names
+            if match and match.group("platform") == platform
+        )
+
+    headers = (
+        {"Authorization": f"Bearer {github_api_bearer_token}"} if github_api_bearer_token else {}
+    )
+    refs = ptex.fetch_json(
+        "https://api.github.com/repos/pantsbuild/pants/git/matching-refs/tags/release_", **headers
+    )
+    return parse_versions(ref["ref"][len("refs/tags/release_") :] for ref in refs)
+
+
+def parse_versions(raw_versions: Iterator[str]) -> list[Version]:
+    versions = []
+    for raw_version in raw_versions:
+        try:
+            versions.append(Version(raw_version))
+        except InvalidVersion:
+            debug(f"Ignoring a release with an invalid version: {raw_version}")
+    return versions
+
+
 def determine_pex_url_and_python_id(
     ptex: Ptex,
     version: Version,
     bootstrap_urls_path: str | None,
-) -> tuple[str, str]:
+) -> tuple[str, str, str | None]:
     uname = os.uname()
     platform = f"{uname.sysname.lower()}_{uname.machine.lower()}"
     pex_url, python = get_pex_url_and_python_id(ptex, version, platform, bootstrap_urls_path)
     if python not in PYTHON_IDS:
         # Should not happen... but if we mess up, this is a nicer error message rather than blowing up.
         fatal(f"This version of scie-pants does not support {python!r}.")
-    return pex_url, PYTHON_IDS[python]
+
+    pex_sha256 = None
+    digests = get_bootstrap_digests(bootstrap_urls_path)
+    if digests is not None:
+        pex_name = pants_pex_name(version, python, platform)
+        pex_sha256 = digests.get(pex_name)
+        if not pex_sha256:
+            fatal(
+                f"{pex_name}: has no digest under the 'sha256' key in PANTS_BOOTSTRAP_URLS file: "
+                f"{bootstrap_urls_path}. When digests are listed, every Pants PEX fetched must "
+                "have one."
+            )
+    return pex_url, PYTHON_IDS[python], pex_sha256
 
 
 def get_bootstrap_urls(bootstrap_urls_path: str | None) -> dict[str, str] | None:
@@ -227,7 +342,73 @@ def get_bootstrap_urls(bootstrap_urls_path: str | None) -> dict[str, str] | None
                 f"The value for the key '{key}' in PANTS_BOOTSTRAP_URLS file: '{bootstrap_urls_path}' "
                 f"under the 'ptex' key was expected to be a string. Got a {type(url).__name__}"
             )
-    return ptex_urls
+    # N.B.: Only the Pants PEX keys are fetched by us; the rest (e.g.: the interpreter
+    # distributions) are fetched by the ptex binary itself; so we pass those through untouched.
+    return {
+        key: (
+            normalize_bootstrap_url(key, url, bootstrap_urls_path)
+            if PANTS_PEX_NAME_RE.match(key)
+            else url
+        )
+        for key, url in ptex_urls.items()
+    }
+
+
+def get_bootstrap_digests(bootstrap_urls_path: str | None) -> dict[str, str] | None:
+    """Returns the expected sha256 digests of artifacts from a PANTS_BOOTSTRAP_URLS file, if any.
+
+    The digests live under a top-level 'sha256' key, beside the 'ptex' key, since the ptex binary
+    reads the 'ptex' URLs too and only understands string values.
+    """
+    if not bootstrap_urls_path:
+        return None
+
+    bootstrap_urls = json.loads(Path(bootstrap_urls_path).read_text())
+    digests = bootstrap_urls.get("sha256")
+    if digests is None:
+        return None
+    if not isinstance(digests, dict):
+        raise TypeError(
+            f"The 'sha256' key in PANTS_BOOTSTRAP_URLS file: '{bootstrap_urls_path}' was expected "
+            f"to map file names to sha256 digests. Got a {type(digests).__name__}"
+        )
+    for key, digest in digests.items():
+        if not isinstance(digest, str) or not SHA256_RE.match(digest):
+            raise ValueError(
+                f"The value for the key '{key}' in PANTS_BOOTSTRAP_URLS file: "
+                f"'{bootstrap_urls_path}' under the 'sha256' key was expected to be a hex sha256 "
+                f"digest. Got: {digest!r}"
+            )
+    return {key: digest.lower() for key, digest in digests.items()}
+
+
+def normalize_bootstrap_url(key: str, url: str, bootstrap_urls_path: str) -> str:
+    """Normalizes a PANTS_BOOTSTRAP_URLS value into a URL for the Pants PEX named by `key`.
+
+    Bare absolute paths are converted to `file://` URLs and values ending in a `/` are treated as
+    a base URL (mirror directory) the artifact file name is appended to.
+    """
+    if os.path.isabs(url):
+        # N.B.: `Path.as_uri` strips any trailing slash; so we restore it to preserve the base URL
+        # semantics below.
+        is_base = url.endswith(("/", os.sep))
+        url = Path(url).as_uri()
+        if is_base:
+            url = f"{url}/"
+
+    parsed = urllib.parse.urlparse(url)
+    if parsed.scheme not in ("file", "http", "https") or (
+        parsed.scheme != "file" and not parsed.netloc
+    ):
+        raise ValueError(
+            f"The value for the key '{key}' in PANTS_BOOTSTRAP_URLS file: '{bootstrap_urls_path}' "
+            f"under the 'ptex' key is not a valid URL or absolute path: {url!r}. Expected an "
+            f"http://, https:// or file:// URL or an absolute local path."
+        )
+
+    if url.endswith("/"):
+        url = f"{url.rstrip('/')}/{urllib.parse.quote(key)}"
+    return url
 
 
 def get_pex_url_and_python_id(
@@ -270,10 +451,83 @@ def get_python_id_for_pants_version(version: Version) -> str | None:
     return None
 
 
+INTERPRETER_CONSTRAINT_RE = re.compile(r"^\s*(?P<name>[A-Za-z]+)?\s*(?P<specifier>.*)$")
+
+
+def satisfies_interpreter_constraints(python: str, interpreter_constraints: list[str]) -> bool:
+    """Returns True if the given lift interpreter id satisfies any of the constraints.
+
+    Constraints are Pants `[python] interpreter_constraints` entries; e.g.: `CPython>=3.8,<3.10`.
+    Only the major and minor version of the interpreter are considered.
+    """
+    python_id = next(
+        (python_id for python_id, lift_id in PYTHON_IDS.items() if lift_id == python), None
+    )
+    if not python_id:
+        return False
+    version = Version(f"{python_id[2]}.{python_id[3:]}")
+    for constraint in interpreter_constraints:
+        match = INTERPRETER_CONSTRAINT_RE.match(constraint)
+        if not match:
+            continue
+        name = match.group("name")
+        if name and name.lower() != "cpython":
+            continue
+        try:
+            specifier = SpecifierSet(match.group("specifier"))
+        except ValueError:
+            warn(f"Ignoring malformed interpreter constraint: {constraint!r}")
+            continue
+        if specifier.contains(version, prereleases=True):
+            return True
+    return False
+
+
+def select_python(resolve_info: ResolveInfo, interpreter_constraints: list[str]) -> ResolveInfo:
+    """Selects the interpreter to install Pants with per the repo's interpreter constraints.
+
+    Only Pants 1.x, installed from wheels that work with any interpreter it supports, can use an
+    interpreter other than the default. Newer Pants is either a PEX or, when installed by commit
+    sha, wheels built for a single Python.
+    """
+    if not interpreter_constraints or satisfies_interpreter_constraints(
+        resolve_info.python, interpreter_constraints
+    ):
+        return resolve_info
+    if resolve_info.version >= PANTS_PEX_GITHUB_RELEASE_VERSION:
+        warn(
+            f"Pants {resolve_info.version} requires {resolve_info.python} which does not satisfy "
+            f"the `[python] interpreter_constraints` of {interpreter_constraints}; using it anyway."
+        )
+        return resolve_info
+    allowed = [
+        python
+        for python in PANTS_1_PYTHONS
+        if satisfies_interpreter_constraints(python, interpreter_constraints)
+    ]
+    if not allowed:
+        warn(
+            f"None of the interpreters Pants {resolve_info.version} supports ("
+            f"{', '.join(PANTS_1_PYTHONS)}) satisfy the `[python] interpreter_constraints` of "
+            f"{interpreter_constraints}; using {resolve_info.python}."
+        )
+        return resolve_info
+    debug(
+        f"Using {allowed[0]} instead of {resolve_info.python} to install Pants "
+        f"{resolve_info.version} per the `[python] interpreter_constraints` of "
+        f"{interpreter_constraints}."
+    )
+    return dataclasses.replace(resolve_info, python=allowed[0])
+
+
+def pants_pex_name(version: Version, python: str, platform: str) -> str:
+    return f"pants.{version}-{python}-{platform}.pex"
+
+
 def get_download_url(
     version: Version, platform: str, python: str, ptex_urls: dict[str, str] | None
 ) -> tuple[str, None] | tuple[None, str]:
-    pex_name = f"pants.{version}-{python}-{platform}.pex"
+    pex_name = pants_pex_name(version, python, platform)
     if ptex_urls:
         pex_url = ptex_urls.get(pex_name)
         if not pex_url:
@@ -282,9 +536,14 @@ def get_download_url(
         pex_url = (
             f"https://github.com/pantsbuild/pants/releases/download/release_{version}/{pex_name}"
         )
+    check_online(pex_url)
     req = urllib.request.Request(pex_url, method="HEAD")
     try:
-        with urllib.request.urlopen(req, timeout=TIMEOUT) as rsp:
+        with with_retries(
+            f"checking {pex_url}",
+            lambda: urllib.request.urlopen(req, timeout=TIMEOUT),
+            is_transient_url_failure,
+        ) as rsp:
             if rsp.status == 200:
                 return pex_url, None
             elif (
@@ -302,3 +561,10 @@ def get_download_url(
             return None, f"{pex_name}: URL check failed, from PANTS_BOOTSTRAP_URLS: {pex_url}: {e}"
         else:
             return None, f"{pex_name}: URL check failed: {pex_url}: {e}"
+
+
+def is_transient_url_failure(e: Exception) -> bool:
+    if isinstance(e, urllib.error.HTTPError):
+        return e.code >= 500 or e.code in (408, 429)
+    reason = e.reason if isinstance(e, urllib.error.URLError) else e
+    return isinstance(reason, (ConnectionError, TimeoutError, socket.timeout, socket.gaierror))
@@ -5,11 +5,102 @@ from __future__ import annotations
 
 import argparse
 import json
+import os
+import re
 import subprocess
+import sys
+import time
+import urllib.request
 from argparse import ArgumentParser, Namespace
 from dataclasses import dataclass
-from subprocess import CompletedProcess
-from typing import IO, Any, Callable, cast
+from subprocess import CalledProcessError, CompletedProcess
+from typing import IO, Any, Callable, TypeVar, cast
+from urllib.parse import urlparse
+
+from scie_pants import deadline
+from scie_pants.log import debug, fatal, warn
+
+DEFAULT_DOWNLOAD_RETRIES = 3
+# The delay before the 1st retry in seconds; this doubles for each subsequent retry.
+INITIAL_RETRY_DELAY = 1.0
+MAX_RETRY_DELAY = 30.0
+
+_T = TypeVar("_T")
+
+
+def download_retries() -> int:
+    # N.B.: The scie-pants launcher validates this env var and always exports it; so we only need
+    # to handle it being absent when the tools are run directly.
+    retries = os.environ.get("SCIE_PANTS_DOWNLOAD_RETRIES")
+    return int(retries) if retries else DEFAULT_DOWNLOAD_RETRIES
+
+
+def check_online(url: str) -> None:
+    """Exits with an error naming `url` if it's remote and `SCIE_PANTS_OFFLINE=1` is set.
+
+    Local `file://` URLs, like those of a `PANTS_BOOTSTRAP_URLS` mirror on disk, are always allowed.
+    """
+    if os.environ.get("SCIE_PANTS_OFFLINE", "").strip() != "1":
+        return
+    if urlparse(url).scheme == "file":
+        return
+    fatal(f"SCIE_PANTS_OFFLINE=1 is set; so refusing to download {url}")
+
+
+def with_retries(
+    description: str, attempt: Callable[[], _T], is_transient: Callable[[Exception], bool]
+) -> _T:
+    """Calls `attempt`, retrying transient failures with exponential backoff.
+
+    The last failure is re-raised once the retries configured via `SCIE_PANTS_DOWNLOAD_RETRIES`
+    are exhausted or as soon as a failure is not transient.
+    """
+    retries = download_retries()
+    delay = INITIAL_RETRY_DELAY
+    for attempt_number in range(1, retries + 2):
+        try:
+            return attempt()
+        except Exception as e:
+            if not is_transient(e):
+                raise
+            if attempt_number > retries:
+                warn(f"Giving up on {description} after {attempt_number} attempts.")
+                raise
+            remaining = deadline.remaining_time(description)
+            if remaining is not None and remaining <= delay:
+                warn(f"Attempt {attempt_number} of {retries + 1} at {description} failed: {e}")
+                deadline.timed_out(description)
+            warn(
+                f"Attempt {attempt_number} of {retries + 1} at {description} failed, retrying in "
+                f"{delay:.1f}s: {e}"
+            )
+            time.sleep(delay)
+            delay = min(delay * 2, MAX_RETRY_DELAY)
+    raise AssertionError("Unreachable.")
+
+
+# N.B.: The ptex binary reports HTTP errors as, for example: `HTTP status client error (404 Not
+# Found) for url (...)`. Client errors won't be fixed by retrying, save for request timeouts and
+# rate limiting.
+_PERMANENT_FAILURE_RE = re.compile(
+    r"HTTP status client error \((?!408|429)\d{3}|No such file or directory"
+)
+
+
+def _is_transient_fetch_failure(e: Exception) -> bool:
+    if not isinstance(e, CalledProcessError):
+        return False
+    stderr = e.stderr.decode(errors="replace") if e.stderr else ""
+    return _PERMANENT_FAILURE_RE.search(stderr) is None
+
+
+def proxy_for(url: str) -> str | None:
+    """Returns the proxy configured via `HTTPS_PROXY`, `HTTP_PROXY` and `NO_PROXY` for `url`."""
+    parsed_url = urlparse(url)
+    proxy = urllib.request.getproxies_environment().get(parsed_url.scheme)
+    if not proxy or urllib.request.proxy_bypass_environment(parsed_url.hostname or ""):
+        return None
+    return proxy
 
 
 @dataclass(frozen=True)
@@ -32,12 +123,33 @@ class Ptex:
 
     _exe: str
 
-    def _fetch(self, url: str, stdout: int, **headers: str) -> CompletedProcess:
+    def _fetch(
+        self, url: str, stdout: int, reset: Callable[[], None] | None = None, **headers: str
+    ) -> CompletedProcess:
+        check_online(url)
         args = [self._exe]
         for header, value in headers.items():
             args.extend(("-H", f"{header}: {value}"))
         args.append(url)
-        return subprocess.run(args=args, stdout=stdout, check=True)
+
+        def attempt() -> CompletedProcess:
+            if reset:
+                reset()
+            result = deadline.run(f"fetching {url}", args, stdout=stdout, stderr=subprocess.PIPE)
+            if result.returncode != 0:
+                sys.stderr.buffer.write(result.stderr)
+                sys.stderr.flush()
+                proxy = proxy_for(url)
+                if proxy:
+                    warn(f"Failed to fetch {url} via the proxy {proxy}.")
+                raise CalledProcessError(
+                    result.returncode, args, output=result.stdout, stderr=result.stderr
+                )
+            if result.stderr:
+                debug(result.stderr.decode(errors="replace"))
+            return result
+
+        return with_retries(f"fetching {url}", attempt, _is_transient_fetch_failure)
 
     def fetch_json(self, url: str, **headers: str) -> Any:
         return json.loads(self._fetch(url, stdout=subprocess.PIPE, **headers).stdout)
@@ -46,4 +158,11 @@ class Ptex:
         return self._fetch(url, stdout=subprocess.PIPE, **headers).stdout.decode()
 
     def fetch_to_fp(self, url: str, fp: IO[bytes], **headers: str) -> None:
-        self._fetch(url, stdout=fp.fileno(), **headers)
+        start = fp.tell()
+
+        def reset() -> None:
+            # N.B.: We discard any partial content from a failed attempt before the next one.
+            fp.seek(start)
+            fp.truncate()
+
+        self._fetch(url, stdout=fp.fileno(), reset=reset, **headers)
@@ -22,7 +22,7 @@ from pathlib import Path, PurePath
 from subprocess import CalledProcessError
 from typing import Any, Dict, List, NoReturn, cast
 
-from packaging.version import Version
+from packaging.version import InvalidVersion, Version
 
 from scie_pants.log import fatal, info, init_logging, warn
 from scie_pants.ptex import Ptex
@@ -31,6 +31,10 @@ log = logging.getLogger(__name__)
 
 
 RELEASE_TAG_MATCHER = re.compile(r"^v(?P<version>\d+\.\d+\.\d+)$")
+# N.B.: Pre-release tags look like `v0.13.0rc1` or `v0.13.0-rc.1`; we let `Version` normalize these.
+PRE_RELEASE_TAG_MATCHER = re.compile(r"^v(?P<version>\d+\.\d+\.\d+[-.]?[a-z]+\.?\d*)$")
+
+CHANNELS = ("stable", "edge")
 EXE_EXTENSION = sysconfig.get_config_var("EXE") or ""
 
 GITHUB_API_BASE_URL = "https://api.github.com/repos/pantsbuild/scie-pants"
@@ -43,6 +47,7 @@ class Release:
     file_name: str
     binary_url: str
     binary_sha256_url: str
+    binary_sig_url: str | None = None
 
     @classmethod
     def from_api_response(
@@ -50,16 +55,20 @@ class Release:
     ) -> Release | None:
         binary_name = f"{BINARY_NAME}-{platform}{EXE_EXTENSION}"
         binary_sha256_name = f"{binary_name}.sha256"
+        binary_sig_name = f"{binary_name}.sig"
         binary_url = None
         binary_sha256_url = None
+        binary_sig_url = None
         for asset in release_data.get("assets", []):
             name = asset.get("name")
             if binary_name == name:
                 binary_url = asset.get("browser_download_url")
             elif binary_sha256_name == name:
                 binary_sha256_url = asset.get("browser_download_url")
-            if binary_url and binary_sha256_url:
-                return cls(version, binary_name, binary_url, binary_sha256_url)
+            elif binary_sig_name == name:
+                binary_sig_url = asset.get("browser_download_url")
+        if binary_url and binary_sha256_url:
+            return cls(version, binary_name, binary_url, binary_sha256_url, binary_sig_url)
         log.debug(
             f"No release for {BINARY_NAME} {version} compatible with {platform} was found in: "
             f"{json.dumps(release_data, indent=2)}"
@@ -98,8 +107,36 @@ def get_release(
     return release
 
 
-def find_latest_production_release(
-    ptex: Ptex, platform: str, github_api_bearer_token: str | None = None
+def release_version(release_data: dict[str, Any], channel: str = "stable") -> Version | None:
+    """Returns the version of the given GitHub release if it is on the given update channel.
+
+    The `stable` channel only has production releases; the `edge` channel adds pre-releases.
+    """
+    if release_data.get("draft"):
+        return None
+    if release_data.get("prerelease") and channel != "edge":
+        return None
+    tag_name = release_data.get("tag_name")
+    if not tag_name:
+        return None
+    match = RELEASE_TAG_MATCHER.match(tag_name)
+    if not match and channel == "edge":
+        match = PRE_RELEASE_TAG_MATCHER.match(tag_name)
+    if not match:
+        log.debug(f"Skipping tag {tag_name} since it is not a {channel} channel release tag.")
+        return None
+    try:
+        version = Version(match["version"])
+    except InvalidVersion:
+        log.debug(f"Skipping tag {tag_name} since it does not name a valid version.")
+        return None
+    if version.is_prerelease and channel != "edge":
+        return None
+    return version
+
+
+def find_latest_release(
+    ptex: Ptex, platform: str, channel: str = "stable", github_api_bearer_token: str | None = None
 ) -> Release | None:
     releases = cast(
         list[dict[str, Any]],
@@ -108,25 +145,16 @@ def find_latest_production_release(
 
     latest_releases = []
     for release_data in releases:
-        if release_data.get("draft") or release_data.get("prerelease"):
-            continue
-        tag_name = release_data.get("tag_name")
-        if not tag_name:
-            continue
-        match = RELEASE_TAG_MATCHER.match(tag_name)
-        if not match:
-            log.debug(
-                f"Skipping tag {tag_name} since it does not match {RELEASE_TAG_MATCHER.pattern}"
-            )
+        version = release_version(release_data, channel=channel)
+        if not version:
             continue
-        version = Version(match["version"])
         release = Release.from_api_response(version, platform, release_data)
         if release:
             latest_releases.append(release)
 
     if not latest_releases:
         log.debug(
-            f"No releases for {BINARY_NAME} compatible with {platform} found in: "
+            f"No {channel} channel releases for {BINARY_NAME} compatible with {platform} found in: "
             f"{json.dumps(releases, indent=2)}"
         )
         return None
@@ -165,6 +193,8 @@ def install_release(ptex: Ptex, release: Release, scie: Path) -> Path:
             f"  {actual_sha256}",
         )
 
+    verify_signature(ptex, release, binary, scie)
+
     # Mark the binary as executable. This is needed on Unix but not on Windows, where its harmless.
     binary.chmod(0o755)
 
@@ -179,6 +209,32 @@ def install_release(ptex: Ptex, release: Release, scie: Path) -> Path:
     return backup
 
 
+def verify_signature(ptex: Ptex, release: Release, binary: Path, scie: Path) -> None:
+    """Has the current scie check the detached signature of the downloaded binary.
+
+    The current scie embeds the public key to check against; so this raises if the signature is
+    missing or does not match unless the current scie was built without one.
+    """
+    signature = binary.with_name(f"{binary.name}.sig")
+    if release.binary_sig_url:
+        with open(signature, "wb") as fp:
+            ptex.fetch_to_fp(release.binary_sig_url, fp)
+    else:
+        # N.B.: An empty signature never verifies; so this only succeeds if the current scie has no
+        # public key to check against.
+        signature.touch()
+    result = subprocess.run(
+        args=[str(scie), str(binary), str(signature)],
+        env={**os.environ, "SCIE_BOOT": "verify-update"},
+    )
+    if result.returncode != 0:
+        missing = "" if release.binary_sig_url else " (the release has no signature)"
+        raise ValueError(
+            f"The binary downloaded from {release.binary_url} failed signature verification"
+            f"{missing}; refusing to install it."
+        )
+
+
 def verify_release(scie: PurePath) -> str:
     return (
         subprocess.run(
@@ -227,6 +283,12 @@ def main() -> NoReturn:
         # The GITHUB_TOKEN to use if running in CI context.
         help=argparse.SUPPRESS,
     )
+    parser.add_argument(
+        "--channel",
+        default="stable",
+        # The SCIE_PANTS_UPDATE_CHANNEL to find the latest version in.
+        help=argparse.SUPPRESS,
+    )
     parser.add_argument(
         "version",
         nargs="?",
@@ -240,6 +302,13 @@ def main() -> NoReturn:
     # or uncaught exceptions below here are clean ways to exit non-zero with useful console output.
     init_logging(base_dir=options.base_dir, log_name="update")
 
+    channel = options.channel.strip() or "stable"
+    if channel not in CHANNELS:
+        fatal(
+            f"SCIE_PANTS_UPDATE_CHANNEL must be one of {' or '.join(map(repr, CHANNELS))}, got: "
+            f"{channel!r}."
+        )
+
     ptex = get_ptex(options)
     if options.version is not None:
         try:
@@ -252,11 +321,14 @@ def main() -> NoReturn:
         except ReleaseNotFoundError as e:
             fatal(f"Failed to find {BINARY_NAME} release for version {options.version}: {e}")
     else:
-        maybe_release = find_latest_production_release(
-            ptex, platform=options.platform, github_api_bearer_token=options.github_api_bearer_token
+        maybe_release = find_latest_release(
+            ptex,
+            platform=options.platform,
+            channel=channel,
+            github_api_bearer_token=options.github_api_bearer_token,
         )
         if not maybe_release or maybe_release.version <= options.current_version:
-            info(f"No new releases of {BINARY_NAME} were found.")
+            info(f"No new {channel} channel releases of {BINARY_NAME} were found.")
             sys.exit(0)
         release = maybe_release
 
@@ -0,0 +1,25 @@
+# Copyright 2024 Pants project contributors.
+# Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+from __future__ import annotations
+
+import hashlib
+from pathlib import Path
+
+import pytest
+
+from scie_pants.install_pants import verify_sha256
+
+
+def test_verify_sha256(tmp_path: Path) -> None:
+    pex = tmp_path / "pants.pex"
+    pex.write_bytes(b"PEX")
+    actual = hashlib.sha256(b"PEX").hexdigest()
+    verify_sha256(pex, "https://example.com/pants.pex", actual)
+
+    expected = "0" * 64
+    with pytest.raises(SystemExit) as exc_info:
+        verify_sha256(pex, "https://example.com/pants.pex", expected)
+    message = str(exc_info.value)
+    assert f"Expected sha256: {expected}" in message
+    assert f"Actual sha256:   {actual}" in message
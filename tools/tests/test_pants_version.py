@@ -0,0 +1,236 @@
+# Copyright 2024 Pants project contributors.
+# Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+from __future__ import annotations
+
+import json
+import os
+import threading
+from http.server import BaseHTTPRequestHandler, HTTPServer
+from pathlib import Path
+from typing import Any, cast
+
+import pytest
+from packaging.version import Version
+
+from scie_pants import pants_version, ptex
+from scie_pants.pants_version import (
+    ResolveInfo,
+    determine_sha_version,
+    determine_version_in_range,
+    get_bootstrap_digests,
+    get_bootstrap_urls,
+    get_download_url,
+    list_released_versions,
+    select_python,
+)
+from scie_pants.ptex import Ptex
+
+PEX_NAME = "pants.2.18.0-cp39-linux_x86_64.pex"
+
+
+def bootstrap_urls(tmp_path: Path, url: str) -> dict[str, str] | None:
+    urls_json = tmp_path / "urls.json"
+    urls_json.write_text(json.dumps({"ptex": {PEX_NAME: url}}))
+    return get_bootstrap_urls(str(urls_json))
+
+
+def test_bare_path(tmp_path: Path) -> None:
+    pex = tmp_path / "mirror" / PEX_NAME
+    assert {PEX_NAME: pex.as_uri()} == bootstrap_urls(tmp_path, str(pex))
+
+
+def test_file_uri(tmp_path: Path) -> None:
+    pex_uri = (tmp_path / "mirror" / PEX_NAME).as_uri()
+    assert {PEX_NAME: pex_uri} == bootstrap_urls(tmp_path, pex_uri)
+
+
+def test_trailing_slash_base(tmp_path: Path) -> None:
+    mirror = tmp_path / "mirror"
+    expected = {PEX_NAME: (mirror / PEX_NAME).as_uri()}
+    assert expected == bootstrap_urls(tmp_path, f"{mirror.as_uri()}/")
+    assert expected == bootstrap_urls(tmp_path, f"{mirror}/")
+    assert {PEX_NAME: f"https://example.com/mirror/{PEX_NAME}"} == bootstrap_urls(
+        tmp_path, "https://example.com/mirror//"
+    )
+
+
+@pytest.mark.parametrize("url", ["mirror/", "ftp://example.com/mirror/", "https:///mirror/"])
+def test_malformed(tmp_path: Path, url: str) -> None:
+    with pytest.raises(ValueError, match="is not a valid URL or absolute path"):
+        bootstrap_urls(tmp_path, url)
+
+
+def test_non_pex_keys_untouched(tmp_path: Path) -> None:
+    urls_json = tmp_path / "urls.json"
+    ptex_urls = {
+        "cpython-3.9.16+20230507-x86_64-unknown-linux-gnu-install_only.tar.gz": "mirror/",
+        PEX_NAME: "https://example.com/mirror/",
+    }
+    urls_json.write_text(json.dumps({"ptex": ptex_urls}))
+    assert {
+        **ptex_urls,
+        PEX_NAME: f"https://example.com/mirror/{PEX_NAME}",
+    } == get_bootstrap_urls(str(urls_json))
+
+
+def test_bootstrap_digests(tmp_path: Path) -> None:
+    urls_json = tmp_path / "urls.json"
+    urls_json.write_text(json.dumps({"ptex": {PEX_NAME: "https://example.com/"}}))
+    assert get_bootstrap_digests(str(urls_json)) is None
+
+    digest = "AB" * 32
+    urls_json.write_text(json.dumps({"ptex": {}, "sha256": {PEX_NAME: digest}}))
+    assert {PEX_NAME: digest.lower()} == get_bootstrap_digests(str(urls_json))
+
+    urls_json.write_text(json.dumps({"ptex": {}, "sha256": {PEX_NAME: "abc"}}))
+    with pytest.raises(ValueError, match="was expected to be a hex sha256 digest"):
+        get_bootstrap_digests(str(urls_json))
+
+
+def released_versions(tmp_path: Path, *pex_names: str) -> list[str]:
+    urls_json = tmp_path / "urls.json"
+    ptex_urls = {pex_name: f"https://example.com/{pex_name}" for pex_name in pex_names}
+    urls_json.write_text(json.dumps({"ptex": ptex_urls}))
+    ptex = cast(Ptex, None)
+    versions = list_released_versions(ptex, None, str(urls_json))
+    return sorted(str(version) for version in versions)
+
+
+def test_list_released_versions_bootstrap_urls(tmp_path: Path) -> None:
+    uname = os.uname()
+    platform = f"{uname.sysname.lower()}_{uname.machine.lower()}"
+    assert ["2.18.0", "2.19.0rc1", "2.25.0"] == released_versions(
+        tmp_path,
+        f"pants.2.18.0-cp39-{platform}.pex",
+        f"pants.2.19.0rc1-cp39-{platform}.pex",
+        f"pants.2.25.0-cp311-{platform}.pex",
+        f"pants.2.26.0-cp311-plan9_mips.pex",
+        f"pants.bogus-cp311-{platform}.pex",
+        "cpython-3.9.16+20230507-x86_64-unknown-linux-gnu-install_only.tar.gz",
+    )
+
+
+@pytest.mark.parametrize(
+    "specifier, expected",
+    [
+        (">=2.18.0,<=2.19.0", "2.19.0"),
+        (">=2.18.0,<2.19.0", "2.18.1"),
+        (">=2.19.0", "2.20.0"),
+        (">=2.20.0rc0", "2.20.1rc1"),
+    ],
+)
+def test_determine_version_in_range(
+    monkeypatch: pytest.MonkeyPatch, tmp_path: Path, specifier: str, expected: str
+) -> None:
+    released = ["2.17.0", "2.18.0", "2.18.1", "2.19.0", "2.20.0", "2.20.1rc1"]
+    monkeypatch.setattr(
+        pants_version,
+        "list_released_versions",
+        lambda *_args: [Version(version) for version in released],
+    )
+    monkeypatch.setattr(
+        pants_version,
+        "determine_tag_version",
+        lambda _ptex, version, *_args: ResolveInfo(version=Version(version), python="cpython39"),
+    )
+    ptex = cast(Ptex, None)
+    resolve_info = determine_version_in_range(ptex, specifier, tmp_path, None, None)
+    assert Version(expected) == resolve_info.version
+
+
+def test_get_download_url_retries(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setattr(ptex, "INITIAL_RETRY_DELAY", 0.0)
+    monkeypatch.delenv("SCIE_PANTS_DOWNLOAD_RETRIES", raising=False)
+
+    requests: list[str] = []
+
+    class FlakyHandler(BaseHTTPRequestHandler):
+        def do_HEAD(self) -> None:
+            requests.append(self.path)
+            # N.B.: The 1st two requests fail with a transient server error.
+            self.send_response(200 if len(requests) > 2 else 503)
+            self.end_headers()
+
+        def log_message(self, *_args: Any) -> None:
+            pass
+
+    server = HTTPServer(("127.0.0.1", 0), FlakyHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    try:
+        pex_url = f"http://127.0.0.1:{server.server_port}/{PEX_NAME}"
+        assert (pex_url, None) == get_download_url(
+            Version("2.18.0"), "linux_x86_64", "cp39", {PEX_NAME: pex_url}
+        )
+        assert [f"/{PEX_NAME}"] * 3 == requests
+    finally:
+        server.shutdown()
+
+
+def test_determine_sha_version() -> None:
+    sha = "1a2b3c4d5e6f1a2b3c4d5e6f1a2b3c4d5e6f1a2b"
+    fetched: list[str] = []
+
+    class FakePtex:
+        def fetch_text(self, url: str) -> str:
+            fetched.append(url)
+            return "2.21.0.dev6\n"
+
+    resolve_info = determine_sha_version(cast(Ptex, FakePtex()), sha)
+    assert [
+        f"https://raw.githubusercontent.com/pantsbuild/pants/{sha}/src/python/pants/VERSION"
+    ] == fetched
+    assert (
+        ResolveInfo(
+            version=Version("2.21.0.dev6+git1a2b3c4d"),
+            python="cpython39",
+            find_links=(
+                f"https://binaries.pantsbuild.org/wheels/pantsbuild.pants/{sha}/"
+                "2.21.0.dev6%2Bgit1a2b3c4d/index.html"
+            ),
+        )
+        == resolve_info
+    )
+
+
+@pytest.mark.parametrize(
+    "constraints, expected",
+    [
+        ([], "cpython38"),
+        (["CPython==3.8.*"], "cpython38"),
+        (["CPython>=3.9,<3.11"], "cpython38"),
+        (["PyPy>=3.8", ">=3.10"], "cpython38"),
+        (["CPython<3.8"], "cpython38"),
+    ],
+)
+def test_select_python_pants_1(constraints: list[str], expected: str) -> None:
+    resolve_info = ResolveInfo(
+        version=Version("1.30.5rc1"), python="cpython38", find_links="file:///find-links"
+    )
+    assert expected == select_python(resolve_info, constraints).python
+
+
+def test_select_python_pants_1_supported(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setattr(pants_version, "PANTS_1_PYTHONS", ["cpython38", "cpython39"])
+    resolve_info = ResolveInfo(
+        version=Version("1.30.5rc1"), python="cpython38", find_links="file:///find-links"
+    )
+    assert "cpython39" == select_python(resolve_info, ["CPython>=3.9"]).python
+    assert "cpython38" == select_python(resolve_info, ["CPython>=3.10"]).python
+
+
+def test_select_python_pex() -> None:
+    resolve_info = ResolveInfo(
+        version=Version("2.18.0"), python="cpython39", pex_url=f"https://example.com/{PEX_NAME}"
+    )
+    assert "cpython39" == select_python(resolve_info, ["CPython>=3.11"]).python
+
+
+def test_select_python_sha_wheels() -> None:
+    resolve_info = ResolveInfo(
+        version=Version("2.25.0.dev1+gitabcdef12"),
+        python="cpython39",
+        find_links="file:///find-links",
+    )
+    assert "cpython39" == select_python(resolve_info, ["CPython==3.11.*"]).python
@@ -0,0 +1,140 @@
+# Copyright 2024 Pants project contributors.
+# Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+from __future__ import annotations
+
+import sys
+import time
+from pathlib import Path
+from subprocess import CalledProcessError
+from typing import Callable
+
+import pytest
+
+from scie_pants import ptex
+from scie_pants.ptex import Ptex, proxy_for, with_retries
+
+
+@pytest.fixture(autouse=True)
+def no_retry_delay(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setattr(ptex, "INITIAL_RETRY_DELAY", 0.0)
+
+
+def flaky(failures: int, error: Exception) -> tuple[list[int], Callable[[], str]]:
+    attempts: list[int] = []
+
+    def attempt() -> str:
+        attempts.append(len(attempts) + 1)
+        if len(attempts) <= failures:
+            raise error
+        return "success"
+
+    return attempts, attempt
+
+
+def test_with_retries_succeeds_after_transient_failures(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.delenv("SCIE_PANTS_DOWNLOAD_RETRIES", raising=False)
+    attempts, attempt = flaky(2, ConnectionResetError())
+    assert "success" == with_retries("testing", attempt, lambda _: True)
+    assert [1, 2, 3] == attempts
+
+
+def test_with_retries_gives_up(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("SCIE_PANTS_DOWNLOAD_RETRIES", "1")
+    attempts, attempt = flaky(2, ConnectionResetError())
+    with pytest.raises(ConnectionResetError):
+        with_retries("testing", attempt, lambda _: True)
+    assert [1, 2] == attempts
+
+
+def test_with_retries_permanent_failure(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.delenv("SCIE_PANTS_DOWNLOAD_RETRIES", raising=False)
+    attempts, attempt = flaky(2, ValueError())
+    with pytest.raises(ValueError):
+        with_retries("testing", attempt, lambda _: False)
+    assert [1] == attempts
+
+
+def test_with_retries_bootstrap_deadline(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.delenv("SCIE_PANTS_DOWNLOAD_RETRIES", raising=False)
+    monkeypatch.setenv("SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS", "1")
+    monkeypatch.setenv("SCIE_PANTS_BOOTSTRAP_DEADLINE", str(time.time() - 1))
+    attempts, attempt = flaky(2, ConnectionResetError())
+    with pytest.raises(SystemExit, match="SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS=1 seconds"):
+        with_retries("testing", attempt, lambda _: True)
+    assert [1] == attempts
+
+
+def test_fetch_bootstrap_deadline(monkeypatch: pytest.MonkeyPatch, tmp_path: Path) -> None:
+    stalled_ptex = tmp_path / "ptex"
+    stalled_ptex.write_text(f"#!{sys.executable}\nimport time\ntime.sleep(60)\n")
+    stalled_ptex.chmod(0o755)
+    monkeypatch.setenv("SCIE_PANTS_BOOTSTRAP_TIMEOUT_SECS", "1")
+    monkeypatch.setenv("SCIE_PANTS_BOOTSTRAP_DEADLINE", str(time.time() + 1))
+
+    start = time.time()
+    with pytest.raises(SystemExit, match="Timed out fetching https://example.com/pants.pex"):
+        Ptex(str(stalled_ptex)).fetch_text("https://example.com/pants.pex")
+    assert time.time() - start < 30
+
+
+def test_fetch_retries_transient_failures(monkeypatch: pytest.MonkeyPatch, tmp_path: Path) -> None:
+    # N.B.: We stand in for the ptex binary with a script that fails until its 3rd invocation.
+    counter = tmp_path / "counter"
+    fake_ptex = tmp_path / "ptex"
+    fake_ptex.write_text(
+        f"""\
+#!{sys.executable}
+import sys
+from pathlib import Path
+
+counter = Path({str(counter)!r})
+count = int(counter.read_text()) + 1 if counter.exists() else 1
+counter.write_text(str(count))
+url = sys.argv[-1]
+if count < 3:
+    sys.stdout.write("partial")
+    sys.exit(f"HTTP status server error (503 Service Unavailable) for url ({{url}})")
+if url.endswith("/missing"):
+    sys.exit(f"HTTP status client error (404 Not Found) for url ({{url}})")
+sys.stdout.write("content")
+"""
+    )
+    fake_ptex.chmod(0o755)
+    monkeypatch.delenv("SCIE_PANTS_DOWNLOAD_RETRIES", raising=False)
+
+    out = tmp_path / "out"
+    with out.open("wb") as fp:
+        fp.write(b"prefix:")
+        fp.flush()
+        Ptex(str(fake_ptex)).fetch_to_fp("https://example.com/pants.pex", fp)
+    assert "prefix:content" == out.read_text()
+    assert "3" == counter.read_text()
+
+    counter.write_text("2")
+    with pytest.raises(CalledProcessError):
+        Ptex(str(fake_ptex)).fetch_text("https://example.com/missing")
+    assert "3" == counter.read_text()
+
+
+def test_proxy_for(monkeypatch: pytest.MonkeyPatch) -> None:
+    for name in "https_proxy", "HTTPS_PROXY", "http_proxy", "HTTP_PROXY", "no_proxy", "NO_PROXY":
+        monkeypatch.delenv(name, raising=False)
+    assert proxy_for("https://github.com/pantsbuild/pants") is None
+
+    monkeypatch.setenv("HTTPS_PROXY", "http://proxy.example:3128")
+    assert "http://proxy.example:3128" == proxy_for("https://github.com/pantsbuild/pants")
+    assert proxy_for("http://github.com/pantsbuild/pants") is None
+
+    monkeypatch.setenv("NO_PROXY", "github.com")
+    assert proxy_for("https://github.com/pantsbuild/pants") is None
+    assert "http://proxy.example:3128" == proxy_for("https://pypi.org/simple/pantsbuild-pants")
+
+
+def test_offline(monkeypatch: pytest.MonkeyPatch, tmp_path: Path) -> None:
+    monkeypatch.setenv("SCIE_PANTS_OFFLINE", "1")
+    url = "https://example.com/pants.2.18.0-cp39-linux_x86_64.pex"
+    with pytest.raises(SystemExit, match=f"refusing to download {url}"):
+        Ptex("/does/not/exist").fetch_text(url)
+    # Local mirrors remain usable.
+    ptex.check_online((tmp_path / "mirror").as_uri())
@@ -0,0 +1,27 @@
+# Copyright 2024 Pants project contributors.
+# Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+from __future__ import annotations
+
+from packaging.version import Version
+
+from scie_pants.update_scie_pants import release_version
+
+
+def test_stable_channel() -> None:
+    assert Version("0.12.2") == release_version({"tag_name": "v0.12.2"})
+    assert release_version({"tag_name": "v0.13.0rc1", "prerelease": True}) is None
+    assert release_version({"tag_name": "v0.13.0rc1"}) is None
+    assert release_version({"tag_name": "v0.12.3", "draft": True}) is None
+
+
+def test_edge_channel() -> None:
+    assert Version("0.12.2") == release_version({"tag_name": "v0.12.2"}, channel="edge")
+    assert Version("0.13.0rc1") == release_version(
+        {"tag_name": "v0.13.0rc1", "prerelease": True}, channel="edge"
+    )
+    assert Version("0.13.0rc1") == release_version(
+        {"tag_name": "v0.13.0-rc.1", "prerelease": True}, channel="edge"
+    )
+    assert release_version({"tag_name": "v0.12.3", "draft": True}, channel="edge") is None
+    assert release_version({"tag_name": "nightly"}, channel="edge") is None